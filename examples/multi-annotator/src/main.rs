@@ -83,15 +83,8 @@ fn main() {
                 {
                     let fragments =
                         peptide.generate_theoretical_fragments(Charge::new::<e>(z), &model);
-                    let annotated = spectrum.annotate(
-                        peptide,
-                        &fragments,
-                        &selected_model,
-                        MassMode::Monoisotopic,
-                    );
-                    let scores: &Scores = &annotated
-                        .scores(&fragments, &selected_model, MassMode::Monoisotopic)
-                        .1[0][0];
+                    let annotated = spectrum.annotate(peptide, &fragments, &selected_model);
+                    let scores: &Scores = &annotated.scores(&fragments, &selected_model).1[0][0];
 
                     let mut row: HashMap<_, _> = line.into();
 
@@ -644,6 +644,7 @@ impl AmbiguousModification {
             localisation_score: localisation_score.map(OrderedFloat),
             group,
             preferred,
+            info_tags: Vec::new(),
         })
     }
 
@@ -765,7 +766,7 @@ impl Fragment {
         FragmentType(self.0.ion.clone())
     }
 
-    /// The peptide this fragment comes from, saved as the index into the list of peptides in the overarching crate::ComplexPeptide struct.
+    /// The peptide this fragment comes from, saved as the index into the list of peptides in the overarching rustyms::Peptidoform struct.
     ///
     /// Returns
     /// -------
@@ -1525,7 +1526,11 @@ impl RawSpectrum {
         model: &FragmentationModel,
         mode: &MassMode,
     ) -> PyResult<AnnotatedSpectrum> {
-        let rusty_model = match_model(model)?;
+        let rusty_model = match_model(model)?.mass_mode(match mode {
+            MassMode::Monoisotopic => rustyms::MassMode::Monoisotopic,
+            MassMode::Average => rustyms::MassMode::Average,
+            MassMode::MostAbundant => rustyms::MassMode::MostAbundant,
+        });
         let fragments = peptide.0.generate_theoretical_fragments(
             self.0
                 .charge
@@ -1536,11 +1541,6 @@ impl RawSpectrum {
             peptide.0,
             &fragments,
             &rusty_model,
-            match mode {
-                MassMode::Monoisotopic => rustyms::MassMode::Monoisotopic,
-                MassMode::Average => rustyms::MassMode::Average,
-                MassMode::MostAbundant => rustyms::MassMode::MostAbundant,
-            },
         )))
     }
 }
@@ -3,8 +3,8 @@ use std::hint::black_box;
 use rustyms::align::*;
 use rustyms::system::dalton;
 use rustyms::system::Mass;
-use rustyms::SimpleLinear;
 use rustyms::*;
+use rustyms::{Linear, SimpleLinear};
 
 use iai_callgrind::{
     library_benchmark, library_benchmark_group, main, LibraryBenchmarkConfig, Tool, ValgrindTool,
@@ -79,7 +79,29 @@ pub fn align_unbounded(setup: (LinearPeptide<SimpleLinear>, LinearPeptide<Simple
     );
 }
 
+#[inline(never)]
+fn setup_proteome() -> Vec<LinearPeptide<Linear>> {
+    let _force_elements_init = black_box(AminoAcid::Alanine.formulas());
+    let (a, b) = setup_igha();
+    (0..50)
+        .flat_map(|_| [a.clone().cast(), b.clone().cast()])
+        .collect()
+}
+
+#[library_benchmark]
+#[bench::igha_100(setup_proteome())]
+pub fn digest(proteins: Vec<LinearPeptide<Linear>>) {
+    digest_proteome(
+        &proteins,
+        &Protease::c_terminal_of(&[AminoAcid::Lysine, AminoAcid::Arginine]),
+        2,
+        7..=40,
+        ..,
+    );
+}
+
 library_benchmark_group!(name = alignment; benchmarks = align_1, align_4, align_unbounded);
+library_benchmark_group!(name = digestion; benchmarks = digest);
 
 main!(config = LibraryBenchmarkConfig::default()
-.tool(Tool::new(ValgrindTool::DHAT)).tool(Tool::new(ValgrindTool::Massif)); library_benchmark_groups = alignment);
+.tool(Tool::new(ValgrindTool::DHAT)).tool(Tool::new(ValgrindTool::Massif)); library_benchmark_groups = alignment, digestion);
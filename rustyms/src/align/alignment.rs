@@ -174,38 +174,35 @@ impl<'lifetime, A, B> Alignment<'lifetime, A, B> {
 
     /// Returns statistics for this match.
     pub fn stats(&self) -> Stats {
-        let (identical, mass_similar, similar, gaps, length) =
-            self.path().iter().fold((0, 0, 0, 0, 0), |acc, p| {
-                let m = p.match_type;
-                (
-                    acc.0
-                        + usize::from(
-                            m == MatchType::IdentityMassMismatch || m == MatchType::FullIdentity,
-                        ) * p.step_a.max(p.step_b) as usize,
-                    acc.1
-                        + usize::from(
-                            m == MatchType::FullIdentity
-                                || m == MatchType::Isobaric
-                                || m == MatchType::Rotation,
-                        ) * p.step_a.max(p.step_b) as usize,
-                    acc.2
-                        + usize::from(
-                            (m == MatchType::IdentityMassMismatch
-                                || m == MatchType::FullIdentity
-                                || m == MatchType::Mismatch)
-                                && p.local_score >= 0,
-                        ) * p.step_a.max(p.step_b) as usize,
-                    acc.3 + usize::from(m == MatchType::Gap),
-                    acc.4 + p.step_a.max(p.step_b) as usize,
-                )
-            });
-        Stats {
-            identical,
-            mass_similar,
-            similar,
-            gaps,
-            length,
+        let mut stats = Stats::default();
+        for piece in self.path() {
+            let m = piece.match_type;
+            let step = piece.step_a.max(piece.step_b) as usize;
+            stats.identical +=
+                usize::from(m == MatchType::IdentityMassMismatch || m == MatchType::FullIdentity)
+                    * step;
+            stats.mass_similar += usize::from(
+                m == MatchType::FullIdentity
+                    || m == MatchType::Isobaric
+                    || m == MatchType::Rotation,
+            ) * step;
+            stats.similar += usize::from(
+                (m == MatchType::IdentityMassMismatch
+                    || m == MatchType::FullIdentity
+                    || m == MatchType::Mismatch)
+                    && piece.local_score >= 0,
+            ) * step;
+            stats.mass_mismatches += usize::from(m == MatchType::IdentityMassMismatch) * step;
+            stats.isobaric += usize::from(m == MatchType::Isobaric) * step;
+            stats.rotations += usize::from(m == MatchType::Rotation) * step;
+            if m == MatchType::Gap {
+                stats.gaps += 1;
+                stats.gap_length_a += piece.step_a as usize;
+                stats.gap_length_b += piece.step_b as usize;
+            }
+            stats.length += step;
         }
+        stats
     }
 }
 
@@ -352,7 +349,7 @@ impl<'lifetime, A: AtMax<Linear>, B: AtMax<Linear>> Alignment<'lifetime, A, B> {
 }
 
 /// Statistics for an alignment with some helper functions to easily retrieve the number of interest.
-#[derive(Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default, Serialize, Deserialize)]
 pub struct Stats {
     /// The total number of identical positions
     pub identical: usize,
@@ -360,8 +357,23 @@ pub struct Stats {
     pub mass_similar: usize,
     /// The total number of similar positions, where the scoring matrix scores above 0, and not isobaric
     pub similar: usize,
+    /// The total number of identical positions with a different mass (eg a different modification)
+    #[serde(default)]
+    pub mass_mismatches: usize,
+    /// The total number of positions in isobaric sets (sets of amino acids with the same mass but a different sequence)
+    #[serde(default)]
+    pub isobaric: usize,
+    /// The total number of positions in rotated sets (sets of amino acids in a different order)
+    #[serde(default)]
+    pub rotations: usize,
     /// The total number of gap positions
     pub gaps: usize,
+    /// The total number of residues of the first sequence that are aligned to a gap
+    #[serde(default)]
+    pub gap_length_a: usize,
+    /// The total number of residues of the second sequence that are aligned to a gap
+    #[serde(default)]
+    pub gap_length_b: usize,
     /// The length of the alignment, the sum of the max of the step for A and B for each position.
     pub length: usize,
 }
@@ -369,22 +381,31 @@ pub struct Stats {
 impl Stats {
     /// Get the identity as fraction.
     pub fn identity(&self) -> f64 {
-        self.identical as f64 / self.length as f64
+        self.fraction(self.identical)
     }
 
     /// Get the mass similarity as fraction.
     pub fn mass_similarity(&self) -> f64 {
-        self.mass_similar as f64 / self.length as f64
+        self.fraction(self.mass_similar)
     }
 
     /// Get the similarity as fraction.
     pub fn similarity(&self) -> f64 {
-        self.similar as f64 / self.length as f64
+        self.fraction(self.similar)
     }
 
     /// Get the gaps as fraction.
     pub fn gaps_fraction(&self) -> f64 {
-        self.gaps as f64 / self.length as f64
+        self.fraction(self.gaps)
+    }
+
+    /// Get the given number as fraction of the length, 0.0 for an empty alignment.
+    fn fraction(&self, value: usize) -> f64 {
+        if self.length == 0 {
+            0.0
+        } else {
+            value as f64 / self.length as f64
+        }
     }
 }
 
@@ -404,7 +425,7 @@ pub struct Score {
 #[allow(clippy::missing_panics_doc)]
 mod tests {
     use crate::{
-        align::{align, matrix::BLOSUM62, AlignType},
+        align::{align, matrix::BLOSUM62, AlignType, Stats},
         peptide::SimpleLinear,
         system::da,
         AminoAcid, LinearPeptide, MultiChemical,
@@ -489,4 +510,35 @@ mod tests {
             "{mass_diff_bc} (peptides) should be equal to {mass_diff_nd} (ND)"
         );
     }
+
+    #[test]
+    fn stats() {
+        let a = LinearPeptide::pro_forma("ANGARSW", None)
+            .unwrap()
+            .into_simple_linear()
+            .unwrap();
+        let b = LinearPeptide::pro_forma("AGGQRS", None)
+            .unwrap()
+            .into_simple_linear()
+            .unwrap();
+        let alignment = align::<4, SimpleLinear, SimpleLinear>(
+            &a,
+            &b,
+            BLOSUM62,
+            crate::Tolerance::new_ppm(10.0),
+            AlignType::GLOBAL,
+        );
+        assert_eq!(alignment.short(), "1=1:2i2:1i2=1D");
+        let stats = alignment.stats();
+        assert_eq!(stats.identical, 3);
+        assert_eq!(stats.mass_mismatches, 0);
+        assert_eq!(stats.isobaric, 4);
+        assert_eq!(stats.rotations, 0);
+        assert_eq!(stats.gaps, 1);
+        assert_eq!(stats.gap_length_a, 1);
+        assert_eq!(stats.gap_length_b, 0);
+        assert_eq!(stats.length, 8);
+        assert!((stats.identity() - 3.0 / 8.0).abs() < f64::EPSILON);
+        assert!(Stats::default().identity().abs() < f64::EPSILON);
+    }
 }
@@ -18,21 +18,120 @@ use super::{
 /// The [`AlignType`] controls the alignment behaviour, global/local or anything in between.
 /// # Panics
 /// It panics when the length of `seq_a` or `seq_b` is bigger than [`isize::MAX`].
-#[allow(clippy::too_many_lines)]
 pub fn align<'lifetime, const STEPS: u16, A: AtMax<SimpleLinear>, B: AtMax<SimpleLinear>>(
     seq_a: &'lifetime LinearPeptide<A>,
     seq_b: &'lifetime LinearPeptide<B>,
     scoring_matrix: &[[i8; AminoAcid::TOTAL_NUMBER]; AminoAcid::TOTAL_NUMBER],
     tolerance: Tolerance<Mass>,
     align_type: AlignType,
+) -> Alignment<'lifetime, A, B> {
+    align_cached::<STEPS, A, B>(
+        seq_a,
+        &calculate_masses::<STEPS>(seq_a),
+        seq_b,
+        &calculate_masses::<STEPS>(seq_b),
+        scoring_matrix,
+        tolerance,
+        align_type,
+    )
+}
+
+/// Align one peptide against many other peptides. The masses for the query are only calculated
+/// once and reused for every reference. The resulting alignments are sorted on normalised score
+/// with the best alignment first. See [`align`] for more information on the parameters.
+/// # Panics
+/// It panics when the length of `query` or any of the `references` is bigger than [`isize::MAX`].
+pub fn align_one_to_many<
+    'lifetime,
+    const STEPS: u16,
+    A: AtMax<SimpleLinear>,
+    B: AtMax<SimpleLinear>,
+>(
+    query: &'lifetime LinearPeptide<A>,
+    references: &'lifetime [LinearPeptide<B>],
+    scoring_matrix: &[[i8; AminoAcid::TOTAL_NUMBER]; AminoAcid::TOTAL_NUMBER],
+    tolerance: Tolerance<Mass>,
+    align_type: AlignType,
+) -> Vec<Alignment<'lifetime, A, B>> {
+    let masses_query = calculate_masses::<STEPS>(query);
+    let mut alignments = references
+        .iter()
+        .map(|reference| {
+            align_cached::<STEPS, A, B>(
+                query,
+                &masses_query,
+                reference,
+                &calculate_masses::<STEPS>(reference),
+                scoring_matrix,
+                tolerance,
+                align_type,
+            )
+        })
+        .collect::<Vec<_>>();
+    alignments.sort_unstable_by(|a, b| b.cmp(a));
+    alignments
+}
+
+/// Only available if the feature `rayon` is turned on.
+/// Align one peptide against many other peptides in parallel. The masses for the query are only
+/// calculated once per rayon job and reused for every reference in that job. The resulting
+/// alignments are sorted on normalised score with the best alignment first. See [`align`] for more information on the parameters.
+/// # Panics
+/// It panics when the length of `query` or any of the `references` is bigger than [`isize::MAX`].
+#[cfg(feature = "rayon")]
+pub fn par_align_one_to_many<
+    'lifetime,
+    const STEPS: u16,
+    A: AtMax<SimpleLinear> + Send + Sync,
+    B: AtMax<SimpleLinear> + Send + Sync,
+>(
+    query: &'lifetime LinearPeptide<A>,
+    references: &'lifetime [LinearPeptide<B>],
+    scoring_matrix: &[[i8; AminoAcid::TOTAL_NUMBER]; AminoAcid::TOTAL_NUMBER],
+    tolerance: Tolerance<Mass>,
+    align_type: AlignType,
+) -> Vec<Alignment<'lifetime, A, B>> {
+    use rayon::prelude::*;
+
+    let mut alignments = references
+        .par_iter()
+        .map_init(
+            || calculate_masses::<STEPS>(query),
+            |masses_query, reference| {
+                align_cached::<STEPS, A, B>(
+                    query,
+                    masses_query,
+                    reference,
+                    &calculate_masses::<STEPS>(reference),
+                    scoring_matrix,
+                    tolerance,
+                    align_type,
+                )
+            },
+        )
+        .collect::<Vec<_>>();
+    alignments.par_sort_unstable_by(|a, b| b.cmp(a));
+    alignments
+}
+
+/// Do the actual alignment, with the masses for both sequences already calculated.
+/// # Panics
+/// It panics when the length of `seq_a` or `seq_b` is bigger than [`isize::MAX`].
+#[allow(clippy::too_many_lines)]
+fn align_cached<'lifetime, const STEPS: u16, A: AtMax<SimpleLinear>, B: AtMax<SimpleLinear>>(
+    seq_a: &'lifetime LinearPeptide<A>,
+    masses_a: &DiagonalArray<Multi<Mass>>,
+    seq_b: &'lifetime LinearPeptide<B>,
+    masses_b: &DiagonalArray<Multi<Mass>>,
+    scoring_matrix: &[[i8; AminoAcid::TOTAL_NUMBER]; AminoAcid::TOTAL_NUMBER],
+    tolerance: Tolerance<Mass>,
+    align_type: AlignType,
 ) -> Alignment<'lifetime, A, B> {
     assert!(isize::try_from(seq_a.len()).is_ok());
     assert!(isize::try_from(seq_b.len()).is_ok());
 
     let mut matrix = Matrix::new(seq_a.len(), seq_b.len());
     let mut global_highest = (0, 0, 0);
-    let masses_a: DiagonalArray<Multi<Mass>> = calculate_masses::<STEPS>(seq_a);
-    let masses_b: DiagonalArray<Multi<Mass>> = calculate_masses::<STEPS>(seq_b);
     let zero: Multi<Mass> = Multi::default();
 
     if align_type.left.global_a() {
@@ -201,7 +300,10 @@ fn score_pair<A: AtMax<SimpleLinear>, B: AtMax<SimpleLinear>>(
     score: isize,
     tolerance: Tolerance<Mass>,
 ) -> Piece {
-    match (a.0 == b.0, tolerance.within(a.1, b.1)) {
+    match (
+        a.0.aminoacid.aminoacid() == b.0.aminoacid.aminoacid(),
+        tolerance.within(a.1, b.1),
+    ) {
         (true, true) => {
             let local = alphabet[a.0.aminoacid.aminoacid() as usize]
                 [b.0.aminoacid.aminoacid() as usize] as isize;
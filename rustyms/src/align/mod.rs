@@ -42,7 +42,9 @@ pub use consecutive::*;
 
 pub use align_type::{AlignType, Side};
 pub use alignment::{Alignment, Score, Stats};
-pub use mass_alignment::align;
+#[cfg(feature = "rayon")]
+pub use mass_alignment::par_align_one_to_many;
+pub use mass_alignment::{align, align_one_to_many};
 pub use piece::Piece;
 pub use scoring::MatchType;
 
@@ -105,4 +107,40 @@ mod tests {
         let c = dbg!(align::<{ u16::MAX }>(&a, &b));
         assert_eq!(c.short(), "1=1:2i2:1i2=");
     }
+
+    #[test]
+    fn one_to_many() {
+        let a = linear("ANGARS");
+        let references = [linear("PLMKW"), linear("ANGARS"), linear("AGGQRS")];
+        let alignments = super::align_one_to_many::<4, SimpleLinear, SimpleLinear>(
+            &a,
+            &references,
+            super::matrix::BLOSUM62,
+            crate::Tolerance::new_ppm(10.0),
+            AlignType::GLOBAL,
+        );
+        assert_eq!(alignments.len(), 3);
+        assert_eq!(alignments[0].seq_b(), &references[1]);
+        assert_eq!(alignments[1].seq_b(), &references[2]);
+        assert_eq!(alignments[1], align::<4>(&a, &references[2]));
+        assert!(alignments
+            .windows(2)
+            .all(|w| w[0].normalised_score() >= w[1].normalised_score()));
+    }
+
+    #[test]
+    fn identity_mass_mismatch() {
+        let a = LinearPeptide::pro_forma("AS[Phospho]A", None)
+            .unwrap()
+            .into_simple_linear()
+            .unwrap();
+        let b = linear("ASA");
+        let c = align::<1>(&a, &b);
+        assert_eq!(
+            c.path()[1].match_type,
+            super::MatchType::IdentityMassMismatch
+        );
+        assert_eq!(c.stats().mass_mismatches, 1);
+        assert_eq!(c.stats().identical, 3);
+    }
 }
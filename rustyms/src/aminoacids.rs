@@ -327,6 +327,19 @@ impl AminoAcid {
                 charge_carriers,
                 ions.c.2,
             ));
+            if ions.radical.0 {
+                base_fragments.extend(Fragment::generate_all(
+                    &(self.formulas_inner(sequence_index, peptide_index)
+                        * (modifications + molecular_formula!(H 1 N 1))),
+                    peptidoform_index,
+                    peptide_index,
+                    &FragmentType::c·(n_pos),
+                    n_term,
+                    ions.c.1,
+                    charge_carriers,
+                    ions.c.2,
+                ));
+            }
         }
         if ions.d.0 && allow_terminal.0 {
             base_fragments.extend(Fragment::generate_all(
@@ -408,17 +421,19 @@ impl AminoAcid {
                 charge_carriers,
                 ions.z.2,
             ));
-            base_fragments.extend(Fragment::generate_all(
-                &(self.formulas_inner(sequence_index, peptide_index)
-                    * (modifications - molecular_formula!(H 1 N 1))),
-                peptidoform_index,
-                peptide_index,
-                &FragmentType::z·(c_pos),
-                c_term,
-                ions.z.1,
-                charge_carriers,
-                ions.z.2,
-            ));
+            if ions.radical.1 {
+                base_fragments.extend(Fragment::generate_all(
+                    &(self.formulas_inner(sequence_index, peptide_index)
+                        * (modifications - molecular_formula!(H 1 N 1))),
+                    peptidoform_index,
+                    peptide_index,
+                    &FragmentType::z·(c_pos),
+                    c_term,
+                    ions.z.1,
+                    charge_carriers,
+                    ions.z.2,
+                ));
+            }
         }
 
         if ions.immonium.0 && allow_terminal.0 && allow_terminal.1 {
@@ -437,6 +452,21 @@ impl AminoAcid {
         base_fragments
     }
 
+    /// Get the molecular formula of this amino acid as residue (so without the water that is lost
+    /// when forming the peptide bond). For example selenocysteine (U) is `C3H5NOSe` and pyrrolysine (O)
+    /// is `C12H19N3O2`. For B and Z this returns the formula of N and Q respectively, use
+    /// [`MultiChemical::formulas`] to get all options. X is defined to be an empty formula.
+    ///
+    /// The monoisotopic mass uses the most abundant isotope for all elements, so selenium is <sup>80</sup>Se.
+    /// Use a global isotope modification (eg `<78Se>`) to use a different selenium isotope.
+    pub fn formula(self) -> MolecularFormula {
+        match self {
+            Self::AmbiguousAsparagine => Self::Asparagine.formula(),
+            Self::AmbiguousGlutamine => Self::Glutamine.formula(),
+            aa => aa.formulas()[0].clone(),
+        }
+    }
+
     /// Get the single letter representation of the amino acid
     pub const fn char(self) -> char {
         match self {
@@ -606,6 +636,8 @@ mod tests {
             ('W', 186.07931, 186.2),
             ('Y', 163.06333, 163.2),
             ('V', 99.06841, 99.13),
+            ('U', 150.95364, 150.0489),
+            ('O', 237.14773, 237.3),
         ];
 
         for (aa, mono_mass, average_weight) in known {
@@ -627,6 +659,62 @@ mod tests {
         }
     }
 
+    /// Pinned residue masses for every amino acid, including all options for the ambiguous ones.
+    /// The average weights follow the IUPAC standard atomic weights as used by this crate.
+    #[test]
+    fn all_residue_masses() {
+        let known: &[(char, &[(f64, f64)])] = &[
+            ('A', &[(71.03711, 71.0779)]),
+            ('R', &[(156.10111, 156.1861)]),
+            ('N', &[(114.04293, 114.1028)]),
+            ('D', &[(115.02694, 115.0873)]),
+            ('C', &[(103.00919, 103.1454)]),
+            ('Q', &[(128.05858, 128.1293)]),
+            ('E', &[(129.04259, 129.1139)]),
+            ('G', &[(57.02146, 57.0514)]),
+            ('H', &[(137.05891, 137.1394)]),
+            ('I', &[(113.08406, 113.1576)]),
+            ('L', &[(113.08406, 113.1576)]),
+            ('K', &[(128.09496, 128.1724)]),
+            ('M', &[(131.04049, 131.1985)]),
+            ('F', &[(147.06841, 147.1734)]),
+            ('P', &[(97.05276, 97.1151)]),
+            ('S', &[(87.03203, 87.0773)]),
+            ('T', &[(101.04768, 101.1039)]),
+            ('W', &[(186.07931, 186.2095)]),
+            ('Y', &[(163.06333, 163.1728)]),
+            ('V', &[(99.06841, 99.1310)]),
+            ('B', &[(114.04293, 114.1028), (115.02694, 115.0873)]),
+            ('J', &[(113.08406, 113.1576)]),
+            ('Z', &[(128.05858, 128.1293), (129.04259, 129.1139)]),
+            ('U', &[(150.95364, 150.0489)]),
+            ('O', &[(237.14773, 237.2981)]),
+            ('X', &[(0.0, 0.0)]),
+        ];
+        assert_eq!(known.len(), AminoAcid::Unknown as usize + 1);
+
+        for (code, options) in known {
+            let aa = AminoAcid::try_from(*code).unwrap();
+            let mut masses = aa
+                .formulas()
+                .iter()
+                .map(|f| (f.monoisotopic_mass().value, f.average_weight().value))
+                .collect::<Vec<_>>();
+            masses.sort_unstable_by(|a, b| a.0.total_cmp(&b.0));
+            assert_eq!(masses.len(), options.len(), "{code}: {masses:?}");
+            for ((mono, weight), (mono_mass, average_weight)) in masses.iter().zip(options.iter()) {
+                assert!(
+                    (mono - mono_mass).abs() < 1e-5,
+                    "{code}: {mono} != {mono_mass}"
+                );
+                assert!(
+                    (weight - average_weight).abs() < 1e-3,
+                    "{code}: {weight} != {average_weight}"
+                );
+            }
+        }
+    }
+
     #[test]
     fn read_aa() {
         assert_eq!(
@@ -640,4 +728,92 @@ mod tests {
         assert_eq!(AminoAcid::try_from('c'), Ok(AminoAcid::Cysteine));
         assert_eq!(AminoAcid::try_from('🦀'), Err(()));
     }
+
+    #[test]
+    fn read_three_letter_codes() {
+        for (code, aa) in [
+            ("Ala", AminoAcid::Alanine),
+            ("Arg", AminoAcid::Arginine),
+            ("Asn", AminoAcid::Asparagine),
+            ("Asp", AminoAcid::AsparticAcid),
+            ("Cys", AminoAcid::Cysteine),
+            ("Gln", AminoAcid::Glutamine),
+            ("Glu", AminoAcid::GlutamicAcid),
+            ("Gly", AminoAcid::Glycine),
+            ("His", AminoAcid::Histidine),
+            ("Ile", AminoAcid::Isoleucine),
+            ("Leu", AminoAcid::Leucine),
+            ("Lys", AminoAcid::Lysine),
+            ("Met", AminoAcid::Methionine),
+            ("Phe", AminoAcid::Phenylalanine),
+            ("Pro", AminoAcid::Proline),
+            ("Ser", AminoAcid::Serine),
+            ("Thr", AminoAcid::Threonine),
+            ("Trp", AminoAcid::Tryptophan),
+            ("Tyr", AminoAcid::Tyrosine),
+            ("Val", AminoAcid::Valine),
+        ] {
+            assert_eq!(AminoAcid::from_three_letter(code), Some(aa));
+            assert_eq!(AminoAcid::try_from(code), Ok(aa));
+            assert_eq!(
+                AminoAcid::try_from(code.to_ascii_uppercase().as_str()),
+                Ok(aa)
+            );
+            assert_eq!(
+                AminoAcid::try_from(code.to_ascii_lowercase().as_str()),
+                Ok(aa)
+            );
+            assert_eq!(aa.code(), code);
+        }
+        // All amino acids can be read back from their code
+        for aa in AminoAcid::CANONICAL_AMINO_ACIDS
+            .iter()
+            .chain(AminoAcid::UNIQUE_MASS_AMINO_ACIDS)
+        {
+            assert_eq!(AminoAcid::from_three_letter(aa.code()), Some(*aa));
+        }
+        assert_eq!(
+            AminoAcid::try_from("Asx"),
+            Ok(AminoAcid::AmbiguousAsparagine)
+        );
+        assert_eq!(
+            AminoAcid::try_from("GLX"),
+            Ok(AminoAcid::AmbiguousGlutamine)
+        );
+        assert_eq!(AminoAcid::try_from("xaa"), Ok(AminoAcid::Unknown));
+        assert_eq!(AminoAcid::try_from("Xle"), Ok(AminoAcid::AmbiguousLeucine));
+        assert_eq!(AminoAcid::try_from("k"), Ok(AminoAcid::Lysine));
+        assert_eq!(AminoAcid::try_from("Al"), Err(()));
+        assert_eq!(AminoAcid::try_from("Alan"), Err(()));
+        assert_eq!(AminoAcid::try_from("Abc"), Err(()));
+        assert_eq!("Ser".parse::<AminoAcid>(), Ok(AminoAcid::Serine));
+    }
+
+    #[test]
+    fn formula() {
+        assert_eq!(
+            AminoAcid::Selenocysteine.formula(),
+            molecular_formula!(C 3 H 5 N 1 O 1 Se 1)
+        );
+        assert_eq!(
+            AminoAcid::Pyrrolysine.formula(),
+            molecular_formula!(C 12 H 19 N 3 O 2)
+        );
+        assert!(
+            (AminoAcid::Selenocysteine
+                .formula()
+                .monoisotopic_mass()
+                .value
+                - 150.953_636)
+                .abs()
+                < 1e-5
+        );
+        assert!(
+            (AminoAcid::Pyrrolysine.formula().monoisotopic_mass().value - 237.147_727).abs() < 1e-5
+        );
+        assert_eq!(
+            AminoAcid::AmbiguousAsparagine.formula(),
+            AminoAcid::Asparagine.formula()
+        );
+    }
 }
@@ -34,6 +34,9 @@ pub fn build_gnome_ontology(out_dir: &OsString, debug: bool) {
         .unwrap();
 }
 
+/// The monoisotopic mass of water (H2O)
+const WATER_MONOISOTOPIC_MASS: f64 = 18.010_564_683_7;
+
 fn find_mass(mods: &HashMap<String, GNOmeModification>, mut name: String) -> Option<f64> {
     let mut mass = None;
     while mass.is_none() {
@@ -71,6 +74,15 @@ fn parse_gnome(_debug: bool) -> HashMap<String, GNOmeModification> {
                 .map(|value| modification.topology = Some(value[17..].to_lowercase()))
         });
 
+        // property_value: GNO:00000202 "HexNAc(4)Hex(5)NeuAc(1)" xsd:string
+        modification.composition = obj.lines.get("property_value").and_then(|values| {
+            values
+                .iter()
+                .find(|value| value.starts_with("GNO:00000202"))
+                .and_then(|value| value.split('"').nth(1))
+                .and_then(parse_composition)
+        });
+
         mods.insert(modification.code_name.clone(), modification);
     }
 
@@ -112,22 +124,35 @@ fn parse_gnome_structures(_debug: bool) -> HashMap<String, GlycanStructure> {
     glycans
 }
 
+/// Parse a GNOme composition (eg `HexNAc(4)Hex(5)NeuAc(1)`), returns None if any of the
+/// monosaccharides is not known (eg the substituents `Sulpho` and `Phospho`)
+fn parse_composition(composition: &str) -> Option<Vec<(MonoSaccharide, isize)>> {
+    let composition = composition.to_ascii_lowercase().replace(['(', ')'], "");
+    crate::helper_functions::parse_named_counter(&composition, glycan_parse_list(), false)
+        .ok()
+        .filter(|c| !c.is_empty())
+}
+
 #[derive(Default, Clone, Debug)]
 struct GNOmeModification {
-    code_name: String,                  // id of current item
-    is_a: String,                       // id to prev in chain
-    topology: Option<String>,           // id of topology
-    mass: Option<f64>,                  // mass if defined
-    structure: Option<GlycanStructure>, // mass if defined
+    code_name: String,                                 // id of current item
+    is_a: String,                                      // id to prev in chain
+    topology: Option<String>,                          // id of topology
+    mass: Option<f64>,                                 // mass if defined
+    composition: Option<Vec<(MonoSaccharide, isize)>>, // composition if defined
+    structure: Option<GlycanStructure>,                // structure if defined
 }
 
 impl GNOmeModification {
     fn into_mod(self) -> SimpleModification {
         if let Some(structure) = self.structure {
             SimpleModification::Gno(GnoComposition::Structure(structure), self.code_name)
+        } else if let Some(composition) = self.composition {
+            SimpleModification::Gno(GnoComposition::Composition(composition), self.code_name)
         } else if let Some(mass) = self.mass {
+            // The GNOme mass is the mass of the free glycan, when attached a water is lost
             SimpleModification::Gno(
-                GnoComposition::Mass(crate::system::f64::da(mass).into()),
+                GnoComposition::Mass(crate::system::f64::da(mass - WATER_MONOISOTOPIC_MASS).into()),
                 self.code_name,
             )
         } else {
@@ -324,7 +324,7 @@ impl Chemical for CheckedAminoAcid<UnAmbiguous> {
             AminoAcid::Methionine => molecular_formula!(H 9 C 5 O 1 N 1 S 1),
             AminoAcid::Phenylalanine => molecular_formula!(H 9 C 9 O 1 N 1),
             AminoAcid::Proline => molecular_formula!(H 7 C 5 O 1 N 1),
-            AminoAcid::Pyrrolysine => molecular_formula!(H 19 C 11 O 2 N 3),
+            AminoAcid::Pyrrolysine => molecular_formula!(H 19 C 12 O 2 N 3),
             AminoAcid::Selenocysteine => molecular_formula!(H 5 C 3 O 1 N 1 Se 1),
             AminoAcid::Serine => molecular_formula!(H 5 C 3 O 2 N 1),
             AminoAcid::Threonine => molecular_formula!(H 7 C 4 O 2 N 1),
@@ -1,8 +1,8 @@
 use crate::{
-    system::{da, fraction, Mass, OrderedMass, Ratio},
-    MassMode,
+    system::{da, fraction, usize::Charge, Mass, MassOverCharge, OrderedMass, Ratio},
+    Element, IonMode, MassMode,
 };
-use std::fmt::Write;
+use std::{fmt::Write, num::NonZeroU16};
 
 #[macro_use]
 #[path = "shared/formula/mod.rs"]
@@ -71,6 +71,46 @@ impl MolecularFormula {
         }
     }
 
+    /// Get the number of times the given element occurs in this formula. With `isotope` set to
+    /// None only the element with its natural distribution is counted, otherwise only the given
+    /// isotope of the element is counted.
+    pub fn count(&self, element: Element, isotope: Option<NonZeroU16>) -> i32 {
+        self.elements()
+            .iter()
+            .find(|(e, i, _)| *e == element && *i == isotope)
+            .map_or(0, |(_, _, n)| *n)
+    }
+
+    /// Get the total number of atoms in this formula, summing all elements (and isotopes) that
+    /// occur a positive number of times. Electrons are not counted as atoms.
+    pub fn total_atoms(&self) -> i32 {
+        self.elements()
+            .iter()
+            .filter(|(e, _, n)| *e != Element::Electron && *n > 0)
+            .map(|(_, _, n)| *n)
+            .sum()
+    }
+
+    /// Get the monoisotopic m/z of this formula when charged by the given number of protons.
+    /// In [`IonMode::Positive`] the protons are added and in [`IonMode::Negative`] they are
+    /// removed, in both cases correcting for the mass of the electrons. Returns None if the charge
+    /// is zero.
+    pub fn mz(&self, charge: Charge, mode: IonMode) -> Option<MassOverCharge> {
+        if charge.value == 0 {
+            return None;
+        }
+        let z = i32::try_from(charge.value).ok()?;
+        let protons = molecular_formula!(H 1 Electron -1)
+            * match mode {
+                IonMode::Positive => z,
+                IonMode::Negative => -z,
+            };
+        Some(
+            (self + &protons).monoisotopic_mass()
+                / crate::system::f64::Charge::new::<crate::system::charge::e>(f64::from(z)),
+        )
+    }
+
     /// Create a [Hill notation](https://en.wikipedia.org/wiki/Chemical_formula#Hill_system) from this collections of elements merged with the ProForma notation for specific isotopes
     pub fn hill_notation(&self) -> String {
         self.hill_notation_generic(|element, buffer| {
@@ -190,9 +230,41 @@ impl std::fmt::Display for MolecularFormula {
 #[allow(clippy::missing_panics_doc)]
 mod tests {
     use crate::{
-        model::ChargeRange, molecular_formula, AminoAcid, Fragment, MolecularCharge,
-        MolecularFormula, MultiChemical,
+        model::ChargeRange, system::usize::Charge, AminoAcid, Element, Fragment, IonMode,
+        MolecularCharge, MolecularFormula, MultiChemical,
     };
+    use std::num::NonZeroU16;
+
+    #[test]
+    fn mz() {
+        let water = molecular_formula!(H 2 O 1);
+        let proton = molecular_formula!(H 1 Electron -1)
+            .monoisotopic_mass()
+            .value;
+        let mass = water.monoisotopic_mass().value;
+        let charge = |z| Charge::new::<crate::system::e>(z);
+        let single = water.mz(charge(1), IonMode::Positive).unwrap().value;
+        assert!((single - 19.017_841).abs() < 1e-6);
+        assert!((single - (mass + proton)).abs() < 1e-9);
+        let double = water.mz(charge(2), IonMode::Positive).unwrap().value;
+        assert!((double - (mass + 2.0 * proton) / 2.0).abs() < 1e-9);
+        let negative = water.mz(charge(1), IonMode::Negative).unwrap().value;
+        assert!((negative - 17.003_288).abs() < 1e-6);
+        assert!((negative - (mass - proton)).abs() < 1e-9);
+        assert!(water.mz(charge(0), IonMode::Positive).is_none());
+    }
+
+    #[test]
+    fn element_counts() {
+        let formula = molecular_formula!(C 6 H 12 [13 C 2] N 1 O -1 Electron 1);
+        assert_eq!(formula.count(Element::C, None), 6);
+        assert_eq!(formula.count(Element::C, NonZeroU16::new(13)), 2);
+        assert_eq!(formula.count(Element::O, None), -1);
+        assert_eq!(formula.count(Element::S, None), 0);
+        assert_eq!(formula.total_atoms(), 21);
+        assert!(formula.elements().contains(&(Element::H, None, 12)));
+        assert_eq!(MolecularFormula::default().total_atoms(), 0);
+    }
 
     #[test]
     fn sorted() {
@@ -268,6 +340,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn pro_forma_casing() {
+        assert_eq!(
+            MolecularFormula::from_pro_forma("CO", .., false, false),
+            Ok(molecular_formula!(C 1 O 1))
+        );
+        assert_eq!(
+            MolecularFormula::from_pro_forma("Co", .., false, false),
+            Ok(molecular_formula!(Co 1))
+        );
+        assert_eq!(
+            MolecularFormula::from_pro_forma("C O2", .., false, false),
+            Ok(molecular_formula!(C 1 O 2))
+        );
+        assert_eq!(
+            MolecularFormula::from_pro_forma("[59Co1]", .., false, false),
+            Ok(molecular_formula!([59 Co 1]))
+        );
+        assert!(MolecularFormula::from_pro_forma("co", .., false, false).is_err());
+        assert!(MolecularFormula::from_pro_forma("[13c1]", .., false, false).is_err());
+        assert!(MolecularFormula::from_pro_forma("[59CO1]", .., false, false).is_err());
+    }
+
     #[test]
     fn pro_forma_empty() {
         assert_eq!(
@@ -3,6 +3,7 @@
 use std::{
     borrow::Cow,
     fmt::{Debug, Display},
+    ops::RangeInclusive,
 };
 
 use itertools::Itertools;
@@ -13,11 +14,12 @@ use crate::{
     model::ChargeRange,
     molecular_charge::{CachedCharge, MolecularCharge},
     system::{
-        f64::{MassOverCharge, Ratio},
+        f64::{Mass, MassOverCharge, Ratio},
         usize::Charge,
     },
-    AmbiguousLabel, AminoAcid, Chemical, MassMode, Modification, MolecularFormula, Multi,
-    NeutralLoss, SequencePosition,
+    AmbiguousLabel, AminoAcid, Chemical, CompoundPeptidoform, LinearPeptide, Linked, MassMode,
+    Modification, MolecularFormula, Multi, NeutralLoss, SequencePosition, Tolerance,
+    WithinTolerance,
 };
 
 /// A theoretical fragment of a peptide
@@ -35,13 +37,37 @@ pub struct Fragment {
     pub peptide_index: usize,
     /// Any neutral losses applied
     pub neutral_loss: Option<NeutralLoss>,
+    /// The isotope peak this fragment represents, 0 for the monoisotopic peak, 1 for M+1, etc
+    #[serde(default)]
+    pub isotope: usize,
+    /// The charge carriers that are included in the formula
+    #[serde(default)]
+    pub(crate) charge_carriers: MolecularFormula,
 }
 
 impl Fragment {
+    /// The mass difference between two consecutive isotope peaks, the averaged spacing for peptides (in Da)
+    pub const ISOTOPE_SPACING: f64 = 1.002_35;
+
     /// Get the mz
     pub fn mz(&self, mode: MassMode) -> MassOverCharge {
-        self.formula.mass(mode)
-            / crate::system::f64::Charge::new::<crate::system::charge::e>(self.charge.value as f64)
+        self.formula.mass(mode) / self.charge.to_float()
+    }
+
+    /// Get the monoisotopic mass of this fragment, including the charge carriers
+    pub fn monoisotopic_mass(&self) -> Mass {
+        self.formula.monoisotopic_mass()
+    }
+
+    /// Get the average mass of this fragment, including the charge carriers
+    pub fn average_mass(&self) -> Mass {
+        self.formula.average_weight()
+    }
+
+    /// Get the neutral mass of this fragment, so without the charge carriers. Useful when comparing
+    /// against deconvoluted spectra.
+    pub fn neutral_mass(&self, mode: MassMode) -> Mass {
+        self.formula.mass(mode) - self.charge_carriers.mass(mode)
     }
 
     /// Get the ppm difference between two fragments
@@ -49,6 +75,142 @@ impl Fragment {
         self.mz(mode).ppm(other.mz(mode))
     }
 
+    /// Get the position of this fragment on the peptide (or None if it is not a peptide fragment, eg a precursor)
+    pub fn position(&self) -> Option<PeptidePosition> {
+        self.ion.position().copied()
+    }
+
+    /// Get the ion series of this fragment, eg `b` for a b3 fragment
+    pub const fn series(&self) -> FragmentKind {
+        self.ion.kind()
+    }
+
+    /// Check if this fragment is part of the given ion series, eg `b` for a b3 fragment
+    pub fn is_series(&self, series: FragmentKind) -> bool {
+        self.series() == series
+    }
+
+    /// Get the ordinal in the ion series of this fragment, eg `3` for a b3 fragment (or None if it is not a peptide fragment, eg a precursor)
+    pub fn ordinal(&self) -> Option<usize> {
+        self.ion.position().map(|p| p.series_number)
+    }
+
+    /// Get a label for this fragment with the ion, the neutral loss, and the isotope, eg `b3-H2O1`
+    /// or `b3+2i` for the M+2 isotope peak, intended for display. Precursor ions are labelled with
+    /// their charge carriers and charge, eg `[M+2H-H3O4P1]2+` for a doubly protonated precursor
+    /// that lost phosphoric acid.
+    pub fn label(&self) -> String {
+        let neutral_loss = self
+            .neutral_loss
+            .as_ref()
+            .map(std::string::ToString::to_string)
+            .unwrap_or_default();
+        if self.ion == FragmentType::precursor {
+            format!(
+                "[M{}{neutral_loss}]{}{}",
+                self.charge_carriers_label(),
+                self.charge_label(),
+                self.isotope_label()
+            )
+        } else {
+            format!("{}{neutral_loss}{}", self.ion, self.isotope_label())
+        }
+    }
+
+    /// The charge carriers of this fragment without the electrons, eg `+2H` or `+H+Na`
+    fn charge_carriers_label(&self) -> String {
+        self.charge_carriers
+            .elements()
+            .iter()
+            .filter(|(element, _, _)| *element != crate::Element::Electron)
+            .map(|(element, isotope, count)| {
+                let element = isotope.map_or_else(
+                    || element.to_string(),
+                    |isotope| format!("[{isotope}{element}]"),
+                );
+                match count {
+                    1 => format!("+{element}"),
+                    -1 => format!("-{element}"),
+                    n => format!("{n:+}{element}"),
+                }
+            })
+            .collect()
+    }
+
+    /// The charge of this fragment with the sign following the number, eg `2+`, or only the sign
+    /// for singly charged fragments
+    fn charge_label(&self) -> String {
+        let sign = if self.formula.charge().value < 0 {
+            '-'
+        } else {
+            '+'
+        };
+        match self.charge.value {
+            1 => sign.to_string(),
+            n => format!("{n}{sign}"),
+        }
+    }
+
+    /// The label for the isotope in mzPAF style, empty for the monoisotopic peak
+    fn isotope_label(&self) -> String {
+        match self.isotope {
+            0 => String::new(),
+            1 => "+i".to_string(),
+            n => format!("+{n}i"),
+        }
+    }
+
+    /// Get the complementary fragment for a backbone fragment, the fragment that together with
+    /// this fragment makes up the full precursor (b↔y, c↔z, c·↔z·, a↔x). The precursor should be
+    /// given as neutral formula, the complement gets the same charge carriers as this fragment.
+    /// Returns None for fragments that do not have a complement (eg precursor, immonium,
+    /// diagnostic, or satellite ions), fragments spanning the whole peptide, and fragments with
+    /// a neutral loss, as the loss could have been on either side of the cleaved bond.
+    pub fn complement(&self, precursor: &MolecularFormula) -> Option<Self> {
+        if self.neutral_loss.is_some() {
+            return None;
+        }
+        let n_position = |position: &PeptidePosition| match position.sequence_index {
+            SequencePosition::Index(i) if i > 0 => Some(PeptidePosition::n(
+                SequencePosition::Index(i - 1),
+                position.sequence_length,
+            )),
+            _ => None,
+        };
+        let c_position = |position: &PeptidePosition| match position.sequence_index {
+            SequencePosition::Index(i) if i + 1 < position.sequence_length => Some(
+                PeptidePosition::c(SequencePosition::Index(i + 1), position.sequence_length),
+            ),
+            _ => None,
+        };
+        // The a and x ions together miss two hydrogens compared to the precursor
+        let (ion, shift) = match &self.ion {
+            FragmentType::a(p) => (FragmentType::x(c_position(p)?), molecular_formula!(H 2)),
+            FragmentType::b(p) => (FragmentType::y(c_position(p)?), MolecularFormula::default()),
+            FragmentType::c(p) => (FragmentType::z(c_position(p)?), MolecularFormula::default()),
+            FragmentType::c·(p) => (
+                FragmentType::z·(c_position(p)?),
+                MolecularFormula::default(),
+            ),
+            FragmentType::x(p) => (FragmentType::a(n_position(p)?), molecular_formula!(H 2)),
+            FragmentType::y(p) => (FragmentType::b(n_position(p)?), MolecularFormula::default()),
+            FragmentType::z(p) => (FragmentType::c(n_position(p)?), MolecularFormula::default()),
+            FragmentType::z·(p) => (
+                FragmentType::c·(n_position(p)?),
+                MolecularFormula::default(),
+            ),
+            _ => return None,
+        };
+        let charge_carriers = &self.charge_carriers;
+        Some(Self {
+            formula: (precursor + charge_carriers + charge_carriers - &self.formula - shift)
+                .without_labels(),
+            ion,
+            isotope: 0,
+            ..self.clone()
+        })
+    }
+
     /// Create a new fragment
     #[must_use]
     pub fn new(
@@ -65,12 +227,12 @@ impl Fragment {
             peptidoform_index,
             peptide_index,
             neutral_loss: None,
+            isotope: 0,
+            charge_carriers: MolecularFormula::default(),
         }
     }
 
     /// Generate a list of possible fragments from the list of possible preceding termini and neutral losses
-    /// # Panics
-    /// When the charge range results in a negative charge
     #[allow(clippy::too_many_arguments)]
     #[must_use]
     pub fn generate_all(
@@ -93,29 +255,29 @@ impl Fragment {
                     + mass
                     + charge.formula_inner(SequencePosition::default(), peptide_index)
                     + loss.unwrap_or(&NeutralLoss::Gain(MolecularFormula::default())),
-                charge: Charge::new::<crate::system::e>(charge.charge().value.try_into().unwrap()),
+                charge: Charge::new::<crate::system::e>(charge.charge().value.unsigned_abs()),
                 ion: annotation.clone(),
                 peptidoform_index,
                 peptide_index,
                 neutral_loss: loss.cloned(),
+                isotope: 0,
+                charge_carriers: charge.formula(),
             })
             .collect()
     }
 
     /// Create a copy of this fragment with the given charge
-    /// # Panics
-    /// If the charge is negative.
     #[must_use]
     fn with_charge(&self, charge: &MolecularCharge) -> Self {
         let formula = charge
             .formula()
             .with_labels(&[AmbiguousLabel::ChargeCarrier(charge.formula())]);
-        let c = Charge::new::<crate::system::charge::e>(
-            usize::try_from(formula.charge().value).unwrap(),
-        );
+        // The charge is stored as magnitude, so negative ions have a positive m/z as well
+        let c = Charge::new::<crate::system::charge::e>(formula.charge().value.unsigned_abs());
         Self {
             formula: &self.formula + &formula,
             charge: c,
+            charge_carriers: charge.formula(),
             ..self.clone()
         }
     }
@@ -142,6 +304,20 @@ impl Fragment {
         }
     }
 
+    /// Create a copy of this fragment as the given isotope peak, so with `isotope` times the
+    /// [isotope spacing](Self::ISOTOPE_SPACING) added to the mass. The isotope is counted from the
+    /// monoisotopic peak, so calling this on a fragment that is already an isotope peak sets the isotope.
+    #[must_use]
+    pub fn with_isotope(&self, isotope: usize) -> Self {
+        let mut formula = self.formula.clone();
+        formula.add_mass(((isotope as f64 - self.isotope as f64) * Self::ISOTOPE_SPACING).into());
+        Self {
+            formula,
+            isotope,
+            ..self.clone()
+        }
+    }
+
     /// Create copies of this fragment with the given neutral losses (and a copy of this fragment itself)
     #[must_use]
     pub fn with_neutral_losses(&self, neutral_losses: &[NeutralLoss]) -> Vec<Self> {
@@ -156,22 +332,156 @@ impl Fragment {
     }
 }
 
+/// Filter the given fragments to only retain the fragments from any of the given ion series with a
+/// charge within the given range.
+///
+/// For example to retain only singly charged b and y ions use
+/// `filter_fragments(&fragments, &[FragmentKind::b, FragmentKind::y], 1..=1)`.
+pub fn filter_fragments<'a>(
+    fragments: impl IntoIterator<Item = &'a Fragment>,
+    series: &'a [FragmentKind],
+    charges: RangeInclusive<usize>,
+) -> impl Iterator<Item = &'a Fragment> {
+    fragments
+        .into_iter()
+        .filter(move |f| series.contains(&f.series()) && charges.contains(&f.charge.value))
+}
+
+/// Merge the given fragments that have the same m/z, within the given tolerance.
+///
+/// Different fragmentation paths can result in fragments with the same m/z, for example a y ion
+/// with a water loss and a b ion of the same composition. Each returned group contains all
+/// fragments for one m/z, so all labels are retained, similar to how [`crate::spectrum::AnnotatedPeak`]
+/// stores all annotations for a single peak. Identical fragments are only retained once. A group
+/// is started at the lowest m/z and contains all fragments within the tolerance of that first
+/// fragment. The groups are sorted on m/z.
+pub fn dedup_fragments(
+    fragments: Vec<Fragment>,
+    tolerance: Tolerance<MassOverCharge>,
+    mode: MassMode,
+) -> Vec<Vec<Fragment>> {
+    let mut groups: Vec<(MassOverCharge, Vec<Fragment>)> = Vec::new();
+    for (mz, fragment) in fragments
+        .into_iter()
+        .map(|f| (f.mz(mode), f))
+        .sorted_by(|a, b| a.0.value.total_cmp(&b.0.value))
+    {
+        match groups.last_mut() {
+            Some((start, group)) if tolerance.within(start, &mz) => {
+                if !group.contains(&fragment) {
+                    group.push(fragment);
+                }
+            }
+            _ => groups.push((mz, vec![fragment])),
+        }
+    }
+    groups.into_iter().map(|(_, group)| group).collect()
+}
+
 impl Display for Fragment {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{}@{}{:+}{}",
+            "{}@{}{:+}{}{}",
             self.ion,
             self.mz(MassMode::Monoisotopic).value,
             self.charge.value,
             self.neutral_loss
                 .as_ref()
                 .map(std::string::ToString::to_string)
-                .unwrap_or_default()
+                .unwrap_or_default(),
+            self.isotope_label()
         )
     }
 }
 
+/// Get a peak list for the given fragments for use in external tools, eg for plotting a
+/// theoretical spectrum.
+///
+/// Each peak is given as the monoisotopic m/z, the relative intensity as given by the intensity
+/// model, and the label of the fragment (see [`Fragment::label`]). The `peptide` is the compound
+/// peptidoform the fragments were generated from, the intensity model gets the peptide of each
+/// fragment. Use [`UniformIntensity`] to give all fragments the same intensity. The peaks are
+/// sorted on m/z.
+///
+/// # Panics
+/// If a fragment does not refer to a peptide in the given compound peptidoform.
+pub fn fragments_to_peak_list(
+    fragments: &[Fragment],
+    peptide: &CompoundPeptidoform,
+    intensity_model: &impl IntensityModel,
+) -> Vec<(MassOverCharge, f64, String)> {
+    fragments
+        .iter()
+        .map(|fragment| {
+            (
+                fragment.mz(MassMode::Monoisotopic),
+                intensity_model.intensity(
+                    fragment,
+                    &peptide.peptidoforms()[fragment.peptidoform_index].peptides()
+                        [fragment.peptide_index],
+                ),
+                fragment.label(),
+            )
+        })
+        .sorted_by(|a, b| a.0.value.total_cmp(&b.0.value))
+        .collect()
+}
+
+/// A model to predict the relative intensity of theoretical fragments.
+///
+/// Used to build peak lists (see [`fragments_to_peak_list`]) and synthetic spectra (see
+/// [`crate::RawSpectrum::from_fragments`]). The model gets the peptide the fragment was generated
+/// from, so it can use the sequence context, for example to integrate a learned predictor. Any
+/// `Fn(&Fragment, &LinearPeptide<Linked>) -> f64` is an intensity model, so a closure can be used
+/// for quick custom models (the argument types have to be annotated, eg
+/// `|f: &Fragment, p: &LinearPeptide<Linked>| 1.0`).
+pub trait IntensityModel {
+    /// Predict the relative intensity of the given fragment of the given peptide.
+    fn intensity(&self, fragment: &Fragment, peptide: &LinearPeptide<Linked>) -> f64;
+}
+
+impl<F: Fn(&Fragment, &LinearPeptide<Linked>) -> f64> IntensityModel for F {
+    fn intensity(&self, fragment: &Fragment, peptide: &LinearPeptide<Linked>) -> f64 {
+        self(fragment, peptide)
+    }
+}
+
+/// An intensity model that gives all fragments an intensity of 1.0.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct UniformIntensity;
+
+impl IntensityModel for UniformIntensity {
+    fn intensity(&self, _fragment: &Fragment, _peptide: &LinearPeptide<Linked>) -> f64 {
+        1.0
+    }
+}
+
+/// An intensity model based on the position of the cleavage in the peptide.
+///
+/// Backbone fragments from cleavages in the middle of the peptide are preferred over those close
+/// to the termini, the intensity scales linearly from 0.5 for a cleavage next to a terminus to
+/// 1.0 for a cleavage in the middle (for odd lengths both central cleavages get 1.0). Peptides
+/// too short to have a middle give 1.0 for all cleavages. All other fragments get an intensity
+/// of 1.0.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PositionIntensity;
+
+impl IntensityModel for PositionIntensity {
+    fn intensity(&self, fragment: &Fragment, peptide: &LinearPeptide<Linked>) -> f64 {
+        fragment.position().map_or(1.0, |position| {
+            let half = peptide.len() / 2;
+            if half <= 1 {
+                return 1.0;
+            }
+            let distance = position
+                .series_number
+                .min(peptide.len().saturating_sub(position.series_number));
+            0.5 + 0.5 * distance.saturating_sub(1) as f64 / (half - 1) as f64
+        })
+    }
+}
+
 // /// An isotope annotation.
 // #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 // pub struct MatchedIsotopeDistribution {
@@ -222,6 +532,24 @@ impl PeptidePosition {
             sequence_length: length,
         }
     }
+    /// The number of residues between this position and the N terminus, the N terminal
+    /// modification and the first residue both are at distance 0
+    pub const fn distance_from_n(&self) -> usize {
+        match self.sequence_index {
+            SequencePosition::NTerm => 0,
+            SequencePosition::Index(i) => i,
+            SequencePosition::CTerm => self.sequence_length.saturating_sub(1),
+        }
+    }
+    /// The number of residues between this position and the C terminus, the C terminal
+    /// modification and the last residue both are at distance 0
+    pub const fn distance_from_c(&self) -> usize {
+        match self.sequence_index {
+            SequencePosition::NTerm => self.sequence_length.saturating_sub(1),
+            SequencePosition::Index(i) => self.sequence_length.saturating_sub(i + 1),
+            SequencePosition::CTerm => 0,
+        }
+    }
     /// Check if this position is on the N terminus
     pub fn is_n_terminal(&self) -> bool {
         self.sequence_index == SequencePosition::NTerm
@@ -317,6 +645,8 @@ pub enum FragmentType {
     b(PeptidePosition),
     /// c
     c(PeptidePosition),
+    /// c· (c-1, the radical c ion, one hydrogen less than c)
+    c·(PeptidePosition),
     /// d
     d(PeptidePosition),
     /// v
@@ -329,7 +659,7 @@ pub enum FragmentType {
     y(PeptidePosition),
     /// z
     z(PeptidePosition),
-    /// z·
+    /// z· (z+1, the radical z ion, one hydrogen more than z)
     z·(PeptidePosition),
     // glycan A fragment (Never generated)
     //A(GlycanPosition),
@@ -367,6 +697,7 @@ impl FragmentType {
             Self::a(n)
             | Self::b(n)
             | Self::c(n)
+            | Self::c·(n)
             | Self::d(n)
             | Self::v(n)
             | Self::w(n)
@@ -395,6 +726,7 @@ impl FragmentType {
             Self::a(n)
             | Self::b(n)
             | Self::c(n)
+            | Self::c·(n)
             | Self::d(n)
             | Self::v(n)
             | Self::w(n)
@@ -432,6 +764,7 @@ impl FragmentType {
             Self::a(_) => Cow::Borrowed("a"),
             Self::b(_) => Cow::Borrowed("b"),
             Self::c(_) => Cow::Borrowed("c"),
+            Self::c·(_) => Cow::Borrowed("c·"),
             Self::d(_) => Cow::Borrowed("d"),
             Self::v(_) => Cow::Borrowed("v"),
             Self::w(_) => Cow::Borrowed("w"),
@@ -461,7 +794,7 @@ impl FragmentType {
         match self {
             Self::a(_) => FragmentKind::a,
             Self::b(_) => FragmentKind::b,
-            Self::c(_) => FragmentKind::c,
+            Self::c(_) | Self::c·(_) => FragmentKind::c,
             Self::d(_) => FragmentKind::d,
             Self::v(_) => FragmentKind::v,
             Self::w(_) => FragmentKind::w,
@@ -502,7 +835,7 @@ pub enum FragmentKind {
     a,
     /// b
     b,
-    /// c
+    /// c and c·
     c,
     /// d
     d,
@@ -592,7 +925,7 @@ impl std::fmt::Display for GlycanBreakPos {
 }
 
 #[cfg(test)]
-#[allow(clippy::missing_panics_doc)]
+#[allow(clippy::float_cmp, clippy::missing_panics_doc)]
 mod tests {
 
     use crate::{AminoAcid, MultiChemical};
@@ -614,6 +947,221 @@ mod tests {
         assert_eq!(a.formula, &loss[1].formula + &molecular_formula!(H 2 O 1));
     }
 
+    #[test]
+    fn dedup() {
+        // y2-H2O (GA) has the same composition as b2 (AG)
+        let peptide = crate::LinearPeptide::pro_forma("AGGA", None)
+            .unwrap()
+            .into_linear()
+            .unwrap();
+        let model = crate::Model::none()
+            .b(crate::model::PrimaryIonSeries::default())
+            .y(crate::model::PrimaryIonSeries::default()
+                .neutral_losses(vec![NeutralLoss::Loss(molecular_formula!(H 2 O 1))]));
+        let fragments = peptide
+            .generate_theoretical_fragments(Charge::new::<crate::system::charge::e>(1), &model);
+        let mut doubled = fragments.clone();
+        doubled.extend(fragments.iter().cloned());
+        let groups = dedup_fragments(doubled, Tolerance::new_ppm(10.0), MassMode::Monoisotopic);
+        assert_eq!(
+            groups.iter().map(Vec::len).sum::<usize>(),
+            fragments.len(),
+            "Identical fragments are merged"
+        );
+        let shared = groups
+            .iter()
+            .find(|g| g.iter().any(|f| f.ion.to_string() == "b2"))
+            .unwrap();
+        assert_eq!(
+            shared
+                .iter()
+                .map(|f| f.ion.to_string())
+                .sorted()
+                .collect_vec(),
+            ["b2", "y2"],
+        );
+        assert!(shared[0].neutral_loss.is_some() || shared[1].neutral_loss.is_some());
+        assert!(groups
+            .windows(2)
+            .all(|w| w[0][0].mz(MassMode::Monoisotopic) < w[1][0].mz(MassMode::Monoisotopic)));
+        assert!(
+            dedup_fragments(Vec::new(), Tolerance::new_ppm(10.0), MassMode::Monoisotopic)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn intensity_models() {
+        /// Prefer y ions over all other fragments
+        struct YPreference(f64);
+        impl IntensityModel for YPreference {
+            fn intensity(&self, fragment: &Fragment, _peptide: &LinearPeptide<Linked>) -> f64 {
+                if fragment.is_series(FragmentKind::y) {
+                    self.0
+                } else {
+                    1.0
+                }
+            }
+        }
+
+        let peptide = crate::LinearPeptide::pro_forma("PEPTIDEK", None)
+            .unwrap()
+            .into_linear()
+            .unwrap();
+        let fragments = peptide.generate_theoretical_fragments(
+            Charge::new::<crate::system::charge::e>(1),
+            &crate::Model::none().only_b_y(),
+        );
+        let compound = CompoundPeptidoform::from(peptide);
+        let peaks = fragments_to_peak_list(&fragments, &compound, &YPreference(3.0));
+        assert_eq!(peaks.len(), fragments.len());
+        for (_, intensity, label) in &peaks {
+            assert_eq!(*intensity, if label.starts_with('y') { 3.0 } else { 1.0 });
+        }
+        let spectrum = crate::RawSpectrum::from_fragments(&fragments, &compound, &YPreference(2.0));
+        assert_eq!(
+            crate::spectrum::PeakSpectrum::spectrum(&spectrum)
+                .map(|p| *p.intensity)
+                .max_by(f64::total_cmp),
+            Some(2.0)
+        );
+        assert!(
+            fragments_to_peak_list(&fragments, &compound, &UniformIntensity)
+                .iter()
+                .all(|(_, intensity, _)| *intensity == 1.0)
+        );
+        // Models get the peptide the fragment was generated from
+        assert!(fragments_to_peak_list(
+            &fragments,
+            &compound,
+            &|_: &Fragment, p: &LinearPeptide<Linked>| p.len() as f64
+        )
+        .iter()
+        .all(|(_, intensity, _)| *intensity == 8.0));
+
+        let linked = compound.peptides().next().unwrap();
+        let position = |label: &str| {
+            PositionIntensity.intensity(
+                fragments.iter().find(|f| f.label() == label).unwrap(),
+                linked,
+            )
+        };
+        assert_eq!(position("b1"), 0.5);
+        assert_eq!(position("b4"), 1.0);
+        assert_eq!(position("y4"), 1.0);
+        assert_eq!(position("y7"), 0.5);
+        assert!(position("b2") < position("b3"));
+        let odd_peptide = CompoundPeptidoform::pro_forma("PEPTIDE", None).unwrap();
+        let odd = odd_peptide.generate_theoretical_fragments(
+            Charge::new::<crate::system::charge::e>(1),
+            &crate::Model::none().only_b_y(),
+        );
+        let position = |label: &str| {
+            PositionIntensity.intensity(
+                odd.iter().find(|f| f.label() == label).unwrap(),
+                odd_peptide.peptides().next().unwrap(),
+            )
+        };
+        assert_eq!(position("b1"), 0.5);
+        assert_eq!(position("b3"), 1.0);
+        assert_eq!(position("b4"), 1.0);
+        assert_eq!(position("y6"), 0.5);
+    }
+
+    #[test]
+    fn structured_label() {
+        let position = PeptidePosition::n(SequencePosition::Index(2), 5);
+        let b3 = Fragment::new(
+            molecular_formula!(C 10 H 20 N 3 O 4),
+            Charge::new::<crate::system::charge::e>(1),
+            0,
+            0,
+            FragmentType::b(position),
+        );
+        let loss = b3.with_neutral_loss(&NeutralLoss::Loss(molecular_formula!(H 2 O 1)));
+        assert_eq!(loss.position(), Some(position));
+        assert_eq!(loss.series(), FragmentKind::b);
+        assert_eq!(loss.ordinal(), Some(3));
+        assert_eq!(
+            loss.neutral_loss,
+            Some(NeutralLoss::Loss(molecular_formula!(H 2 O 1)))
+        );
+        assert_eq!(b3.label(), "b3");
+        assert_eq!(loss.label(), format!("b3-{}", molecular_formula!(H 2 O 1)));
+
+        let precursor = Fragment::new(
+            molecular_formula!(C 10 H 20 N 3 O 4),
+            Charge::new::<crate::system::charge::e>(1),
+            0,
+            0,
+            FragmentType::precursor,
+        );
+        assert_eq!(precursor.position(), None);
+        assert_eq!(precursor.ordinal(), None);
+        assert_eq!(precursor.series(), FragmentKind::precursor);
+        assert_eq!(precursor.label(), "[M]+");
+    }
+
+    #[test]
+    fn complement() {
+        let peptide = crate::LinearPeptide::pro_forma("PEPTIDEK", None)
+            .unwrap()
+            .into_linear()
+            .unwrap();
+        let precursor = peptide.formulas()[0].clone();
+        let model = crate::Model::none()
+            .a(crate::model::PrimaryIonSeries::default())
+            .b(crate::model::PrimaryIonSeries::default())
+            .c(crate::model::PrimaryIonSeries::default())
+            .x(crate::model::PrimaryIonSeries::default())
+            .y(crate::model::PrimaryIonSeries::default())
+            .z(crate::model::PrimaryIonSeries::default());
+        let fragments = peptide
+            .generate_theoretical_fragments(Charge::new::<crate::system::charge::e>(1), &model);
+        let find = |label: &str| {
+            fragments
+                .iter()
+                .find(|f| f.neutral_loss.is_none() && f.ion.to_string() == label)
+                .unwrap()
+        };
+        // b3 + y5 = precursor
+        let b3 = find("b3");
+        let y5 = b3.complement(&precursor).unwrap();
+        assert_eq!(y5.ion.to_string(), "y5");
+        assert_eq!(y5.charge, b3.charge);
+        assert!(
+            (b3.neutral_mass(MassMode::Monoisotopic) + y5.neutral_mass(MassMode::Monoisotopic)
+                - precursor.monoisotopic_mass())
+            .value
+            .abs()
+                < 1e-6
+        );
+        assert_eq!(y5.formula, find("y5").formula);
+        assert_eq!(y5.complement(&precursor).unwrap().formula, b3.formula);
+        for (ion, complement) in [("a2", "x6"), ("c4", "z4"), ("y1", "b7"), ("x3", "a5")] {
+            let fragment = find(ion).complement(&precursor).unwrap();
+            assert_eq!(fragment.ion.to_string(), complement);
+            assert_eq!(fragment.formula, find(complement).formula, "{ion}");
+        }
+        assert!(
+            Fragment::new(precursor.clone(), b3.charge, 0, 0, FragmentType::precursor)
+                .complement(&precursor)
+                .is_none()
+        );
+        assert!(Fragment::new(
+            precursor.clone(),
+            b3.charge,
+            0,
+            0,
+            FragmentType::immonium(
+                PeptidePosition::n(SequencePosition::Index(1), 8),
+                AminoAcid::GlutamicAcid
+            )
+        )
+        .complement(&precursor)
+        .is_none());
+    }
+
     #[test]
     fn flip_terminal() {
         let n0 = PeptidePosition::n(SequencePosition::Index(0), 2);
@@ -626,4 +1174,24 @@ mod tests {
         assert_eq!(n1.flip_terminal(), c1);
         assert_eq!(n2.flip_terminal(), c2);
     }
+
+    #[test]
+    fn terminal_distance() {
+        for (index, n, c) in [
+            (SequencePosition::NTerm, 0, 4),
+            (SequencePosition::Index(0), 0, 4),
+            (SequencePosition::Index(2), 2, 2),
+            (SequencePosition::Index(4), 4, 0),
+            (SequencePosition::CTerm, 4, 0),
+        ] {
+            for position in [PeptidePosition::n(index, 5), PeptidePosition::c(index, 5)] {
+                assert_eq!(position.distance_from_n(), n, "{index:?}");
+                assert_eq!(position.distance_from_c(), c, "{index:?}");
+                assert_eq!(position.flip_terminal().distance_from_n(), n);
+                assert_eq!(position.flip_terminal().distance_from_c(), c);
+                assert_eq!(position.is_n_terminal(), index == SequencePosition::NTerm);
+                assert_eq!(position.is_c_terminal(), index == SequencePosition::CTerm);
+            }
+        }
+    }
 }
@@ -185,6 +185,392 @@ fn higher_charges() {
     );
 }
 
+#[test]
+fn fragment_charge_range() {
+    let peptide = LinearPeptide::pro_forma("ACD", None)
+        .unwrap()
+        .into_linear()
+        .unwrap();
+    let model = Model::none().a(PrimaryIonSeries::default());
+    let all = peptide.generate_theoretical_fragments(Charge::new::<crate::system::e>(5), &model);
+    let limited = peptide.generate_theoretical_fragments(
+        Charge::new::<crate::system::e>(5),
+        &model.fragment_charge_range(1..=2),
+    );
+    assert_eq!(all.len(), 11);
+    assert_eq!(limited.len(), 4);
+    assert!(limited.iter().all(|f| (1..=2).contains(&f.charge.value)));
+    assert!(limited.iter().all(|f| all.contains(f)));
+}
+
+#[test]
+fn fragment_isotopes() {
+    let peptide = LinearPeptide::pro_forma("ACD", None)
+        .unwrap()
+        .into_linear()
+        .unwrap();
+    let model = Model::none().a(PrimaryIonSeries::default());
+    let charge = Charge::new::<crate::system::e>(2);
+    let base = peptide.generate_theoretical_fragments(charge, &model);
+    let isotopes = peptide.generate_theoretical_fragments(charge, &model.fragment_isotopes(2));
+    assert_eq!(isotopes.len(), base.len() * 3);
+    for fragment in &base {
+        for isotope in 1..=2 {
+            let expected = fragment.mz(MassMode::Monoisotopic).value
+                + isotope as f64 * Fragment::ISOTOPE_SPACING / fragment.charge.value as f64;
+            assert!(isotopes.iter().any(|f| f.isotope == isotope
+                && f.ion == fragment.ion
+                && f.charge == fragment.charge
+                && (f.mz(MassMode::Monoisotopic).value - expected).abs() < 1e-9));
+        }
+    }
+    assert_eq!(
+        base[0].with_isotope(2).label(),
+        format!("{}+2i", base[0].ion)
+    );
+    assert_eq!(base[0].with_isotope(2).with_isotope(0), base[0]);
+}
+
+#[test]
+fn min_fragment_length() {
+    let peptide = LinearPeptide::pro_forma("ACDEK", None)
+        .unwrap()
+        .into_linear()
+        .unwrap();
+    let charge = Charge::new::<crate::system::e>(1);
+    let model = Model::none().only_b_y();
+    let all = peptide.generate_theoretical_fragments(charge, &model);
+    assert!(all.iter().any(|f| f.ordinal() == Some(1)));
+    let limited = peptide.generate_theoretical_fragments(charge, &model.min_fragment_length(2));
+    assert!(limited.iter().all(|f| f.ordinal().map_or(true, |o| o >= 2)));
+    assert!(!limited.iter().any(|f| f.ion.to_string() == "b1"));
+    assert!(!limited.iter().any(|f| f.ion.to_string() == "y1"));
+    assert!(limited.iter().any(|f| f.ion.to_string() == "b2"));
+    assert!(limited.iter().any(|f| f.ion.to_string() == "y2"));
+    assert_eq!(limited.len(), all.len() - 2);
+}
+
+#[test]
+fn max_ambiguous_combinations() {
+    let peptide = LinearPeptide::pro_forma("[Phospho]?STG", None)
+        .unwrap()
+        .into_linear()
+        .unwrap();
+    let charge = Charge::new::<crate::system::e>(1);
+    let model = Model::none().b(PrimaryIonSeries::default());
+    let count = |fragments: &[Fragment], ion: &str| {
+        fragments
+            .iter()
+            .filter(|f| f.ion.to_string() == ion)
+            .count()
+    };
+    // b1 with and without the phospho, b2 with the phospho on either position
+    let all = peptide.generate_theoretical_fragments(charge, &model);
+    assert_eq!(count(&all, "b1"), 2);
+    assert_eq!(count(&all, "b2"), 2);
+    let limited = peptide
+        .generate_theoretical_fragments(charge, &model.clone().max_ambiguous_combinations(1));
+    assert_eq!(count(&limited, "b1"), 1);
+    assert_eq!(count(&limited, "b2"), 1);
+    assert!(limited.iter().all(|f| all.contains(f)));
+    // Many ambiguous modifications are cut off instead of generating all combinations
+    let peptide = LinearPeptide::pro_forma("[Phospho]^6?SSSSSSSSSSSSG", None)
+        .unwrap()
+        .into_linear()
+        .unwrap();
+    let limited =
+        peptide.generate_theoretical_fragments(charge, &model.max_ambiguous_combinations(10));
+    for ordinal in 1..=12 {
+        let ion = format!("b{ordinal}");
+        assert!((1..=10).contains(&count(&limited, &ion)), "{ion}");
+    }
+}
+
+#[test]
+fn theoretical_fragments_iter() {
+    let peptide = LinearPeptide::pro_forma("<13C>[Acetyl]-AC[Carbamidomethyl]DK", None)
+        .unwrap()
+        .into_linear()
+        .unwrap();
+    let charge = Charge::new::<crate::system::e>(2);
+    let model = Model::all();
+    assert_eq!(
+        peptide
+            .theoretical_fragments_iter(charge, &model)
+            .collect_vec(),
+        peptide.generate_theoretical_fragments(charge, &model)
+    );
+    // Isotopes directly follow their monoisotopic fragment
+    let model = model.fragment_isotopes(1);
+    let mut lazy = peptide.theoretical_fragments_iter(charge, &model);
+    let first = lazy.next().unwrap();
+    assert_eq!(first.isotope, 0);
+    assert_eq!(lazy.next(), Some(first.with_isotope(1)));
+}
+
+#[test]
+fn theoretical_fragments_range() {
+    let peptide = LinearPeptide::pro_forma("PEPT[Phospho]IDEK", None)
+        .unwrap()
+        .into_linear()
+        .unwrap();
+    let charge = Charge::new::<crate::system::e>(1);
+    let model = Model::none()
+        .b(PrimaryIonSeries::default())
+        .y(PrimaryIonSeries::default());
+    let labels = |fragments: &[Fragment]| {
+        fragments
+            .iter()
+            .map(|f| f.ion.to_string())
+            .sorted()
+            .collect_vec()
+    };
+
+    let range = peptide.generate_theoretical_fragments_range(2..4, charge, &model);
+    assert_eq!(labels(&range), ["b3", "b4", "y5", "y6"]);
+    // The fragments are identical to the ones from the full peptide
+    let full = peptide.generate_theoretical_fragments(charge, &model);
+    for fragment in &range {
+        assert!(full.contains(fragment), "{fragment}");
+    }
+    assert!(peptide
+        .generate_theoretical_fragments_range(4..4, charge, &model)
+        .is_empty());
+    assert_eq!(
+        labels(&peptide.generate_theoretical_fragments_range(6..20, charge, &model)),
+        ["b7", "y1", "y2"]
+    );
+}
+
+#[test]
+fn radical_ions() {
+    let peptide = LinearPeptide::pro_forma("ACD", None)
+        .unwrap()
+        .into_linear()
+        .unwrap();
+    let hydrogen = molecular_formula!(H 1).monoisotopic_mass().value;
+    let model = Model::none()
+        .c(PrimaryIonSeries::default())
+        .z(PrimaryIonSeries::default());
+    let charge = Charge::new::<crate::system::e>(1);
+    let find = |fragments: &[Fragment], label: &str| {
+        fragments
+            .iter()
+            .find(|f| f.ion.to_string() == label)
+            .map(|f| f.mz(MassMode::Monoisotopic).value)
+    };
+
+    let radicals = peptide.generate_theoretical_fragments(charge, &model.clone().c_radical(true));
+    assert!(
+        (find(&radicals, "z·2").unwrap() - find(&radicals, "z2").unwrap() - hydrogen).abs() < 1e-6
+    );
+    assert!(
+        (find(&radicals, "c2").unwrap() - find(&radicals, "c·2").unwrap() - hydrogen).abs() < 1e-6
+    );
+
+    let none = peptide.generate_theoretical_fragments(charge, &model.z_radical(false));
+    assert!(find(&none, "z2").is_some());
+    assert!(find(&none, "c2").is_some());
+    assert!(find(&none, "z·2").is_none());
+    assert!(find(&none, "c·2").is_none());
+}
+
+#[test]
+fn fragment_masses() {
+    let peptide = LinearPeptide::pro_forma("ACD", None)
+        .unwrap()
+        .into_linear()
+        .unwrap();
+    let model = Model::none().b(PrimaryIonSeries::default());
+    let proton = molecular_formula!(H 1 Electron -1)
+        .monoisotopic_mass()
+        .value;
+    for fragment in
+        peptide.generate_theoretical_fragments(Charge::new::<crate::system::e>(2), &model)
+    {
+        let z = fragment.charge.value as f64;
+        assert!(
+            (fragment.monoisotopic_mass().value - fragment.mz(MassMode::Monoisotopic).value * z)
+                .abs()
+                < 1e-9
+        );
+        assert!(
+            (fragment.neutral_mass(MassMode::Monoisotopic).value
+                - (fragment.monoisotopic_mass().value - z * proton))
+                .abs()
+                < 1e-9,
+            "{fragment}"
+        );
+        assert!(fragment.average_mass() > fragment.monoisotopic_mass());
+    }
+}
+
+#[test]
+fn reference_peptide() {
+    // Singly charged ions for PEPTIDE as listed by Matrix Science and Protein Prospector, with z
+    // following the Matrix Science definition (y - NH3) and z· being z+1
+    #[allow(clippy::unreadable_literal)]
+    let theoretical_fragments = &[
+        (70.065126, "a1"),
+        (98.060040, "b1"),
+        (115.086589, "c1"),
+        (199.107719, "a2"),
+        (227.102633, "b2"),
+        (244.129182, "c2"),
+        (296.160483, "a3"),
+        (324.155397, "b3"),
+        (341.181946, "c3"),
+        (397.208161, "a4"),
+        (425.203076, "b4"),
+        (442.229625, "c4"),
+        (510.292225, "a5"),
+        (538.287140, "b5"),
+        (555.313689, "c5"),
+        (625.319168, "a6"),
+        (653.314083, "b6"),
+        (670.340632, "c6"),
+        (174.039699, "x1"),
+        (148.060434, "y1"),
+        (131.033885, "z1"),
+        (132.041710, "z·1"),
+        (289.066642, "x2"),
+        (263.087377, "y2"),
+        (246.060828, "z2"),
+        (247.068653, "z·2"),
+        (402.150706, "x3"),
+        (376.171441, "y3"),
+        (359.144892, "z3"),
+        (360.152717, "z·3"),
+        (503.198384, "x4"),
+        (477.219120, "y4"),
+        (460.192571, "z4"),
+        (461.200396, "z·4"),
+        (600.251148, "x5"),
+        (574.271884, "y5"),
+        (557.245334, "z5"),
+        (558.253159, "z·5"),
+        (729.293741, "x6"),
+        (703.314477, "y6"),
+        (686.287928, "z6"),
+        (687.295753, "z·6"),
+    ];
+    let peptide = LinearPeptide::pro_forma("PEPTIDE", None)
+        .unwrap()
+        .into_linear()
+        .unwrap();
+    let model = Model::none()
+        .a(PrimaryIonSeries::default())
+        .b(PrimaryIonSeries::default())
+        .c(PrimaryIonSeries::default())
+        .x(PrimaryIonSeries::default())
+        .y(PrimaryIonSeries::default())
+        .z(PrimaryIonSeries::default());
+    let fragments = peptide
+        .generate_theoretical_fragments(Charge::new::<crate::system::e>(1), &model)
+        .into_iter()
+        .filter(|f| f.neutral_loss.is_none() && f.ion.kind() != fragment::FragmentKind::precursor)
+        .collect_vec();
+    for (mass, label) in theoretical_fragments {
+        let fragment = fragments
+            .iter()
+            .find(|f| f.ion.to_string() == *label)
+            .unwrap_or_else(|| panic!("No {label} fragment generated"));
+        let mz = fragment.mz(MassMode::Monoisotopic).value;
+        assert!(
+            (mz - mass).abs() < 0.001,
+            "{label} is {mz} but should be {mass}"
+        );
+    }
+    assert_eq!(fragments.len(), theoretical_fragments.len());
+}
+
+#[test]
+fn average_mass_mode() {
+    use crate::spectrum::{PeakSpectrum, RawPeak};
+    // For a large fragment the average mass is clearly higher than the monoisotopic mass, as the
+    // heavier isotopes contribute to the average
+    let peptide = LinearPeptide::pro_forma("PEPTIDEPEPTIDEPEPTIDEK", None)
+        .unwrap()
+        .into_linear()
+        .unwrap();
+    let model = Model::none()
+        .y(PrimaryIonSeries::default())
+        .mass_mode(MassMode::Average);
+    let fragments =
+        peptide.generate_theoretical_fragments(Charge::new::<crate::system::e>(1), &model);
+    let y = fragments
+        .iter()
+        .filter(|f| matches!(f.ion, fragment::FragmentType::y(_)) && f.neutral_loss.is_none())
+        .max_by(|a, b| {
+            a.mz(MassMode::Monoisotopic)
+                .value
+                .total_cmp(&b.mz(MassMode::Monoisotopic).value)
+        })
+        .unwrap();
+    let monoisotopic = y.mz(MassMode::Monoisotopic);
+    let average = y.mz(MassMode::Average);
+    assert!((average.value - y.average_mass().value).abs() < 1e-9);
+    let difference = (average - monoisotopic).value;
+    assert!(
+        (1.0..2.0).contains(&difference),
+        "{monoisotopic:?} {average:?} {difference}"
+    );
+    // The annotation uses the mass mode of the model
+    let mut spectrum = RawSpectrum::default();
+    spectrum.extend([RawPeak {
+        mz: average,
+        intensity: 1.0.into(),
+    }]);
+    let is_annotated = |model: &Model| {
+        !spectrum
+            .annotate(
+                CompoundPeptidoform::from(peptide.clone()),
+                &fragments,
+                model,
+            )
+            .spectrum()
+            .next()
+            .unwrap()
+            .annotation
+            .is_empty()
+    };
+    assert!(is_annotated(&model));
+    assert!(!is_annotated(&model.mass_mode(MassMode::Monoisotopic)));
+}
+
+#[test]
+fn chemically_aware_losses() {
+    let peptide = LinearPeptide::pro_forma("GLAPKAG", None)
+        .unwrap()
+        .into_linear()
+        .unwrap();
+    let water = NeutralLoss::Loss(molecular_formula!(H 2 O 1));
+    let ammonia = NeutralLoss::Loss(molecular_formula!(H 3 N 1));
+    let series = PrimaryIonSeries::default().neutral_losses(vec![water.clone(), ammonia.clone()]);
+    let model = Model::none().b(series.clone()).y(series);
+    let charge = Charge::new::<crate::system::e>(1);
+    let b_with_loss = |model: &Model, loss: &NeutralLoss| {
+        peptide
+            .generate_theoretical_fragments(charge, model)
+            .into_iter()
+            .filter_map(|f| match f.ion {
+                fragment::FragmentType::b(position) if f.neutral_loss.as_ref() == Some(loss) => {
+                    Some(position.series_number)
+                }
+                _ => None,
+            })
+            .sorted()
+            .collect_vec()
+    };
+    assert!(!b_with_loss(&model, &water).is_empty());
+    let aware = model.chemically_aware_losses(true);
+    // No S, T, D, or E so no water loss at all
+    assert!(b_with_loss(&aware, &water).is_empty());
+    // Only b ions that contain the K can lose ammonia
+    assert!(b_with_loss(&aware, &ammonia).iter().all(|n| *n >= 5));
+    assert!(!b_with_loss(&aware, &ammonia).is_empty());
+}
+
 #[test]
 fn all_aminoacids() {
     // Compare rustyms with https://proteomicsresource.washington.edu/cgi-bin/fragment.cgi
@@ -739,3 +1125,27 @@ fn test(
         );
     }
 }
+
+#[test]
+fn filter_fragments() {
+    let peptide = LinearPeptide::pro_forma("ACDEK", None)
+        .unwrap()
+        .into_linear()
+        .unwrap();
+    let fragments =
+        peptide.generate_theoretical_fragments(Charge::new::<crate::system::e>(2), &Model::all());
+    let y =
+        fragment::filter_fragments(&fragments, &[fragment::FragmentKind::y], 1..=1).collect_vec();
+    assert!(!y.is_empty());
+    assert!(y
+        .iter()
+        .all(|f| f.is_series(fragment::FragmentKind::y) && f.charge.value == 1));
+    assert_eq!(
+        y.len(),
+        fragments
+            .iter()
+            .filter(|f| matches!(f.ion, fragment::FragmentType::y(_)) && f.charge.value == 1)
+            .count()
+    );
+    assert!(y.len() < fragments.len());
+}
@@ -0,0 +1,276 @@
+//! Handle glycan compositions
+use std::{
+    fmt::Display,
+    ops::{Add, Neg, Sub},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{glycan_parse_list, MonoSaccharide};
+use crate::{
+    error::{Context, CustomError},
+    formula::{Chemical, MolecularFormula},
+    helper_functions::parse_named_counter,
+    modification::{GnoComposition, SimpleModification},
+    system::Mass,
+    SequencePosition,
+};
+
+/// A glycan composition, the number of times each monosaccharide occurs.
+///
+/// The composition is always normalised (sorted and deduplicated) and does not contain any zero
+/// counts. Compositions can be added and subtracted, subtraction can result in negative counts,
+/// use [`Self::is_valid`] to check if a composition can exist on its own.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct MonosaccharideComposition(Vec<(MonoSaccharide, isize)>);
+
+impl MonosaccharideComposition {
+    /// Create a new composition from the given monosaccharides and their counts.
+    /// Returns None if overflow occurred, meaning that there where more than `isize::MAX` or less
+    /// then `isize::MIN` monosaccharides for one species.
+    pub fn new(composition: Vec<(MonoSaccharide, isize)>) -> Option<Self> {
+        MonoSaccharide::simplify_composition(composition).map(Self)
+    }
+
+    /// Get the monosaccharides and their counts, sorted on monosaccharide
+    pub fn composition(&self) -> &[(MonoSaccharide, isize)] {
+        &self.0
+    }
+
+    /// Get the number of times the given monosaccharide occurs in this composition
+    pub fn count(&self, monosaccharide: &MonoSaccharide) -> isize {
+        self.0
+            .iter()
+            .find(|(m, _)| m == monosaccharide)
+            .map_or(0, |(_, n)| *n)
+    }
+
+    /// Check if this composition is empty
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Check if this composition could exist on its own, meaning that it is not empty and no
+    /// monosaccharide occurs a negative number of times.
+    pub fn is_valid(&self) -> bool {
+        !self.0.is_empty() && self.0.iter().all(|(_, n)| *n > 0)
+    }
+
+    /// Get the monoisotopic mass of this composition, see [`Chemical::formula`] to get the full
+    /// molecular formula.
+    pub fn mass(&self) -> Mass {
+        self.formula().monoisotopic_mass()
+    }
+
+    /// Combine two compositions, adding (`sign = 1`) or subtracting (`sign = -1`) the other.
+    /// # Panics
+    /// If one monosaccharide species has occurrence outside the range of [`isize::MIN`] to [`isize::MAX`].
+    fn combine(&self, other: &Self, sign: isize) -> Self {
+        Self::new(
+            self.0
+                .iter()
+                .cloned()
+                .chain(other.0.iter().map(|(m, n)| (m.clone(), n * sign)))
+                .collect(),
+        )
+        .expect("One monosaccharide species has a number outside of the range of isize")
+    }
+}
+
+impl Chemical for MonosaccharideComposition {
+    fn formula_inner(
+        &self,
+        sequence_index: SequencePosition,
+        peptide_index: usize,
+    ) -> MolecularFormula {
+        self.0
+            .iter()
+            .fold(MolecularFormula::default(), |acc, (m, n)| {
+                acc + m.formula_inner(sequence_index, peptide_index) * *n as i32
+            })
+    }
+}
+
+impl FromStr for MonosaccharideComposition {
+    type Err = CustomError;
+    /// Parse a glycan composition in the same format as used for `Glycan:` modifications in
+    /// ProForma, for example `HexNAc2Hex3`.
+    /// # Errors
+    /// If the monosaccharides or numbers cannot be recognised.
+    fn from_str(line: &str) -> Result<Self, CustomError> {
+        let composition =
+            parse_named_counter(&line.to_ascii_lowercase(), glycan_parse_list(), false).map_err(
+                |e| {
+                    CustomError::error("Invalid glycan composition", e, Context::full_line(0, line))
+                },
+            )?;
+        Self::new(composition).ok_or_else(|| {
+            CustomError::error(
+                "Invalid glycan composition",
+                format!(
+                    "The occurrence of one monosaccharide species is outside of the range {} to {}",
+                    isize::MIN,
+                    isize::MAX
+                ),
+                Context::full_line(0, line),
+            )
+        })
+    }
+}
+
+impl Display for MonosaccharideComposition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (monosaccharide, count) in &self.0 {
+            write!(f, "{monosaccharide}{count}")?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<&SimpleModification> for MonosaccharideComposition {
+    type Error = ();
+    /// Get the glycan composition of a `Glycan` or `GlycanStructure` modification or a GNOme
+    /// modification with a known composition or structure.
+    fn try_from(value: &SimpleModification) -> Result<Self, Self::Error> {
+        match value {
+            SimpleModification::Glycan(composition)
+            | SimpleModification::Gno(GnoComposition::Composition(composition), _) => {
+                Ok(Self(composition.clone()))
+            }
+            SimpleModification::GlycanStructure(structure)
+            | SimpleModification::Gno(GnoComposition::Structure(structure), _) => {
+                Ok(Self(structure.composition()))
+            }
+            _ => Err(()),
+        }
+    }
+}
+
+impl From<MonosaccharideComposition> for SimpleModification {
+    fn from(value: MonosaccharideComposition) -> Self {
+        Self::Glycan(value.0)
+    }
+}
+
+impl Add<&MonosaccharideComposition> for &MonosaccharideComposition {
+    type Output = MonosaccharideComposition;
+    fn add(self, rhs: &MonosaccharideComposition) -> Self::Output {
+        self.combine(rhs, 1)
+    }
+}
+
+impl Sub<&MonosaccharideComposition> for &MonosaccharideComposition {
+    type Output = MonosaccharideComposition;
+    fn sub(self, rhs: &MonosaccharideComposition) -> Self::Output {
+        self.combine(rhs, -1)
+    }
+}
+
+impl Neg for MonosaccharideComposition {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self(self.0.into_iter().map(|(m, n)| (m, -n)).collect())
+    }
+}
+
+impl_binop_ref_cases!(impl Add, add for MonosaccharideComposition, MonosaccharideComposition, MonosaccharideComposition);
+impl_binop_ref_cases!(impl Sub, sub for MonosaccharideComposition, MonosaccharideComposition, MonosaccharideComposition);
+
+#[cfg(test)]
+#[allow(clippy::missing_panics_doc)]
+mod tests {
+    use super::*;
+    use crate::{system::da, LinearPeptide, Modification};
+
+    #[test]
+    fn parse_and_display() {
+        let composition: MonosaccharideComposition = "HexNAc2Hex3".parse().unwrap();
+        assert_eq!(composition.composition().len(), 2);
+        assert_eq!(
+            composition,
+            "Hex3HexNAc2".parse::<MonosaccharideComposition>().unwrap()
+        );
+        assert_eq!(
+            composition,
+            composition
+                .to_string()
+                .parse::<MonosaccharideComposition>()
+                .unwrap()
+        );
+        assert!("HexNAc2Nonsense3"
+            .parse::<MonosaccharideComposition>()
+            .is_err());
+    }
+
+    #[test]
+    fn arithmetic() {
+        let core: MonosaccharideComposition = "HexNAc2Hex3".parse().unwrap();
+        let hex: MonosaccharideComposition = "Hex1".parse().unwrap();
+        let fucose: MonosaccharideComposition = "Fuc1".parse().unwrap();
+        let man5 = &core + &hex + &hex;
+        assert_eq!(man5, "HexNAc2Hex5".parse().unwrap());
+        assert_eq!(&man5 - &hex - &hex, core);
+        assert_eq!(&man5 - &man5, MonosaccharideComposition::default());
+        assert!((&man5 - &man5).is_empty());
+
+        let fucosylated = core.clone() + fucose.clone();
+        assert_eq!(fucosylated.composition().len(), 3);
+        assert_eq!(
+            fucosylated.count(&fucose.composition()[0].0),
+            1,
+            "{fucosylated}"
+        );
+        assert!(fucosylated.is_valid());
+        let negative = &core - &fucose;
+        assert!(!negative.is_valid());
+        assert_eq!(negative.count(&fucose.composition()[0].0), -1);
+        assert_eq!(-negative, &fucose - &core);
+    }
+
+    #[test]
+    fn mass() {
+        let hex: MonosaccharideComposition = "Hex1".parse().unwrap();
+        let hexnac: MonosaccharideComposition = "HexNAc1".parse().unwrap();
+        // Hex residue C6H10O5
+        assert!((hex.mass() - da(162.052_823)).value.abs() < 1e-5);
+        // HexNAc residue C8H13NO5
+        assert!((hexnac.mass() - da(203.079_373)).value.abs() < 1e-5);
+        let core = &(&hexnac + &hexnac) + &(&(&hex + &hex) + &hex);
+        assert!(
+            (core.mass() - (hex.mass() * 3.0 + hexnac.mass() * 2.0))
+                .value
+                .abs()
+                < 1e-9
+        );
+        assert!(
+            (core.mass() - (&core - &hex).mass() - hex.mass())
+                .value
+                .abs()
+                < 1e-9
+        );
+    }
+
+    #[test]
+    fn from_modification() {
+        let peptide = LinearPeptide::pro_forma("N[Glycan:HexNAc2Hex3]K", None).unwrap();
+        let Modification::Simple(glycan) = &peptide.sequence()[0].modifications[0] else {
+            panic!("Not a simple modification")
+        };
+        let composition = MonosaccharideComposition::try_from(glycan).unwrap();
+        assert_eq!(composition, "HexNAc2Hex3".parse().unwrap());
+        assert_eq!(SimpleModification::from(composition), *glycan);
+        let peptide =
+            LinearPeptide::pro_forma("N[GlycanStructure:HexNAc(HexNAc(Hex))]K", None).unwrap();
+        let Modification::Simple(structure) = &peptide.sequence()[0].modifications[0] else {
+            panic!("Not a simple modification")
+        };
+        assert_eq!(
+            MonosaccharideComposition::try_from(structure).unwrap(),
+            "HexNAc2Hex1".parse().unwrap()
+        );
+        assert!(
+            MonosaccharideComposition::try_from(&SimpleModification::Mass(da(1.0).into())).is_err()
+        );
+    }
+}
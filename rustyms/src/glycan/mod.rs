@@ -1,9 +1,11 @@
 //! Handle glycan related issues, access provided if you want to work with glycans on your own.
 
+mod composition;
 mod glycan_structure;
 mod monosaccharide;
 mod positioned_structure;
 
+pub use composition::*;
 pub use glycan_structure::*;
 pub use monosaccharide::*;
 pub use positioned_structure::*;
@@ -11,6 +11,17 @@ include!("../shared/glycan.rs");
 include!("../shared/glycan_lists.rs");
 
 impl MonoSaccharide {
+    /// Get a monosaccharide by its ProForma name, for example `HexNAc`, `Hex`, `Fuc`, `NeuAc`, or
+    /// `NeuGc`. The name is matched case insensitively against the same list as used to parse
+    /// `Glycan:` modifications. Use [`Chemical::formula`] to get the residue formula.
+    pub fn from_proforma_name(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        glycan_parse_list()
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, m)| m.clone())
+    }
+
     /// Simplify a glycan composition to be sorted and deduplicated.
     /// Returns None if overflow occurred, meaning that there where more than `isize::MAX` or less then `isize::MIN` monosaccharides for one species.
     pub(crate) fn simplify_composition(
@@ -293,6 +304,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn from_proforma_name() {
+        let cases = &[
+            ("Hex", molecular_formula!(H 10 C 6 O 5), 162.052_823),
+            ("HexNAc", molecular_formula!(H 13 C 8 N 1 O 5), 203.079_373),
+            ("Fuc", molecular_formula!(H 10 C 6 O 4), 146.057_909),
+            ("NeuAc", molecular_formula!(H 17 C 11 N 1 O 8), 291.095_417),
+            ("NeuGc", molecular_formula!(H 17 C 11 N 1 O 9), 307.090_331),
+        ];
+        for (name, formula, mass) in cases {
+            let monosaccharide = MonoSaccharide::from_proforma_name(name)
+                .unwrap_or_else(|| panic!("Assumed {name} would be defined"));
+            assert_eq!(monosaccharide.formula(), *formula, "{name}");
+            assert!(
+                (monosaccharide.formula().monoisotopic_mass().value - mass).abs() < 1e-5,
+                "{name}"
+            );
+            assert_eq!(
+                MonoSaccharide::from_proforma_name(&name.to_ascii_uppercase()),
+                Some(monosaccharide)
+            );
+        }
+        assert_eq!(MonoSaccharide::from_proforma_name("HexNAc2"), None);
+        assert_eq!(MonoSaccharide::from_proforma_name("Nonsense"), None);
+        assert_eq!(MonoSaccharide::from_proforma_name(""), None);
+    }
+
     #[test]
     fn iupac_short_names() {
         let parse = |str: &str| {
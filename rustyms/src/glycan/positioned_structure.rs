@@ -67,32 +67,8 @@ impl PositionedGlycanStructure {
                     .collect_vec();
                 // Generate all Y fragments
                 base_fragments.extend(
-                    self.internal_break_points(peptide_index, attachment)
-                        .iter()
-                        .filter(|(_, bonds)| {
-                            bonds.iter().all(|b| !matches!(b, GlycanBreakPos::B(_)))
-                                && !bonds.iter().all(|b| matches!(b, GlycanBreakPos::End(_)))
-                        })
-                        .flat_map(move |(f, bonds)| {
-                            full_formula.iter().map(move |full| {
-                                Fragment::new(
-                                    full - self
-                                        .formula_inner(SequencePosition::default(), peptide_index)
-                                        + f,
-                                    Charge::zero(),
-                                    peptidoform_index,
-                                    peptide_index,
-                                    FragmentType::Y(
-                                        bonds
-                                            .iter()
-                                            .filter(|b| !matches!(b, GlycanBreakPos::End(_)))
-                                            .map(GlycanBreakPos::position)
-                                            .cloned()
-                                            .collect(),
-                                    ),
-                                )
-                            })
-                        })
+                    self.y_fragments(peptidoform_index, peptide_index, full_formula, attachment)
+                        .into_iter()
                         .flat_map(|f| {
                             f.with_charge_range(charge_carriers, model.glycan.other_charge_range)
                         })
@@ -111,6 +87,46 @@ impl PositionedGlycanStructure {
             .unwrap_or_default()
     }
 
+    /// Generate the uncharged Y ion ladder for this glycan, meaning the peptide with every
+    /// possible sub-tree of the glycan still attached (the fully intact glycan is excluded).
+    /// * `full_formula` the total formula of the whole peptide + glycan
+    pub fn y_fragments(
+        &self,
+        peptidoform_index: usize,
+        peptide_index: usize,
+        full_formula: &Multi<MolecularFormula>,
+        attachment: Option<(AminoAcid, usize)>,
+    ) -> Vec<Fragment> {
+        let glycan = self.formula_inner(SequencePosition::default(), peptide_index);
+        self.internal_break_points(peptide_index, attachment)
+            .into_iter()
+            .filter(|(_, bonds)| {
+                bonds.iter().all(|b| !matches!(b, GlycanBreakPos::B(_)))
+                    && !bonds.iter().all(|b| matches!(b, GlycanBreakPos::End(_)))
+            })
+            .flat_map(|(f, bonds)| {
+                let bonds: Vec<GlycanPosition> = bonds
+                    .iter()
+                    .filter(|b| !matches!(b, GlycanBreakPos::End(_)))
+                    .map(GlycanBreakPos::position)
+                    .cloned()
+                    .collect();
+                full_formula
+                    .iter()
+                    .map(|full| {
+                        Fragment::new(
+                            full - &glycan + &f,
+                            Charge::zero(),
+                            peptidoform_index,
+                            peptide_index,
+                            FragmentType::Y(bonds.clone()),
+                        )
+                    })
+                    .collect_vec()
+            })
+            .collect()
+    }
+
     /// Get uncharged diagnostic ions from all positions
     fn diagnostic_ions(
         &self,
@@ -252,3 +268,33 @@ impl PositionedGlycanStructure {
         }
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::missing_panics_doc)]
+mod tests {
+    use super::*;
+    use crate::{glycan::GlycanStructure, MultiChemical};
+
+    #[test]
+    fn y_ladder() {
+        let glycan = GlycanStructure::from_short_iupac("Man(?1-?)GlcNAc(?1-?)GlcNAc", 0..27, 0)
+            .unwrap()
+            .determine_positions();
+        let peptide = AminoAcid::Asparagine.formulas();
+        let full = &peptide + &glycan.formula();
+        let fragments = glycan.y_fragments(0, 0, &full, Some((AminoAcid::Asparagine, 0)));
+        // Bare peptide, +HexNAc, and +HexNAc2, the intact glycan is not included
+        assert_eq!(fragments.len(), 3);
+        let labels = fragments
+            .iter()
+            .map(|f| f.ion.position_label().unwrap_or_default())
+            .sorted()
+            .collect_vec();
+        assert_eq!(labels, ["1", "2", "3"]);
+        let bare = fragments
+            .iter()
+            .find(|f| f.ion.position_label().as_deref() == Some("3"))
+            .unwrap();
+        assert_eq!(bare.formula, peptide[0]);
+    }
+}
@@ -1,6 +1,7 @@
 #![allow(dead_code)]
 
 use std::{
+    borrow::Cow,
     num::{IntErrorKind, ParseIntError},
     ops::{Bound, Range, RangeBounds},
     path::Path,
@@ -241,13 +242,26 @@ pub fn next_char(chars: &[u8], start: usize, char: u8) -> Option<usize> {
 }
 
 #[allow(dead_code)]
-/// Find the enclosed text by the given symbols, assumes a single open is already read just before the start, guarantees to only pick full characters
+/// Find the enclosed text by the given symbols, assumes a single open is already read just before the start, guarantees to only pick full characters.
+///
+/// Any character escaped with a backslash (`\`) is skipped.
 pub fn end_of_enclosure(text: &str, start: usize, open: u8, close: u8) -> Option<usize> {
     let mut state = 1;
+    let mut escaped = false;
     for (i, ch) in text[start..].as_bytes().iter().enumerate() {
+        // Skip the continuation bytes of multi byte characters
+        if !text.is_char_boundary(start + i) {
+            continue;
+        }
+        if escaped {
+            escaped = false;
+            continue;
+        }
         // Check if this byte is a full character (is_char_boundary also works on index==len)
-        if text.is_char_boundary(start + i) && text.is_char_boundary(start + i + 1) {
-            if *ch == open {
+        if text.is_char_boundary(start + i + 1) {
+            if *ch == b'\\' {
+                escaped = true;
+            } else if *ch == open {
                 state += 1;
             } else if *ch == close {
                 state -= 1;
@@ -263,6 +277,8 @@ pub fn end_of_enclosure(text: &str, start: usize, open: u8, close: u8) -> Option
 #[allow(dead_code)]
 /// Find the enclosed text by the given symbols, assumes a single open is already read just before the start.
 /// This also takes brackets '[]' into account and these take precedence over the enclosure searched for.
+///
+/// Any character escaped with a backslash (`\`) is skipped.
 pub fn end_of_enclosure_with_brackets(
     text: &str,
     start: usize,
@@ -281,6 +297,10 @@ pub fn end_of_enclosure_with_brackets(
             continue;
         }
         let ch = text.as_bytes()[index];
+        if ch == b'\\' {
+            index += 2;
+            continue;
+        }
         if ch == b'[' {
             index = end_of_enclosure(text, index + 1, b'[', b']')?;
         }
@@ -297,6 +317,63 @@ pub fn end_of_enclosure_with_brackets(
     None
 }
 
+#[allow(dead_code)]
+/// Split the text on the given separator, any separator escaped with a backslash (`\`) is ignored.
+pub fn split_unescaped(text: &str, separator: u8) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+    for (i, ch) in text.as_bytes().iter().enumerate() {
+        if escaped {
+            escaped = false;
+        } else if *ch == b'\\' {
+            escaped = true;
+        } else if *ch == separator {
+            parts.push(&text[start..i]);
+            start = i + 1;
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+#[allow(dead_code)]
+/// Remove all escaping backslashes (`\`) from the text, an escaped backslash results in a single backslash.
+pub fn unescape(text: &str) -> Cow<'_, str> {
+    if text.contains('\\') {
+        let mut output = String::with_capacity(text.len());
+        let mut escaped = false;
+        for ch in text.chars() {
+            if !escaped && ch == '\\' {
+                escaped = true;
+            } else {
+                escaped = false;
+                output.push(ch);
+            }
+        }
+        Cow::Owned(output)
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+#[allow(dead_code)]
+/// Escape all given characters (and backslashes (`\`) themselves) in the text with a backslash.
+pub fn escape<'a>(text: &'a str, characters: &[char]) -> Cow<'a, str> {
+    if text.contains(|c| c == '\\' || characters.contains(&c)) {
+        let mut output = String::with_capacity(text.len() + 1);
+        for ch in text.chars() {
+            if ch == '\\' || characters.contains(&ch) {
+                output.push('\\');
+            }
+            output.push(ch);
+        }
+        Cow::Owned(output)
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
 #[allow(dead_code)]
 /// Get the next number, returns length in bytes and the number.
 /// # Panics
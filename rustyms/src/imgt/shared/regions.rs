@@ -301,6 +301,7 @@ impl<'a> IntoParallelIterator for &'a Germline {
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct AnnotatedSequence {
     /// The sequence
+    #[serde(with = "crate::peptide::germline_layout")]
     pub sequence: LinearPeptide<UnAmbiguous>,
     /// The different regions in the sequence, defined by their name and length
     pub regions: Vec<(Region, usize)>,
@@ -1,10 +1,105 @@
-use crate::{system::da, system::Mass, MolecularFormula};
+use crate::{
+    system::{da, fraction, Mass, Ratio},
+    Element, MolecularFormula,
+};
 use itertools::Itertools;
 use ndarray::{arr1, concatenate, s, Array1, Axis};
 use probability::distribution::{Binomial, Discrete};
+use serde::{Deserialize, Serialize};
 use std::{cmp::Ordering, collections::HashMap};
 
+/// A table with isotopic abundances, for all elements that are not set in this table the natural
+/// abundances as defined by CIAAW are used.
+///
+/// This can be used to model samples with non natural isotope ratios, for example from metabolic
+/// labelling.
+///
+/// The abundances for an element are given as a list of the isotope (mass number, eg 13 for 13C)
+/// and the relative abundance of that isotope. The abundances are normalised to 1 so they can be
+/// given as fractions or percentages. Any isotope of that element not listed has an abundance of 0.
+/// ```rust
+/// # use rustyms::{Element, IsotopeTable};
+/// let mut table = IsotopeTable::default();
+/// // A sample enriched to 50% 13C
+/// assert!(table.set_abundances(Element::C, &[(12, 50.0), (13, 50.0)]));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct IsotopeTable {
+    abundances: HashMap<Element, Vec<(u16, f64)>>,
+}
+
+impl IsotopeTable {
+    /// Set the abundances for the given element, if any of the isotopes does not exist for this
+    /// element or the total abundance is not positive nothing is changed and false is returned.
+    #[must_use]
+    pub fn set_abundances(&mut self, element: Element, abundances: &[(u16, f64)]) -> bool {
+        let total: f64 = abundances.iter().map(|(_, a)| a).sum();
+        if total > 0.0
+            && abundances.iter().all(|(isotope, abundance)| {
+                *abundance >= 0.0 && element.isotopes().iter().any(|i| i.0 == *isotope)
+            })
+        {
+            self.abundances.insert(
+                element,
+                abundances
+                    .iter()
+                    .map(|(isotope, abundance)| (*isotope, abundance / total))
+                    .collect(),
+            );
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Get all isotopes (N, mass, abundance) for the given element, using the abundances from this
+    /// table if set and otherwise the natural abundances.
+    pub fn isotopes(&self, element: Element) -> Vec<(u16, Mass, f64)> {
+        self.abundances.get(&element).map_or_else(
+            || element.isotopes().to_vec(),
+            |abundances| {
+                element
+                    .isotopes()
+                    .iter()
+                    .map(|(n, mass, _)| {
+                        (
+                            *n,
+                            *mass,
+                            abundances
+                                .iter()
+                                .find(|(i, _)| i == n)
+                                .map_or(0.0, |(_, a)| *a),
+                        )
+                    })
+                    .collect()
+            },
+        )
+    }
+}
+
 impl MolecularFormula {
+    /// The average weight of the molecular formula, using the abundances from the given table.
+    /// Elements with a specified isotope are not influenced by the table.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn average_weight_with(&self, table: &IsotopeTable) -> Mass {
+        let mut mass = da(*self.additional_mass()); // Technically this is wrong, the additional mass is defined to be monoisotopic
+        for (e, i, n) in self.elements() {
+            let weight = if i.is_none() && table.abundances.contains_key(e) {
+                table
+                    .isotopes(*e)
+                    .iter()
+                    .fold(Mass::default(), |acc, (_, m, a)| {
+                        acc + *m * Ratio::new::<fraction>(*a)
+                    })
+            } else {
+                e.average_weight(*i)
+                    .expect("An invalid molecular formula was created, please report this crash")
+            };
+            mass += weight * Ratio::new::<fraction>(f64::from(*n));
+        }
+        mass
+    }
+
     /// Get the isotopic distribution, using the natural distribution as defined by CIAAW.
     /// All elements are considered. The return is an array with the probability per offset.
     /// The first element of the array is the base peak, every consecutive peak is 1 dalton heavier.
@@ -13,8 +108,14 @@ impl MolecularFormula {
     /// This approximation slightly overestimates the tail end of the distribution. Especially
     /// for species with multiple higher mass isotopes as it does not take the number of already
     /// chosen atom for lower weighed isotopes into account.
-    #[allow(clippy::missing_panics_doc)]
     pub fn isotopic_distribution(&self, threshold: f64) -> Array1<f64> {
+        self.isotopic_distribution_with(&IsotopeTable::default(), threshold)
+    }
+
+    /// Get the isotopic distribution, using the abundances from the given table. See
+    /// [`Self::isotopic_distribution`] for more details.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn isotopic_distribution_with(&self, table: &IsotopeTable, threshold: f64) -> Array1<f64> {
         let mut result = arr1(&[1.0]);
         for (element, isotope, amount) in self.elements() {
             if isotope.is_some() || *amount <= 0 {
@@ -22,37 +123,43 @@ impl MolecularFormula {
                 continue;
             }
             let amount = usize::try_from(*amount).unwrap();
-            let isotopes = element
-                .isotopes()
-                .iter()
-                .filter(|i| i.2 != 0.0)
-                .collect_vec();
-            if isotopes.len() < 2 {
-                // Only a single species, so no distribution is needed
+            // The base is the lightest naturally occurring isotope, so the first peak is always the
+            // monoisotopic peak, even if the abundance of the base isotope is changed
+            let Some(base) = element.isotopes().iter().find(|i| i.2 != 0.0) else {
                 continue;
-            }
+            };
             // Get the probability and base offset (weight) for all non base isotopes
-            let base = isotopes[0];
-            let isotopes = isotopes
+            let isotopes = table
+                .isotopes(*element)
                 .into_iter()
-                .skip(1)
+                .filter(|i| i.2 != 0.0 && i.0 > base.0)
                 .map(|i| (i.0 - base.0, i.2))
                 .collect_vec();
+            if isotopes.is_empty() {
+                // Only a single species, so no distribution is needed
+                continue;
+            }
 
             for isotope in isotopes {
                 // Generate distribution (take already chosen into account?)
-                let binomial = Binomial::new(amount, isotope.1);
+                // A fully enriched isotope is not a valid binomial, but means all atoms are this isotope
+                let binomial = (isotope.1 < 1.0).then(|| Binomial::new(amount, isotope.1));
+                let probability = |t: usize| {
+                    binomial
+                        .as_ref()
+                        .map_or(if t == amount { 1.0 } else { 0.0 }, |b| b.mass(t))
+                };
 
                 // See how many numbers are below the threshold from the end of the distribution
                 let tail = (0..=amount)
                     .rev()
-                    .map(|t| binomial.mass(t))
+                    .map(probability)
                     .take_while(|a| *a < threshold)
                     .count();
 
                 // Get all numbers start to the tail threshold
                 let mut distribution: Array1<f64> = (0..=amount - tail)
-                    .map(|t| binomial.mass(t))
+                    .map(probability)
                     .flat_map(|a| {
                         // Interweave the probability of this isotope with the mass difference to generate the correct distribution
                         std::iter::once(a)
@@ -138,3 +245,55 @@ fn memoized_f64_factorial(num: u16, cache: &mut HashMap<u16, f64>) -> f64 {
 fn stupid_f64_factorial(num: u16) -> f64 {
     (2..=num).fold(1.0, |acc, i| acc * f64::from(i))
 }
+
+#[cfg(test)]
+#[allow(clippy::missing_panics_doc)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn natural_table() {
+        let formula = molecular_formula!(C 6 H 12 O 6);
+        assert_eq!(
+            formula.isotopic_distribution(0.0001),
+            formula.isotopic_distribution_with(&IsotopeTable::default(), 0.0001)
+        );
+        assert_eq!(
+            formula.average_weight(),
+            formula.average_weight_with(&IsotopeTable::default())
+        );
+    }
+
+    #[test]
+    fn enriched_carbon() {
+        let mut table = IsotopeTable::default();
+        assert!(table.set_abundances(Element::C, &[(12, 50.0), (13, 50.0)]));
+        assert!(!table.clone().set_abundances(Element::C, &[(40, 1.0)]));
+        let distribution = molecular_formula!(C 2).isotopic_distribution_with(&table, 0.0);
+        assert_eq!(distribution.len(), 3);
+        assert!((distribution[0] - 0.25).abs() < 1e-9);
+        assert!((distribution[1] - 0.5).abs() < 1e-9);
+        assert!((distribution[2] - 0.25).abs() < 1e-9);
+        let mass = |n: u16| Element::C.mass(std::num::NonZeroU16::new(n)).unwrap().value;
+        let expected = (mass(12) + mass(13)) / 2.0;
+        assert!(
+            (molecular_formula!(C 1).average_weight_with(&table).value - expected).abs() < 1e-9
+        );
+        // Fully labelled, so the first peak is empty
+        assert!(table.set_abundances(Element::C, &[(13, 1.0)]));
+        let distribution = molecular_formula!(C 2).isotopic_distribution_with(&table, 0.0);
+        assert!(distribution[0].abs() < 1e-9);
+        assert!((distribution[2] - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn most_abundant_mass() {
+        // A ~10 kDa protein, the monoisotopic peak is not the most abundant peak
+        let formula = molecular_formula!(C 444 H 690 N 122 O 134 S 3);
+        let offset = (formula.most_abundant_mass() - formula.monoisotopic_mass()).value;
+        assert!((offset - 6.0).abs() < f64::EPSILON, "{offset}");
+        // For small molecules this is the monoisotopic peak
+        let glucose = molecular_formula!(C 6 H 12 O 6);
+        assert_eq!(glucose.most_abundant_mass(), glucose.monoisotopic_mass());
+    }
+}
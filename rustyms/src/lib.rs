@@ -72,7 +72,9 @@ mod tolerance;
 pub use crate::element::*;
 pub use crate::formula::*;
 pub use crate::isobaric_sets::{building_blocks, find_isobaric_sets};
-pub use crate::mass_mode::MassMode;
+#[cfg(feature = "isotopes")]
+pub use crate::isotopes::IsotopeTable;
+pub use crate::mass_mode::{IonMode, MassMode};
 pub use crate::model::Model;
 pub use crate::modification::{CrossLinkName, Modification};
 pub use crate::molecular_charge::MolecularCharge;
@@ -86,6 +88,7 @@ pub use crate::protease::*;
 pub use crate::sequence_element::SequenceElement;
 pub use crate::sequence_position::*;
 pub use crate::spectrum::{AnnotatableSpectrum, AnnotatedSpectrum, RawSpectrum};
+pub use crate::system::helpers::{charge, da, mz};
 pub use crate::tolerance::*;
 pub use aminoacids::AminoAcid;
 pub use checked_aminoacid::CheckedAminoAcid;
@@ -120,7 +123,7 @@ mod test {
         let peptide = CompoundPeptidoform::pro_forma("WFWF", None).unwrap();
         let fragments = peptide
             .generate_theoretical_fragments(system::usize::Charge::new::<system::e>(1), &model);
-        let annotated = spectrum[0].annotate(peptide, &fragments, &model, MassMode::Monoisotopic);
+        let annotated = spectrum[0].annotate(peptide, &fragments, &model);
         println!("{annotated:?}");
     }
 }
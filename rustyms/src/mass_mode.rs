@@ -17,3 +17,16 @@ pub enum MassMode {
     /// Only available with crate feature 'isotopes'.
     MostAbundant,
 }
+
+/// The ionisation mode, determines if the charge is gained by adding or removing protons, see
+/// [`MolecularFormula::mz`](crate::MolecularFormula::mz).
+#[derive(
+    Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default, Debug, Serialize, Deserialize,
+)]
+pub enum IonMode {
+    /// Positive mode, every charge is a proton that is added ([M+zH]z+)
+    #[default]
+    Positive,
+    /// Negative mode, every charge is a proton that is removed ([M-zH]z-)
+    Negative,
+}
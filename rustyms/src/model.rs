@@ -5,9 +5,9 @@ use std::ops::RangeInclusive;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    fragment::PeptidePosition,
+    fragment::{FragmentKind, PeptidePosition},
     system::{e, f64::MassOverCharge, isize::Charge, mz},
-    NeutralLoss, Tolerance,
+    MassMode, NeutralLoss, Tolerance,
 };
 
 /// Control what charges are allowed for an ion series. Defined as an inclusive range.
@@ -97,6 +97,10 @@ pub struct Model {
     pub y: PrimaryIonSeries,
     /// z series ions
     pub z: PrimaryIonSeries,
+    /// If the radical c· ions (c-1) should be generated alongside the c ions
+    pub c_radical: bool,
+    /// If the radical z· ions (z+1) should be generated alongside the z ions
+    pub z_radical: bool,
     /// precursor ions
     pub precursor: (Vec<NeutralLoss>, ChargeRange),
     /// immonium ions
@@ -115,6 +119,29 @@ pub struct Model {
     pub tolerance: Tolerance<MassOverCharge>,
     /// The range in which fragments fall, can be used to limit the theoretical fragments to a known window
     pub mz_range: RangeInclusive<MassOverCharge>,
+    /// The absolute charges that are allowed for any fragment, this is applied on top of the
+    /// charge ranges for the separate ion series, any charge outside of this range is never generated
+    pub fragment_charge_range: RangeInclusive<usize>,
+    /// The number of isotope peaks (M+1, M+2, etc) to generate for every fragment, on top of the
+    /// monoisotopic fragment, see [`crate::Fragment::with_isotope`]
+    pub fragment_isotopes: usize,
+    /// The minimal number of residues in a fragment from any of the primary ion series (a, b, c,
+    /// d, v, w, x, y, and z), shorter fragments (eg b1 and y1 for a value of 2) are not generated
+    pub min_fragment_length: usize,
+    /// The maximal number of combinations of placements of ambiguous modifications that are
+    /// considered for each fragment, any further combinations are ignored. This limits the time
+    /// and memory needed for peptides with many ambiguous modifications.
+    pub max_ambiguous_combinations: usize,
+    /// If a fragment is annotated on every peak within the tolerance, instead of only on the
+    /// closest peak, so all competing annotations for a peak can be reviewed
+    pub allow_multiple_annotations: bool,
+    /// The mass mode used to calculate the m/z of fragments and the precursor, use
+    /// [`MassMode::Average`] for low resolution data where the isotopes are not resolved
+    pub mass_mode: MassMode,
+    /// If the water and ammonia losses of the primary ion series (a, b, c, d, v, w, x, y, and z)
+    /// are only generated for fragments that contain a residue that can lose them, see
+    /// [`NeutralLoss::possible_from`]
+    pub chemically_aware_losses: bool,
 }
 
 /// The settings for any primary ion series
@@ -260,6 +287,8 @@ pub struct PossibleIons<'a> {
     pub y: (bool, &'a [NeutralLoss], ChargeRange),
     /// z series ions
     pub z: (bool, &'a [NeutralLoss], ChargeRange),
+    /// If the radical c· and z· ions should be generated
+    pub radical: (bool, bool),
     /// precursor ions
     pub precursor: (&'a [NeutralLoss], ChargeRange),
     /// immonium
@@ -271,13 +300,13 @@ impl<'a> PossibleIons<'a> {
     pub fn size_upper_bound(&self) -> usize {
         usize::from(self.a.0) * (self.a.1.len() + 1)
             + usize::from(self.b.0) * (self.b.1.len() + 1)
-            + usize::from(self.c.0) * (self.c.1.len() + 1)
+            + usize::from(self.c.0) * (1 + usize::from(self.radical.0)) * (self.c.1.len() + 1)
             + usize::from(self.d.0) * 2 * (self.d.1.len() + 1)
             + usize::from(self.v.0) * (self.v.1.len() + 1)
             + usize::from(self.w.0) * 2 * (self.w.1.len() + 1)
             + usize::from(self.x.0) * (self.x.1.len() + 1)
             + usize::from(self.y.0) * (self.y.1.len() + 1)
-            + usize::from(self.z.0) * 2 * (self.z.1.len() + 1)
+            + usize::from(self.z.0) * (1 + usize::from(self.radical.1)) * (self.z.1.len() + 1)
             + self.precursor.0.len()
             + 1
     }
@@ -330,6 +359,16 @@ impl Model {
     pub fn z(self, z: PrimaryIonSeries) -> Self {
         Self { z, ..self }
     }
+    /// Set if the radical c· ions should be generated
+    #[must_use]
+    pub fn c_radical(self, c_radical: bool) -> Self {
+        Self { c_radical, ..self }
+    }
+    /// Set if the radical z· ions should be generated
+    #[must_use]
+    pub fn z_radical(self, z_radical: bool) -> Self {
+        Self { z_radical, ..self }
+    }
     /// Set glycan
     #[must_use]
     pub fn glycan(self, glycan: GlycanModel) -> Self {
@@ -380,7 +419,7 @@ impl Model {
             ..self
         }
     }
-    /// Set the tolerance
+    /// Set the tolerance, a bare [`Ratio`](crate::system::Ratio) (eg a ppm value) is used as a relative tolerance
     #[must_use]
     pub fn tolerance(self, tolerance: impl Into<Tolerance<MassOverCharge>>) -> Self {
         Self {
@@ -393,58 +432,164 @@ impl Model {
     pub fn mz_range(self, mz_range: RangeInclusive<MassOverCharge>) -> Self {
         Self { mz_range, ..self }
     }
+    /// Set the fragment charge range
+    #[must_use]
+    pub fn fragment_charge_range(self, fragment_charge_range: RangeInclusive<usize>) -> Self {
+        Self {
+            fragment_charge_range,
+            ..self
+        }
+    }
+    /// Set the number of isotope peaks to generate for every fragment
+    #[must_use]
+    pub fn fragment_isotopes(self, fragment_isotopes: usize) -> Self {
+        Self {
+            fragment_isotopes,
+            ..self
+        }
+    }
+    /// Set the minimal number of residues in a fragment from any of the primary ion series
+    #[must_use]
+    pub fn min_fragment_length(self, min_fragment_length: usize) -> Self {
+        Self {
+            min_fragment_length,
+            ..self
+        }
+    }
+    /// Set the maximal number of combinations of placements of ambiguous modifications
+    #[must_use]
+    pub fn max_ambiguous_combinations(self, max_ambiguous_combinations: usize) -> Self {
+        Self {
+            max_ambiguous_combinations,
+            ..self
+        }
+    }
+    /// Set if fragments are annotated on every peak within the tolerance instead of only on the closest peak
+    #[must_use]
+    pub fn allow_multiple_annotations(self, state: bool) -> Self {
+        Self {
+            allow_multiple_annotations: state,
+            ..self
+        }
+    }
+    /// Set the mass mode used to calculate the m/z of fragments and the precursor
+    #[must_use]
+    pub fn mass_mode(self, mass_mode: MassMode) -> Self {
+        Self { mass_mode, ..self }
+    }
+    /// Set if water and ammonia losses are only generated for fragments with a residue that can lose them
+    #[must_use]
+    pub fn chemically_aware_losses(self, state: bool) -> Self {
+        Self {
+            chemically_aware_losses: state,
+            ..self
+        }
+    }
+
+    /// Only generate b and y ions on all locations, all other primary ion series are turned off.
+    /// The neutral losses and charge ranges of the b and y series are kept.
+    #[must_use]
+    pub fn only_b_y(self) -> Self {
+        let mut model = self.without_primary_ions();
+        model.b.location = Location::All;
+        model.y.location = Location::All;
+        model
+    }
+    /// Only generate c and z ions on all locations, all other primary ion series are turned off.
+    /// The neutral losses and charge ranges of the c and z series are kept.
+    #[must_use]
+    pub fn only_c_z(self) -> Self {
+        let mut model = self.without_primary_ions();
+        model.c.location = Location::All;
+        model.z.location = Location::All;
+        model
+    }
+    /// Additionally generate a ions on all locations
+    #[must_use]
+    pub fn add_a_ions(self) -> Self {
+        Self {
+            a: self.a.location(Location::All),
+            ..self
+        }
+    }
+    /// Additionally generate x ions on all locations
+    #[must_use]
+    pub fn add_x_ions(self) -> Self {
+        Self {
+            x: self.x.location(Location::All),
+            ..self
+        }
+    }
+    /// Turn off all primary ion series
+    fn without_primary_ions(self) -> Self {
+        Self {
+            a: self.a.location(Location::None),
+            b: self.b.location(Location::None),
+            c: self.c.location(Location::None),
+            d: self.d.location(Location::None),
+            v: self.v.location(Location::None),
+            w: self.w.location(Location::None),
+            x: self.x.location(Location::None),
+            y: self.y.location(Location::None),
+            z: self.z.location(Location::None),
+            ..self
+        }
+    }
 }
 
 impl Model {
     /// Give all possible ions for the given N position
     pub fn ions(&self, position: PeptidePosition) -> PossibleIons {
         let c_position = position.flip_terminal();
+        let n_long_enough = position.series_number >= self.min_fragment_length;
+        let c_long_enough = c_position.series_number >= self.min_fragment_length;
         PossibleIons {
             a: (
-                self.a.location.possible(position),
+                n_long_enough && self.a.location.possible(position),
                 self.a.neutral_losses.as_slice(),
                 self.a.charge_range,
             ),
             b: (
-                self.b.location.possible(position),
+                n_long_enough && self.b.location.possible(position),
                 self.b.neutral_losses.as_slice(),
                 self.b.charge_range,
             ),
             c: (
-                self.c.location.possible(position),
+                n_long_enough && self.c.location.possible(position),
                 self.c.neutral_losses.as_slice(),
                 self.c.charge_range,
             ),
             d: (
-                self.d.location.possible(position),
+                n_long_enough && self.d.location.possible(position),
                 self.d.neutral_losses.as_slice(),
                 self.d.charge_range,
             ),
             v: (
-                self.v.location.possible(c_position),
+                c_long_enough && self.v.location.possible(c_position),
                 self.v.neutral_losses.as_slice(),
                 self.v.charge_range,
             ),
             w: (
-                self.w.location.possible(c_position),
+                c_long_enough && self.w.location.possible(c_position),
                 self.w.neutral_losses.as_slice(),
                 self.w.charge_range,
             ),
             x: (
-                self.x.location.possible(c_position),
+                c_long_enough && self.x.location.possible(c_position),
                 self.x.neutral_losses.as_slice(),
                 self.x.charge_range,
             ),
             y: (
-                self.y.location.possible(c_position),
+                c_long_enough && self.y.location.possible(c_position),
                 self.y.neutral_losses.as_slice(),
                 self.y.charge_range,
             ),
             z: (
-                self.z.location.possible(c_position),
+                c_long_enough && self.z.location.possible(c_position),
                 self.z.neutral_losses.as_slice(),
                 self.z.charge_range,
             ),
+            radical: (self.c_radical, self.z_radical),
             precursor: (self.precursor.0.as_slice(), self.precursor.1),
             immonium: self.immonium,
         }
@@ -471,6 +616,8 @@ impl Model {
                 .neutral_losses(vec![NeutralLoss::Loss(molecular_formula!(H 2 O 1))]),
             z: PrimaryIonSeries::default()
                 .neutral_losses(vec![NeutralLoss::Loss(molecular_formula!(H 2 O 1))]),
+            c_radical: true,
+            z_radical: true,
             precursor: (
                 vec![NeutralLoss::Loss(molecular_formula!(H 2 O 1))],
                 ChargeRange::PRECURSOR,
@@ -484,6 +631,13 @@ impl Model {
             allow_cross_link_cleavage: true,
             tolerance: Tolerance::new_ppm(20.0),
             mz_range: MassOverCharge::new::<mz>(0.0)..=MassOverCharge::new::<mz>(f64::MAX),
+            fragment_charge_range: 1..=usize::MAX,
+            fragment_isotopes: 0,
+            min_fragment_length: 1,
+            max_ambiguous_combinations: 65_536,
+            allow_multiple_annotations: false,
+            mass_mode: MassMode::Monoisotopic,
+            chemically_aware_losses: false,
         }
     }
 
@@ -499,6 +653,8 @@ impl Model {
             x: PrimaryIonSeries::default().location(Location::None),
             y: PrimaryIonSeries::default().location(Location::None),
             z: PrimaryIonSeries::default().location(Location::None),
+            c_radical: false,
+            z_radical: true,
             precursor: (vec![], ChargeRange::PRECURSOR),
             immonium: (false, ChargeRange::ONE),
             m: false,
@@ -508,6 +664,40 @@ impl Model {
             allow_cross_link_cleavage: false,
             tolerance: Tolerance::new_ppm(20.0),
             mz_range: MassOverCharge::new::<mz>(0.0)..=MassOverCharge::new::<mz>(f64::MAX),
+            fragment_charge_range: 1..=usize::MAX,
+            fragment_isotopes: 0,
+            min_fragment_length: 1,
+            max_ambiguous_combinations: 65_536,
+            allow_multiple_annotations: false,
+            mass_mode: MassMode::Monoisotopic,
+            chemically_aware_losses: false,
+        }
+    }
+
+    /// Generate only the given fragment series (and the precursor), all other settings are the
+    /// same as [`Self::none`]. For the primary ion series (a, b, c, d, v, w, x, y, and z) the
+    /// series is generated on the given location with the default neutral losses and charge
+    /// range. For the other kinds the location is ignored and the respective setting is turned on.
+    pub fn single(series: FragmentKind, location: Location) -> Self {
+        let primary = PrimaryIonSeries::default().location(location);
+        let none = Self::none();
+        match series {
+            FragmentKind::a => none.a(primary),
+            FragmentKind::b => none.b(primary),
+            FragmentKind::c => none.c(primary),
+            FragmentKind::d => none.d(primary),
+            FragmentKind::v => none.v(primary),
+            FragmentKind::w => none.w(primary),
+            FragmentKind::x => none.x(primary),
+            FragmentKind::y => none.y(primary),
+            FragmentKind::z => none.z(primary),
+            FragmentKind::Y | FragmentKind::Oxonium => none.glycan(GlycanModel::ALLOW),
+            FragmentKind::immonium => none.immonium((true, ChargeRange::ONE)),
+            FragmentKind::m => none.m(true),
+            FragmentKind::diagnostic => {
+                none.modification_specific_diagnostic_ions((true, ChargeRange::ONE))
+            }
+            FragmentKind::precursor => none,
         }
     }
 
@@ -528,6 +718,8 @@ impl Model {
                 .neutral_losses(vec![NeutralLoss::Loss(molecular_formula!(H 2 O 1))]),
             z: PrimaryIonSeries::default()
                 .neutral_losses(vec![NeutralLoss::Loss(molecular_formula!(H 2 O 1))]),
+            c_radical: false,
+            z_radical: true,
             precursor: (
                 vec![NeutralLoss::Loss(molecular_formula!(H 2 O 1))],
                 ChargeRange {
@@ -544,6 +736,13 @@ impl Model {
             allow_cross_link_cleavage: true,
             tolerance: Tolerance::new_ppm(20.0),
             mz_range: MassOverCharge::new::<mz>(0.0)..=MassOverCharge::new::<mz>(f64::MAX),
+            fragment_charge_range: 1..=usize::MAX,
+            fragment_isotopes: 0,
+            min_fragment_length: 1,
+            max_ambiguous_combinations: 65_536,
+            allow_multiple_annotations: false,
+            mass_mode: MassMode::Monoisotopic,
+            chemically_aware_losses: false,
         }
     }
 
@@ -565,6 +764,8 @@ impl Model {
             y: PrimaryIonSeries::default()
                 .neutral_losses(vec![NeutralLoss::Loss(molecular_formula!(H 2 O 1))]),
             z: PrimaryIonSeries::default().location(Location::None),
+            c_radical: false,
+            z_radical: true,
             precursor: (
                 vec![NeutralLoss::Loss(molecular_formula!(H 2 O 1))],
                 ChargeRange::PRECURSOR,
@@ -577,6 +778,13 @@ impl Model {
             allow_cross_link_cleavage: true,
             tolerance: Tolerance::new_ppm(20.0),
             mz_range: MassOverCharge::new::<mz>(0.0)..=MassOverCharge::new::<mz>(f64::MAX),
+            fragment_charge_range: 1..=usize::MAX,
+            fragment_isotopes: 0,
+            min_fragment_length: 1,
+            max_ambiguous_combinations: 65_536,
+            allow_multiple_annotations: false,
+            mass_mode: MassMode::Monoisotopic,
+            chemically_aware_losses: false,
         }
     }
 
@@ -595,6 +803,8 @@ impl Model {
                 .neutral_losses(vec![NeutralLoss::Loss(molecular_formula!(H 2 O 1))]),
             z: PrimaryIonSeries::default()
                 .neutral_losses(vec![NeutralLoss::Loss(molecular_formula!(H 2 O 1))]),
+            c_radical: false,
+            z_radical: true,
             precursor: (
                 vec![
                     NeutralLoss::Loss(molecular_formula!(H 2 O 1)),
@@ -616,29 +826,43 @@ impl Model {
             allow_cross_link_cleavage: true,
             tolerance: Tolerance::new_ppm(20.0),
             mz_range: MassOverCharge::new::<mz>(0.0)..=MassOverCharge::new::<mz>(f64::MAX),
+            fragment_charge_range: 1..=usize::MAX,
+            fragment_isotopes: 0,
+            min_fragment_length: 1,
+            max_ambiguous_combinations: 65_536,
+            allow_multiple_annotations: false,
+            mass_mode: MassMode::Monoisotopic,
+            chemically_aware_losses: false,
         }
     }
 }
 
-/// A location, or range of locations where an ion can be generated
+/// A location, or range of locations where an ion can be generated.
+///
+/// The locations are defined on the amino acids of the peptide and are interpreted in the same
+/// way for N and C terminal ion series. An N terminal ion (a/b/c/d) generated at an amino acid
+/// contains that amino acid and all amino acids before it, while a C terminal ion (v/w/x/y/z)
+/// generated at an amino acid contains that amino acid and all amino acids after it. So for
+/// `PEPTIDE` with [`Location::SkipN(1)`](Location::SkipN) the b1 and y7 ions are not generated,
+/// while with [`Location::SkipC(1)`](Location::SkipC) the b7 and y1 ions are not generated.
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default, Debug, Serialize, Deserialize)]
 pub enum Location {
-    /// Skip the given number from the N terminal side
+    /// Skip the given number of amino acids from the N terminal side
     SkipN(usize),
     /// Skip the given number of aminoacids from the N terminal and C terminal side respectively, only using the positions between these two
     SkipNC(usize, usize),
-    /// Skip a certain number and then take a certain number of aminoacids
+    /// Skip a certain number of amino acids from the N terminal side and then take a certain number of aminoacids
     TakeN {
         /// Skip this number of aminoacids
         skip: usize,
         /// Take this number of aminoacids
         take: usize,
     },
-    /// Skip a given number from the C terminal side
+    /// Skip a given number of amino acids from the C terminal side
     SkipC(usize),
     /// Take a given number of aminoacids from the C terminal side
     TakeC(usize),
-    /// All positions (including 0 and len-1)
+    /// All positions, except for the ions that would contain the full sequence (`b_len`, `y_len` etc)
     All,
     /// Do not allow it anywhere
     #[default]
@@ -678,3 +902,89 @@ fn location_all() {
     assert!(!ions_c0.a.0);
     assert!(ions_c0.x.0);
 }
+
+#[test]
+#[allow(clippy::missing_panics_doc)]
+fn location_variants() {
+    // For each location give the sequence indices on a peptide of length 5 where an N and C terminal ion is generated
+    let possible = |location: Location| {
+        (0..5)
+            .map(|i| {
+                let n = PeptidePosition::n(crate::SequencePosition::Index(i), 5);
+                (location.possible(n), location.possible(n.flip_terminal()))
+            })
+            .collect::<Vec<_>>()
+    };
+    let both = |indices: &[usize]| {
+        (0..5)
+            .map(|i| (indices.contains(&i), indices.contains(&i)))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(possible(Location::SkipN(1)), both(&[1, 2, 3, 4]));
+    assert_eq!(possible(Location::SkipC(1)), both(&[0, 1, 2, 3]));
+    assert_eq!(possible(Location::SkipNC(1, 2)), both(&[1, 2]));
+    assert_eq!(
+        possible(Location::TakeN { skip: 1, take: 2 }),
+        both(&[1, 2])
+    );
+    assert_eq!(possible(Location::TakeC(2)), both(&[3, 4]));
+    assert_eq!(possible(Location::None), both(&[]));
+    // All skips the ions containing the full sequence: b5 (index 4) and y5 (index 0)
+    assert_eq!(
+        possible(Location::All),
+        vec![
+            (true, false),
+            (true, true),
+            (true, true),
+            (true, true),
+            (false, true)
+        ]
+    );
+}
+
+#[test]
+#[allow(clippy::missing_panics_doc)]
+fn model_series_helpers() {
+    let position = PeptidePosition::n(crate::SequencePosition::Index(1), 4);
+    let ions = |model: &Model| {
+        let ions = model.ions(position);
+        [
+            ions.a.0, ions.b.0, ions.c.0, ions.d.0, ions.v.0, ions.w.0, ions.x.0, ions.y.0,
+            ions.z.0,
+        ]
+    };
+    let b_y = Model::all().only_b_y();
+    assert_eq!(
+        ions(&b_y),
+        [false, true, false, false, false, false, false, true, false]
+    );
+    assert_eq!(b_y.b.neutral_losses, Model::all().b.neutral_losses);
+    assert_eq!(
+        ions(&b_y.add_a_ions()),
+        [true, true, false, false, false, false, false, true, false]
+    );
+    assert_eq!(
+        ions(&Model::none().only_c_z().add_x_ions()),
+        [false, false, true, false, false, false, true, false, true]
+    );
+}
+
+#[test]
+#[allow(clippy::missing_panics_doc)]
+fn single_series() {
+    assert_eq!(
+        Model::single(FragmentKind::a, Location::All),
+        Model::none().a(PrimaryIonSeries::default())
+    );
+    assert_eq!(
+        Model::single(FragmentKind::y, Location::SkipN(1)),
+        Model::none().y(PrimaryIonSeries::default().location(Location::SkipN(1)))
+    );
+    let immonium = Model::single(FragmentKind::immonium, Location::SkipN(1));
+    assert!(immonium.immonium.0);
+    assert_eq!(immonium.a.location, Location::None);
+    assert_eq!(
+        Model::single(FragmentKind::precursor, Location::All),
+        Model::none()
+    );
+}
@@ -11,10 +11,12 @@ use std::{
 };
 
 use crate::{
+    fragment::PeptidePosition,
     glycan::{GlycanStructure, MonoSaccharide},
+    helper_functions::escape,
     molecular_charge::CachedCharge,
     ontologies::CustomDatabase,
-    peptide::Linked,
+    peptide::{Linked, SemiAmbiguous},
     placement_rule::PlacementRule,
     system::{Mass, OrderedMass},
     AmbiguousLabel, AminoAcid, Chemical, DiagnosticIon, Fragment, LinearPeptide, Model,
@@ -24,6 +26,19 @@ use crate::{
 
 include!("shared/modification.rs");
 
+/// The characters that have to be escaped in names when displaying modifications as ProForma
+const ESCAPED_CHARACTERS: &[char] = &['[', ']', '|', '#'];
+
+/// Write the given `INFO` tags as ProForma, to be placed directly after the modification.
+/// # Errors
+/// When the given writer errors.
+pub(crate) fn display_info_tags(f: &mut impl Write, tags: &[String]) -> std::fmt::Result {
+    for tag in tags {
+        write!(f, "|INFO:{}", escape(tag, ESCAPED_CHARACTERS))?;
+    }
+    Ok(())
+}
+
 impl ModificationId {
     /// Get the accession number name for the ontology
     pub fn url(&self) -> Option<String> {
@@ -104,7 +119,8 @@ impl Chemical for SimpleModification {
         match self {
             Self::Mass(m) => MolecularFormula::with_additional_mass(m.value),
             Self::Formula(elements) => elements.clone(),
-            Self::Glycan(monosaccharides) => monosaccharides
+            Self::Glycan(monosaccharides)
+            | Self::Gno(GnoComposition::Composition(monosaccharides), _) => monosaccharides
                 .iter()
                 .fold(MolecularFormula::default(), |acc, i| {
                     acc + i.0.formula_inner(position, peptide_index) * i.1 as i32
@@ -142,7 +158,8 @@ impl SimpleModification {
         match self {
             Self::Mass(m) => MolecularFormula::with_additional_mass(m.value),
             Self::Formula(elements) => elements.clone(),
-            Self::Glycan(monosaccharides) => monosaccharides
+            Self::Glycan(monosaccharides)
+            | Self::Gno(GnoComposition::Composition(monosaccharides), _) => monosaccharides
                 .iter()
                 .fold(MolecularFormula::default(), |acc, i| {
                     acc + i.0.formula_inner(sequence_index, peptide_index) * i.1 as i32
@@ -209,6 +226,43 @@ impl SimpleModification {
         }
     }
 
+    /// Get all placement rules for this modification. Only modifications from ontologies and
+    /// cross-linkers have placement rules, for all other modifications this is empty meaning that
+    /// they can be placed anywhere. For cross-linkers the rules for both sides are included.
+    pub fn allowed_positions(&self) -> Vec<PlacementRule> {
+        match self {
+            Self::Database { specificities, .. } => specificities
+                .iter()
+                .flat_map(|(rules, _, _)| rules.iter().cloned())
+                .unique()
+                .collect(),
+            Self::Linker { specificities, .. } => specificities
+                .iter()
+                .flat_map(|spec| match spec {
+                    LinkerSpecificity::Symmetric(rules, _, _) => rules.clone(),
+                    LinkerSpecificity::Asymmetric((rules_left, rules_right), _, _) => rules_left
+                        .iter()
+                        .chain(rules_right.iter())
+                        .cloned()
+                        .collect(),
+                })
+                .unique()
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Check if this modification can be placed on the given amino acid at the given position.
+    /// Rules that depend on other modifications being present on the same residue
+    /// ([`PlacementRule::PsiModification`]) never match as the amino acid is considered unmodified.
+    pub fn can_place_on(&self, aminoacid: AminoAcid, position: &PeptidePosition) -> bool {
+        self.is_possible(
+            &SequenceElement::<SemiAmbiguous>::new(aminoacid.into(), None),
+            position.sequence_index,
+        )
+        .any_possible()
+    }
+
     /// Display a modification either normalised to the internal representation or as fully valid ProForma
     /// (no glycan structure or custom modifications).
     /// # Errors
@@ -250,7 +304,11 @@ impl SimpleModification {
                     },
                 ..
             } if specification_compliant => {
-                write!(f, "Formula:{formula}|INFO:Custom:{name}")?;
+                write!(
+                    f,
+                    "Formula:{formula}|INFO:Custom:{}",
+                    escape(name, ESCAPED_CHARACTERS)
+                )?;
             }
             Self::Database {
                 id:
@@ -261,13 +319,22 @@ impl SimpleModification {
                     },
                 ..
             } if specification_compliant => {
-                write!(f, "C:{name}")?;
+                write!(f, "C:{}", escape(name, ESCAPED_CHARACTERS))?;
             }
-            Self::Database { id, .. } => {
-                write!(f, "{}:{}", id.ontology.char(), id.name)?;
+            Self::Database { id, .. } | Self::Linker { id, .. } => {
+                write!(
+                    f,
+                    "{}:{}",
+                    id.ontology.char(),
+                    escape(&id.name, ESCAPED_CHARACTERS)
+                )?;
             }
-            Self::Gno(_, name) => write!(f, "{}:{name}", Ontology::Gnome.char())?,
-            Self::Linker { id, .. } => write!(f, "{}:{}", id.ontology.char(), id.name)?,
+            Self::Gno(_, name) => write!(
+                f,
+                "{}:{}",
+                Ontology::Gnome.char(),
+                escape(name, ESCAPED_CHARACTERS)
+            )?,
         }
         Ok(())
     }
@@ -538,6 +605,24 @@ impl Modification {
             })
     }
 
+    /// Get all placement rules for this modification, see [`SimpleModification::allowed_positions`].
+    pub fn allowed_positions(&self) -> Vec<PlacementRule> {
+        match self {
+            Self::Simple(modification) => modification.allowed_positions(),
+            Self::CrossLink { linker, .. } => linker.allowed_positions(),
+        }
+    }
+
+    /// Check if this modification can be placed on the given amino acid at the given position,
+    /// see [`SimpleModification::can_place_on`].
+    pub fn can_place_on(&self, aminoacid: AminoAcid, position: &PeptidePosition) -> bool {
+        self.is_possible(
+            &SequenceElement::<SemiAmbiguous>::new(aminoacid.into(), None),
+            position.sequence_index,
+        )
+        .any_possible()
+    }
+
     /// Generate theoretical fragments for side chains (glycans)
     pub(crate) fn generate_theoretical_fragments(
         &self,
@@ -622,13 +707,15 @@ impl SimpleModification {
                     Ontology::Gnome
                         .lookup(custom_database)
                         .iter()
-                        .filter(|(_, _, m)| {
-                            if let Self::Gno(GnoComposition::Structure(structure), _) = m {
+                        .filter(|(_, _, m)| match m {
+                            Self::Gno(GnoComposition::Structure(structure), _) => {
                                 MonoSaccharide::search_composition(structure.composition())
                                     == *search
-                            } else {
-                                false
                             }
+                            Self::Gno(GnoComposition::Composition(composition), _) => {
+                                MonoSaccharide::search_composition(composition.clone()) == *search
+                            }
+                            _ => false,
                         })
                         .map(|(i, n, m)| (Ontology::Gnome, *i, n.clone(), m.clone()))
                         .collect(),
@@ -672,7 +759,9 @@ impl SimpleModification {
                     full_formula,
                     attachment,
                 )
-        } else if let Self::Glycan(composition) = self {
+        } else if let Self::Glycan(composition)
+        | Self::Gno(GnoComposition::Composition(composition), _) = self
+        {
             MonoSaccharide::theoretical_fragments(
                 composition,
                 model,
@@ -716,7 +805,7 @@ pub type AmbiguousLookup = Vec<(String, Option<SimpleModification>)>;
 pub type CrossLinkLookup = Vec<(CrossLinkName, Option<SimpleModification>)>;
 
 /// An ambiguous modification which could be placed on any of a set of locations
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AmbiguousModification {
     /// The id to compare be able to find the other locations where this modifications can be placed
     pub id: usize,
@@ -728,6 +817,66 @@ pub struct AmbiguousModification {
     pub group: String,
     /// If this is the preferred location or not
     pub preferred: bool,
+    /// The `INFO` tags given with this modification, these are not taken into account when
+    /// comparing ambiguous modifications
+    #[serde(default)]
+    pub info_tags: Vec<String>,
+}
+
+impl PartialEq for AmbiguousModification {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+            && self.modification == other.modification
+            && self.localisation_score == other.localisation_score
+            && self.group == other.group
+            && self.preferred == other.preferred
+    }
+}
+
+impl Eq for AmbiguousModification {}
+
+impl PartialOrd for AmbiguousModification {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for AmbiguousModification {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.id
+            .cmp(&other.id)
+            .then_with(|| self.modification.cmp(&other.modification))
+            .then_with(|| self.localisation_score.cmp(&other.localisation_score))
+            .then_with(|| self.group.cmp(&other.group))
+            .then_with(|| self.preferred.cmp(&other.preferred))
+    }
+}
+
+impl std::hash::Hash for AmbiguousModification {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+        self.modification.hash(state);
+        self.localisation_score.hash(state);
+        self.group.hash(state);
+        self.preferred.hash(state);
+    }
+}
+
+/// A modification as placed on a peptide, see [`LinearPeptide::modifications`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PlacedModification<'a> {
+    /// A modification that is placed on exactly this position
+    Defined(&'a Modification),
+    /// An ambiguous modification, that could be placed on this position or any of the other
+    /// positions with the same id
+    Ambiguous(&'a AmbiguousModification),
+}
+
+impl PlacedModification<'_> {
+    /// Check if this is an ambiguous modification
+    pub const fn is_ambiguous(&self) -> bool {
+        matches!(self, Self::Ambiguous(_))
+    }
 }
 
 impl Chemical for AmbiguousModification {
@@ -1,4 +1,9 @@
-use std::{cmp::Ordering, collections::HashMap, hash::Hash};
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    hash::Hash,
+    ops::{Add, AddAssign, RangeInclusive},
+};
 
 use crate::{
     model::ChargeRange, system::isize::Charge, Chemical, Element, MolecularFormula,
@@ -11,6 +16,7 @@ pub struct CachedCharge {
     charge: MolecularCharge,
     options: HashMap<Charge, Vec<MolecularCharge>>,
     number: Charge,
+    limit: RangeInclusive<usize>,
 }
 
 impl CachedCharge {
@@ -25,11 +31,21 @@ impl CachedCharge {
             .or_insert_with(|| self.charge.options(charge))
     }
 
-    /// Get all options
+    /// Only allow charges within this absolute range to be generated
+    #[must_use]
+    pub fn limit(self, limit: RangeInclusive<usize>) -> Self {
+        Self { limit, ..self }
+    }
+
+    /// Get all options, the range is interpreted as absolute charges, so for negative charge
+    /// carriers it results in the negative charges with the same magnitude
     pub fn range(&mut self, range: ChargeRange) -> Vec<MolecularCharge> {
+        let sign = self.number.value.signum();
         let mut options = Vec::new();
-        for c in range.charges_iter(self.charge()) {
-            options.extend_from_slice(self.options(c));
+        for c in range.charges_iter(Charge::new::<crate::system::e>(self.number.value.abs())) {
+            if usize::try_from(c.value).is_ok_and(|c| self.limit.contains(&c)) {
+                options.extend_from_slice(self.options(c * sign));
+            }
         }
         options
     }
@@ -42,6 +58,7 @@ impl From<MolecularCharge> for CachedCharge {
             charge: value,
             options: HashMap::new(),
             number: n,
+            limit: 1..=usize::MAX,
         }
     }
 }
@@ -52,6 +69,7 @@ impl From<&MolecularCharge> for CachedCharge {
             charge: value.clone(),
             options: HashMap::new(),
             number: value.charge(),
+            limit: 1..=usize::MAX,
         }
     }
 }
@@ -85,14 +103,38 @@ impl MolecularCharge {
         }
     }
 
-    /// Get all options resulting in this exact charge
+    /// Create a charge state with a single adduct ion occurring `count` times. The formula is the
+    /// full formula for the adduct ion, so including the missing or gained electrons that give the
+    /// adduct its charge, eg sodium (Na+) is `molecular_formula!(Na 1 Electron -1)`. Multiple
+    /// adducts can be combined by adding the charge states together.
+    pub fn adduct(count: isize, formula: MolecularFormula) -> Self {
+        Self {
+            charge_carriers: vec![(count, formula)],
+        }
+    }
+
+    /// Get the total charge of these charge carriers as a plain number
+    pub fn total_charge(&self) -> isize {
+        self.charge().value
+    }
+
+    /// Get the net formula of all these charge carriers combined
+    pub fn net_formula(&self) -> MolecularFormula {
+        self.formula()
+    }
+
+    /// Get all options resulting in this exact charge. For negative charge carriers the charge
+    /// has to be negative as well.
     /// # Panics
-    /// If the charge is not at least 1.
+    /// If the charge is zero or does not have the same sign as the charge carriers.
     pub fn options(&self, charge: Charge) -> Vec<Self> {
-        assert!(charge.value > 0);
-        let own_charge = self.charge();
-        let remainder = charge.value.rem_euclid(own_charge.value);
-        let quotient = charge.value.div_euclid(own_charge.value).max(0);
+        let sign = self.charge().value.signum();
+        assert!(charge.value != 0 && charge.value.signum() == sign);
+        // Work on the magnitude of the charge, so negative charge carriers are handled identically
+        let magnitude = |charge: Charge| charge.value * sign;
+        let own_charge = magnitude(self.charge());
+        let remainder = magnitude(charge).rem_euclid(own_charge);
+        let quotient = magnitude(charge).div_euclid(own_charge).max(0);
 
         let mut too_low_options: Vec<Vec<(isize, MolecularFormula)>> = Vec::new();
         let mut options = Vec::new();
@@ -101,7 +143,7 @@ impl MolecularCharge {
             if too_low_options.is_empty() {
                 for n in 0..=carrier.0 {
                     let charge = n * carrier.1.charge();
-                    match charge.value.cmp(&remainder) {
+                    match magnitude(charge).cmp(&remainder) {
                         Ordering::Less => new_too_low_options.push(vec![(n, carrier.1.clone())]),
                         Ordering::Equal => options.push(vec![(n, carrier.1.clone())]),
                         Ordering::Greater => (),
@@ -119,7 +161,7 @@ impl MolecularCharge {
                             });
 
                         let charge = n * carrier.1.charge() + full_charge;
-                        match charge.value.cmp(&remainder) {
+                        match magnitude(charge).cmp(&remainder) {
                             Ordering::Less => new_too_low_options.push(new),
                             Ordering::Equal => options.push(new),
                             Ordering::Greater => (),
@@ -190,6 +232,41 @@ impl Chemical for MolecularCharge {
     }
 }
 
+impl AddAssign<&Self> for MolecularCharge {
+    /// Combine the charge carriers, any adduct ion that occurs in both gets its count summed
+    fn add_assign(&mut self, rhs: &Self) {
+        for (count, formula) in &rhs.charge_carriers {
+            if let Some((amount, _)) = self
+                .charge_carriers
+                .iter_mut()
+                .find(|(_, f)| *f == *formula)
+            {
+                *amount += count;
+            } else {
+                self.charge_carriers.push((*count, formula.clone()));
+            }
+        }
+        self.charge_carriers.retain(|el| el.0 != 0);
+    }
+}
+
+impl AddAssign<Self> for MolecularCharge {
+    fn add_assign(&mut self, rhs: Self) {
+        *self += &rhs;
+    }
+}
+
+impl Add<&MolecularCharge> for &MolecularCharge {
+    type Output = MolecularCharge;
+    fn add(self, rhs: &MolecularCharge) -> Self::Output {
+        let mut result = self.clone();
+        result += rhs;
+        result
+    }
+}
+
+impl_binop_ref_cases!(impl Add, add for MolecularCharge, MolecularCharge, MolecularCharge);
+
 impl std::fmt::Display for MolecularCharge {
     /// Is not guaranteed to fully conform to the ProForma standard. Because the data structure accepts more than the standard.
     /// So adducts with other than +1/-1 charge states, or adducts with complex formula (not a single element) will not adhere to the standard.
@@ -229,7 +306,7 @@ impl std::fmt::Display for MolecularCharge {
 #[cfg(test)]
 #[allow(clippy::missing_panics_doc)]
 mod tests {
-    use crate::Chemical;
+    use crate::{Chemical, CompoundPeptidoform};
 
     use super::MolecularCharge;
 
@@ -240,4 +317,36 @@ mod tests {
         assert_eq!(options.len(), 1);
         assert_eq!(options[0].formula(), molecular_formula!(H 1 Electron -1));
     }
+
+    #[test]
+    fn negative_charge_options() {
+        let mc = MolecularCharge::new(&[(2, molecular_formula!(I 1 Electron 1))]);
+        let options = mc.options(crate::system::isize::Charge::new::<crate::system::e>(-1));
+        assert_eq!(options.len(), 1);
+        assert_eq!(options[0].formula(), molecular_formula!(I 1 Electron 1));
+        let options = mc.options(crate::system::isize::Charge::new::<crate::system::e>(-2));
+        assert_eq!(options.len(), 1);
+        assert_eq!(options[0].formula(), molecular_formula!(I 2 Electron 2));
+    }
+
+    #[test]
+    fn adducts() {
+        let sodium = MolecularCharge::adduct(2, molecular_formula!(Na 1 Electron -1));
+        let proton = MolecularCharge::adduct(-1, molecular_formula!(H 1 Electron -1));
+        let combined = &sodium + &proton;
+        assert_eq!(combined.total_charge(), 1);
+        assert_eq!(
+            combined.net_formula(),
+            molecular_formula!(Na 2 H -1 Electron -1)
+        );
+        let peptide = CompoundPeptidoform::pro_forma("EMEVEESPEK/1[+2Na+,-H+]", None)
+            .unwrap()
+            .singular_peptide()
+            .unwrap();
+        assert_eq!(peptide.get_charge_carriers(), Some(&combined));
+        assert_eq!(
+            combined + MolecularCharge::adduct(1, molecular_formula!(H 1 Electron -1)),
+            sodium
+        );
+    }
 }
@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 use crate::{
     error::{Context, CustomError},
     formula::MolecularFormula,
-    Multi,
+    AminoAcid, Multi,
 };
 
 include!("shared/neutral_loss.rs");
@@ -18,6 +18,34 @@ impl NeutralLoss {
         }
     }
 
+    /// Check if this neutral loss can originate from a fragment with the given residues. A loss of
+    /// water (H2O) needs a serine, threonine, aspartic acid, or glutamic acid and a loss of ammonia
+    /// (NH3) needs a lysine, arginine, asparagine, or glutamine. Any other loss or gain is always
+    /// possible.
+    pub fn possible_from(&self, mut residues: impl Iterator<Item = AminoAcid>) -> bool {
+        match self {
+            Self::Loss(formula) if *formula == molecular_formula!(H 2 O 1) => residues.any(|aa| {
+                matches!(
+                    aa,
+                    AminoAcid::Serine
+                        | AminoAcid::Threonine
+                        | AminoAcid::AsparticAcid
+                        | AminoAcid::GlutamicAcid
+                )
+            }),
+            Self::Loss(formula) if *formula == molecular_formula!(H 3 N 1) => residues.any(|aa| {
+                matches!(
+                    aa,
+                    AminoAcid::Lysine
+                        | AminoAcid::Arginine
+                        | AminoAcid::Asparagine
+                        | AminoAcid::Glutamine
+                )
+            }),
+            _ => true,
+        }
+    }
+
     /// Generate a nice HTML notation for this `NeutralLoss`
     pub fn hill_notation_html(&self) -> String {
         match self {
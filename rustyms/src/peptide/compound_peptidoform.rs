@@ -4,8 +4,11 @@ use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    peptide::Linked, system::usize::Charge, Fragment, LinearPeptide, Model, MolecularFormula,
-    Multi, Peptidoform,
+    modification::PlacedModification,
+    peptide::{Linear, Linked},
+    system::usize::Charge,
+    Chemical, Fragment, LinearPeptide, Model, Modification, MolecularFormula, Multi, Peptidoform,
+    Protease, SequencePosition,
 };
 
 /// A single full ProForma entry. This entry can contain multiple sets of cross-linked peptides.
@@ -54,6 +57,72 @@ impl CompoundPeptidoform {
         &self.0
     }
 
+    /// Get all peptides making up this compound peptidoform, from all peptidoforms.
+    pub fn peptides(&self) -> impl Iterator<Item = &LinearPeptide<Linked>> {
+        self.0.iter().flat_map(Peptidoform::peptides)
+    }
+
+    /// Get all cross-links (and branches) in this compound peptidoform, both links between
+    /// different peptides and links within a single peptide. Every link is given once as the two
+    /// linked positions and the formula of the linker. A position is given as the index of the
+    /// peptide in [`Self::peptides`] and the position in that peptide. The links are sorted on the
+    /// first position, which is always the lowest of the two positions.
+    #[allow(clippy::type_complexity)]
+    pub fn cross_links(
+        &self,
+    ) -> Vec<(
+        (usize, SequencePosition),
+        (usize, SequencePosition),
+        MolecularFormula,
+    )> {
+        let mut result = Vec::new();
+        let mut offset = 0;
+        for peptidoform in &self.0 {
+            for (peptide_index, peptide) in peptidoform.peptides().iter().enumerate() {
+                for (position, modification) in peptide.modifications() {
+                    if let PlacedModification::Defined(Modification::CrossLink {
+                        peptide: other_peptide,
+                        sequence_index,
+                        linker,
+                        ..
+                    }) = modification
+                    {
+                        // Cross-links are stored on both sides, only report them from the first
+                        if (peptide_index, position) <= (*other_peptide, *sequence_index) {
+                            result.push((
+                                (offset + peptide_index, position),
+                                (offset + other_peptide, *sequence_index),
+                                linker.formula(),
+                            ));
+                        }
+                    }
+                }
+            }
+            offset += peptidoform.peptides().len();
+        }
+        result.sort_by_key(|(a, b, _)| (*a, *b));
+        result
+    }
+
+    /// Digest all peptides in this compound peptidoform with the given protease and the given
+    /// maximal number of missed cleavages, see [`LinearPeptide::digest`]. All cross-links and
+    /// branches are dropped before digestion (see [`LinearPeptide::remove_cross_links`]), so the
+    /// resulting peptides do not contain the linker or the peptides they were linked to.
+    pub fn digest(
+        &self,
+        protease: &Protease,
+        max_missed_cleavages: usize,
+    ) -> Vec<LinearPeptide<Linear>> {
+        self.peptides()
+            .flat_map(|peptide| {
+                peptide
+                    .clone()
+                    .remove_cross_links()
+                    .digest(protease, max_missed_cleavages)
+            })
+            .collect()
+    }
+
     /// Generate the theoretical fragments for this compound peptidoform.
     pub fn generate_theoretical_fragments(
         &self,
@@ -82,12 +151,13 @@ impl CompoundPeptidoform {
             .flat_map(Peptidoform::peptides)
             .next()
             .map_or(empty.as_slice(), |p| p.get_global());
-        for (element, isotope) in global {
+        for (element, isotope, location) in global {
             write!(
                 f,
-                "<{}{}>",
+                "<{}{}{}>",
                 isotope.map(|i| i.to_string()).unwrap_or_default(),
-                element
+                element,
+                location.map(|l| format!("@{l}")).unwrap_or_default()
             )?;
         }
 
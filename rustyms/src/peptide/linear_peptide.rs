@@ -2,19 +2,20 @@
 
 use crate::{
     checked_aminoacid::CheckedAminoAcid,
-    fragment::{DiagnosticPosition, Fragment, FragmentType, PeptidePosition},
+    fragment::{DiagnosticPosition, Fragment, FragmentKind, FragmentType, PeptidePosition},
     glycan::MonoSaccharide,
     helper_functions::RangeExtension,
+    model::Location,
     modification::{
-        AmbiguousModification, CrossLinkName, GnoComposition, LinkerSpecificity, Modification,
-        SimpleModification,
+        display_info_tags, AmbiguousModification, CrossLinkName, GnoComposition, LinkerSpecificity,
+        Modification, PlacedModification, SimpleModification,
     },
     molecular_charge::{CachedCharge, MolecularCharge},
     peptide::*,
     placement_rule::PlacementRule,
-    system::{dalton, usize::Charge, Mass},
-    AmbiguousLabel, Chemical, DiagnosticIon, Element, Model, MolecularFormula, Multi,
-    MultiChemical, NeutralLoss, Protease, SequenceElement, SequencePosition, Tolerance,
+    system::{da, dalton, usize::Charge, Mass, MassOverCharge},
+    AmbiguousLabel, AminoAcid, Chemical, DiagnosticIon, Element, MassMode, Model, MolecularFormula,
+    Multi, MultiChemical, NeutralLoss, Protease, SequenceElement, SequencePosition, Tolerance,
     WithinTolerance,
 };
 use itertools::Itertools;
@@ -25,7 +26,7 @@ use std::{
     fmt::{Display, Write},
     marker::PhantomData,
     num::NonZeroU16,
-    ops::{Index, IndexMut, RangeBounds},
+    ops::{Bound, Index, IndexMut, Range, RangeBounds, RangeInclusive},
     slice::SliceIndex,
 };
 
@@ -85,18 +86,25 @@ use std::{
 /// PEPTIDE/3[1Zn+2,1H+1]
 /// ```
 ///
-#[derive(PartialOrd, Ord, Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct LinearPeptide<Complexity> {
     /// Global isotope modifications, saved as the element and the species that
     /// all occurrence of that element will consist of. For example (N, 15) will
-    /// make all occurring nitrogen atoms be isotope 15.
-    global: Vec<(Element, Option<NonZeroU16>)>,
+    /// make all occurring nitrogen atoms be isotope 15. If a location is given only
+    /// the atoms on that location are replaced (`<D@backbone-amide>`).
+    global: Vec<(Element, Option<NonZeroU16>, Option<IsotopeLocation>)>,
     /// Labile modifications, which will not be found in the actual spectrum.
     labile: Vec<SimpleModification>,
     /// N terminal modification
     n_term: Option<Modification>,
     /// C terminal modification
     c_term: Option<Modification>,
+    /// The `INFO` tags given with the N terminal modification
+    #[serde(default)]
+    n_term_info_tags: Vec<String>,
+    /// The `INFO` tags given with the C terminal modification
+    #[serde(default)]
+    c_term_info_tags: Vec<String>,
     /// The sequence of this peptide (includes local modifications)
     sequence: Vec<SequenceElement<Complexity>>,
     /// For each ambiguous modification list all possible positions it can be placed on.
@@ -104,6 +112,14 @@ pub struct LinearPeptide<Complexity> {
     ambiguous_modifications: Vec<Vec<usize>>,
     /// The adduct ions, if specified
     charge_carriers: Option<MolecularCharge>,
+    /// If the N terminus of this peptide is not the N terminus of the protein it was taken from,
+    /// see [`Self::is_protein_n_term`].
+    #[serde(default)]
+    internal_n_term: bool,
+    /// If the C terminus of this peptide is not the C terminus of the protein it was taken from,
+    /// see [`Self::is_protein_c_term`].
+    #[serde(default)]
+    internal_c_term: bool,
     /// The marker indicating which level of complexity this peptide (potentially) uses
     marker: PhantomData<Complexity>,
 }
@@ -115,9 +131,13 @@ impl<Complexity> Default for LinearPeptide<Complexity> {
             labile: Vec::new(),
             n_term: None,
             c_term: None,
+            n_term_info_tags: Vec::new(),
+            c_term_info_tags: Vec::new(),
             sequence: Vec::new(),
             ambiguous_modifications: Vec::new(),
             charge_carriers: None,
+            internal_n_term: false,
+            internal_c_term: false,
             marker: PhantomData,
         }
     }
@@ -130,9 +150,13 @@ impl<Complexity> Clone for LinearPeptide<Complexity> {
             labile: self.labile.clone(),
             n_term: self.n_term.clone(),
             c_term: self.c_term.clone(),
+            n_term_info_tags: self.n_term_info_tags.clone(),
+            c_term_info_tags: self.c_term_info_tags.clone(),
             sequence: self.sequence.clone(),
             ambiguous_modifications: self.ambiguous_modifications.clone(),
             charge_carriers: self.charge_carriers.clone(),
+            internal_n_term: self.internal_n_term,
+            internal_c_term: self.internal_c_term,
             marker: PhantomData,
         }
     }
@@ -152,6 +176,146 @@ impl<OwnComplexity, OtherComplexity> PartialEq<LinearPeptide<OtherComplexity>>
     }
 }
 
+#[allow(clippy::redundant_pub_crate)]
+/// (De)serialise a peptide without the protein terminal flags and `INFO` tags.
+///
+/// This is the layout of the pre-generated IMGT germlines, which are stored with bincode (which
+/// cannot skip missing fields). The flags are set to `false` and the tags are left empty when
+/// deserialising.
+pub(crate) mod germline_layout {
+    use super::*;
+    use serde::{Deserializer, Serializer};
+
+    #[derive(Serialize)]
+    #[serde(rename = "LinearPeptide")]
+    struct Borrowed<'a, Complexity> {
+        global: &'a Vec<(Element, Option<NonZeroU16>, Option<IsotopeLocation>)>,
+        labile: &'a Vec<SimpleModification>,
+        n_term: &'a Option<Modification>,
+        c_term: &'a Option<Modification>,
+        sequence: Vec<BorrowedElement<'a, Complexity>>,
+        ambiguous_modifications: &'a Vec<Vec<usize>>,
+        charge_carriers: &'a Option<MolecularCharge>,
+        marker: PhantomData<Complexity>,
+    }
+
+    #[derive(Serialize)]
+    #[serde(rename = "SequenceElement")]
+    struct BorrowedElement<'a, Complexity> {
+        aminoacid: &'a CheckedAminoAcid<Complexity>,
+        modifications: &'a Vec<Modification>,
+        possible_modifications: &'a Vec<AmbiguousModification>,
+        ambiguous: &'a Option<usize>,
+        marker: PhantomData<Complexity>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename = "LinearPeptide")]
+    struct Owned<Complexity> {
+        global: Vec<(Element, Option<NonZeroU16>, Option<IsotopeLocation>)>,
+        labile: Vec<SimpleModification>,
+        n_term: Option<Modification>,
+        c_term: Option<Modification>,
+        sequence: Vec<OwnedElement<Complexity>>,
+        ambiguous_modifications: Vec<Vec<usize>>,
+        charge_carriers: Option<MolecularCharge>,
+        marker: PhantomData<Complexity>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename = "SequenceElement")]
+    struct OwnedElement<Complexity> {
+        aminoacid: CheckedAminoAcid<Complexity>,
+        modifications: Vec<Modification>,
+        possible_modifications: Vec<AmbiguousModification>,
+        ambiguous: Option<usize>,
+        marker: PhantomData<Complexity>,
+    }
+
+    /// # Errors
+    /// If the serializer fails
+    pub fn serialize<S: Serializer, Complexity: Serialize>(
+        peptide: &LinearPeptide<Complexity>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        Borrowed {
+            global: &peptide.global,
+            labile: &peptide.labile,
+            n_term: &peptide.n_term,
+            c_term: &peptide.c_term,
+            sequence: peptide
+                .sequence
+                .iter()
+                .map(|element| BorrowedElement {
+                    aminoacid: &element.aminoacid,
+                    modifications: &element.modifications,
+                    possible_modifications: &element.possible_modifications,
+                    ambiguous: &element.ambiguous,
+                    marker: PhantomData,
+                })
+                .collect(),
+            ambiguous_modifications: &peptide.ambiguous_modifications,
+            charge_carriers: &peptide.charge_carriers,
+            marker: PhantomData,
+        }
+        .serialize(serializer)
+    }
+
+    /// # Errors
+    /// If the deserializer fails
+    pub fn deserialize<'de, D: Deserializer<'de>, Complexity: Deserialize<'de>>(
+        deserializer: D,
+    ) -> Result<LinearPeptide<Complexity>, D::Error> {
+        let peptide = Owned::deserialize(deserializer)?;
+        Ok(LinearPeptide {
+            global: peptide.global,
+            labile: peptide.labile,
+            n_term: peptide.n_term,
+            c_term: peptide.c_term,
+            n_term_info_tags: Vec::new(),
+            c_term_info_tags: Vec::new(),
+            sequence: peptide
+                .sequence
+                .into_iter()
+                .map(|element| {
+                    let mut result = SequenceElement::new(element.aminoacid, element.ambiguous);
+                    result.modifications = element.modifications;
+                    result.possible_modifications = element.possible_modifications;
+                    result
+                })
+                .collect(),
+            ambiguous_modifications: peptide.ambiguous_modifications,
+            charge_carriers: peptide.charge_carriers,
+            internal_n_term: false,
+            internal_c_term: false,
+            marker: PhantomData,
+        })
+    }
+}
+
+impl<Complexity: Ord> PartialOrd for LinearPeptide<Complexity> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Compares the same fields as [`PartialEq`], so the protein terminal flags are ignored
+impl<Complexity: Ord> Ord for LinearPeptide<Complexity> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.global
+            .cmp(&other.global)
+            .then_with(|| self.labile.cmp(&other.labile))
+            .then_with(|| self.n_term.cmp(&other.n_term))
+            .then_with(|| self.c_term.cmp(&other.c_term))
+            .then_with(|| self.sequence.cmp(&other.sequence))
+            .then_with(|| {
+                self.ambiguous_modifications
+                    .cmp(&other.ambiguous_modifications)
+            })
+            .then_with(|| self.charge_carriers.cmp(&other.charge_carriers))
+    }
+}
+
 impl<Complexity> std::hash::Hash for LinearPeptide<Complexity> {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.global.hash(state);
@@ -256,9 +420,9 @@ impl<Complexity: HighestOf<Linear>> LinearPeptide<Complexity> {
         mut self,
         global: impl IntoIterator<Item = (Element, Option<NonZeroU16>)>,
     ) -> Option<LinearPeptide<Complexity::HighestLevel>> {
-        for modification in global {
-            if modification.0.is_valid(modification.1) {
-                self.global.push(modification);
+        for (element, isotope) in global {
+            if element.is_valid(isotope) {
+                self.global.push((element, isotope, None));
             } else {
                 return None;
             }
@@ -285,6 +449,8 @@ impl<Complexity> LinearPeptide<Complexity> {
             labile: self.labile,
             n_term: self.n_term,
             c_term: self.c_term,
+            n_term_info_tags: self.n_term_info_tags,
+            c_term_info_tags: self.c_term_info_tags,
             sequence: self
                 .sequence
                 .into_iter()
@@ -292,6 +458,8 @@ impl<Complexity> LinearPeptide<Complexity> {
                 .collect(),
             ambiguous_modifications: self.ambiguous_modifications,
             charge_carriers: self.charge_carriers,
+            internal_n_term: self.internal_n_term,
+            internal_c_term: self.internal_c_term,
             marker: PhantomData,
         }
     }
@@ -305,6 +473,7 @@ impl<Complexity> LinearPeptide<Complexity> {
 
     /// Create a new [`LinearPeptide`], if you want an empty peptide look at [`LinearPeptide::default`].
     /// Potentially the `.collect()` or `.into()` methods can be useful as well.
+    /// The placement of the modifications is not validated, use [`Self::validate`] for that.
     #[must_use]
     pub fn new<OtherComplexity: AtMax<Complexity>>(
         sequence: impl IntoIterator<Item = SequenceElement<OtherComplexity>>,
@@ -324,17 +493,32 @@ impl<Complexity> LinearPeptide<Complexity> {
         &mut self.sequence
     }
 
-    /// Add the N terminal modification
+    /// Substitute the amino acid at the given index, all modifications (defined and ambiguous)
+    /// are kept on this position. The placement rules are NOT checked, use [`Self::validate`]
+    /// for that. Returns the original amino acid.
+    /// # Panics
+    /// If the index is out of bounds.
+    pub fn substitute<OtherComplexity: AtMax<Complexity>>(
+        &mut self,
+        index: usize,
+        aminoacid: CheckedAminoAcid<OtherComplexity>,
+    ) -> CheckedAminoAcid<Complexity> {
+        std::mem::replace(&mut self.sequence[index].aminoacid, aminoacid.mark())
+    }
+
+    /// Add the N terminal modification. The placement is not validated, use [`Self::validate`] for that.
     #[must_use]
     pub fn n_term(mut self, term: Option<Modification>) -> Self {
         self.n_term = term;
+        self.n_term_info_tags.clear();
         self
     }
 
-    /// Add the C terminal modification
+    /// Add the C terminal modification. The placement is not validated, use [`Self::validate`] for that.
     #[must_use]
     pub fn c_term(mut self, term: Option<Modification>) -> Self {
         self.c_term = term;
+        self.c_term_info_tags.clear();
         self
     }
 
@@ -348,6 +532,82 @@ impl<Complexity> LinearPeptide<Complexity> {
         self.sequence.is_empty()
     }
 
+    /// Find all locations where the given peptide occurs in this peptide, returns the start index
+    /// of all (potentially overlapping) occurrences. If `match_modifications` is set the
+    /// modifications on each position have to be identical as well (in any order), otherwise
+    /// only the amino acids are compared. Terminal, ambiguous, and labile modifications are never
+    /// taken into account. If `canonical` is set amino acids are compared with
+    /// [`CheckedAminoAcid::canonical_identical`], so B matches N and D, Z matches E and Q, J
+    /// matches I and L, and X matches anything.
+    pub fn find_subsequence<OtherComplexity>(
+        &self,
+        needle: &LinearPeptide<OtherComplexity>,
+        match_modifications: bool,
+        canonical: bool,
+    ) -> Vec<usize> {
+        if needle.is_empty() || needle.len() > self.len() {
+            return Vec::new();
+        }
+        self.sequence
+            .windows(needle.len())
+            .enumerate()
+            .filter(|(_, window)| {
+                window.iter().zip(needle.sequence()).all(|(a, b)| {
+                    let aminoacid = if canonical {
+                        a.aminoacid
+                            .aminoacid()
+                            .canonical_identical(b.aminoacid.aminoacid())
+                    } else {
+                        a.aminoacid.aminoacid() == b.aminoacid.aminoacid()
+                    };
+                    aminoacid
+                        && (!match_modifications
+                            || a.modifications.len() == b.modifications.len()
+                                && a.modifications.iter().all(|m| b.modifications.contains(m)))
+                })
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Find all N-glycosylation sequons (N-X-S/T with X not P) and return the index of the N of
+    /// each. A B (N or D) is treated as a potential N, so it is reported as a site as well. Any
+    /// other ambiguous residue (eg X or J) at the middle position is accepted as it could be
+    /// anything but P, but only S and T are accepted at the last position. Sequons that would be
+    /// completed outside of this peptide are not reported.
+    pub fn n_glycosylation_sites(&self) -> Vec<usize> {
+        self.sequence
+            .windows(3)
+            .enumerate()
+            .filter(|(_, window)| {
+                matches!(
+                    window[0].aminoacid.aminoacid(),
+                    AminoAcid::Asparagine | AminoAcid::AmbiguousAsparagine
+                ) && window[1].aminoacid.aminoacid() != AminoAcid::Proline
+                    && matches!(
+                        window[2].aminoacid.aminoacid(),
+                        AminoAcid::Serine | AminoAcid::Threonine
+                    )
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Find all potential O-glycosylation sites, meaning the indices of all S and T residues.
+    pub fn o_glycosylation_sites(&self) -> Vec<usize> {
+        self.sequence
+            .iter()
+            .enumerate()
+            .filter(|(_, element)| {
+                matches!(
+                    element.aminoacid.aminoacid(),
+                    AminoAcid::Serine | AminoAcid::Threonine
+                )
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
     /// Get the N terminal modification.
     pub const fn get_n_term(&self) -> Option<&Modification> {
         self.n_term.as_ref()
@@ -358,14 +618,88 @@ impl<Complexity> LinearPeptide<Complexity> {
         self.c_term.as_ref()
     }
 
+    /// Check if the N terminus of this peptide is the N terminus of the protein it was taken from.
+    /// This is true for any parsed peptide, while for peptides taken from a larger sequence (see
+    /// [`LinearPeptide::sub_peptide`] and [`LinearPeptide::digest`]) this is only true if the
+    /// peptide starts at the start of that sequence. Modifications that are defined on the
+    /// protein N terminus ([`Position::ProteinNTerm`](crate::placement_rule::Position::ProteinNTerm))
+    /// can only be placed if this is true.
+    pub const fn is_protein_n_term(&self) -> bool {
+        !self.internal_n_term
+    }
+
+    /// Check if the C terminus of this peptide is the C terminus of the protein it was taken from,
+    /// see [`Self::is_protein_n_term`].
+    pub const fn is_protein_c_term(&self) -> bool {
+        !self.internal_c_term
+    }
+
+    /// Get all modifications on this peptide with their positions, in order. This starts with the
+    /// N terminal modification, then all modifications on each residue (with all ambiguous
+    /// modifications for a residue after the defined ones), and ends with the C terminal modification.
+    pub fn modifications(&self) -> Vec<(SequencePosition, PlacedModification<'_>)> {
+        self.n_term
+            .iter()
+            .map(|m| (SequencePosition::NTerm, PlacedModification::Defined(m)))
+            .chain(self.sequence.iter().enumerate().flat_map(|(index, seq)| {
+                seq.modifications
+                    .iter()
+                    .map(PlacedModification::Defined)
+                    .chain(
+                        seq.possible_modifications
+                            .iter()
+                            .map(PlacedModification::Ambiguous),
+                    )
+                    .map(move |m| (SequencePosition::Index(index), m))
+            }))
+            .chain(
+                self.c_term
+                    .iter()
+                    .map(|m| (SequencePosition::CTerm, PlacedModification::Defined(m))),
+            )
+            .collect()
+    }
+
+    /// Get the summed formula of all labile modifications, without the global isotope
+    /// modifications applied. Labile modifications are not part of [`LinearPeptide::formulas`] (or any
+    /// fragment) as these are assumed to be lost before fragmentation, see
+    /// [`LinearPeptide::formulas_with_labile`] for the formulas of the intact peptide.
+    pub fn labile_formula(&self) -> MolecularFormula {
+        self.labile.iter().map(Chemical::formula).sum()
+    }
+
     /// Set the N terminal modification as a simple modification
     pub fn set_simple_n_term(&mut self, modification: Option<SimpleModification>) {
         self.n_term = modification.map(Modification::Simple);
+        self.n_term_info_tags.clear();
     }
 
     /// Set the C terminal modification as a simple modification
     pub fn set_simple_c_term(&mut self, modification: Option<SimpleModification>) {
         self.c_term = modification.map(Modification::Simple);
+        self.c_term_info_tags.clear();
+    }
+
+    /// Get the `INFO` tags given with the N terminal modification
+    pub fn get_n_term_info_tags(&self) -> &[String] {
+        &self.n_term_info_tags
+    }
+
+    /// Get the `INFO` tags given with the C terminal modification
+    pub fn get_c_term_info_tags(&self) -> &[String] {
+        &self.c_term_info_tags
+    }
+
+    /// Set the `INFO` tags for the N terminal modification, these are removed again when the N
+    /// terminal modification is changed.
+    pub fn set_n_term_info_tags(&mut self, tags: Vec<String>) {
+        self.n_term_info_tags = tags;
+    }
+
+    /// Set the `INFO` tags for the C terminal modification, these are removed again when the C
+    /// terminal modification is changed.
+    pub fn set_c_term_info_tags(&mut self, tags: Vec<String>) {
+        self.c_term_info_tags = tags;
     }
 
     /// Add a modification to this peptide
@@ -572,8 +906,51 @@ impl<Complexity> LinearPeptide<Complexity> {
         )
     }
 
+    /// Lazily iterate over all combinations of placements of the ambiguous modifications within
+    /// the given range. Each combination contains for each ambiguous modification that has a
+    /// possible location in the range either the placement (id, sequence index) or None if it is
+    /// placed outside of the range. It always yields at least one combination.
+    fn ambiguous_combinations(
+        &self,
+        range: impl RangeBounds<usize>,
+    ) -> impl Iterator<Item = Vec<Option<(usize, usize)>>> {
+        // The options for each modification, the last modification is iterated over the slowest
+        let options = self
+            .ambiguous_modifications
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(id, possibilities)| {
+                // If there is an option to place this mod outside of this range allow that as well
+                let outside = possibilities.iter().any(|pos| !range.contains(pos));
+                let options = possibilities
+                    .iter()
+                    .filter(|pos| range.contains(pos))
+                    .flat_map(|pos| {
+                        std::iter::once(Some((id, *pos))).chain(outside.then_some(None))
+                    })
+                    .collect_vec();
+                // If no location is possible for this modification it is never placed
+                if options.is_empty() {
+                    vec![None]
+                } else {
+                    options
+                }
+            })
+            .collect_vec();
+        // Without any ambiguous modifications this yields a single empty combination
+        options
+            .into_iter()
+            .multi_cartesian_product()
+            .map(|mut combination| {
+                combination.reverse();
+                combination
+            })
+    }
+
     /// Generate all possible patterns for the ambiguous positions.
-    /// It always contains at least one pattern.
+    /// It always contains at least one pattern, and at most `max_combinations` patterns of
+    /// ambiguous modifications are considered, any further patterns are ignored.
     /// The global isotope modifications are NOT applied.
     /// Additionally it also returns all peptides present as cross-link.
     #[allow(clippy::too_many_arguments)]
@@ -587,6 +964,7 @@ impl<Complexity> LinearPeptide<Complexity> {
         applied_cross_links: &mut Vec<CrossLinkName>,
         allow_ms_cleavable: bool,
         peptide_index: usize,
+        max_combinations: usize,
     ) -> (Multi<MolecularFormula>, HashSet<CrossLinkName>) {
         // Calculate all formulas for the selected AA range without any ambiguous modifications
         let (formulas, seen) = self.sequence[(
@@ -614,48 +992,13 @@ impl<Complexity> LinearPeptide<Complexity> {
             );
 
         // Calculate all masses (and labels) for all possible combinations of ambiguous masses
-        let previous_combinations = self.ambiguous_modifications.iter().enumerate().fold(
-            vec![Vec::new()],
-            |previous_combinations, (id, possibilities)| {
-                // Go over all possible locations for this ambiguous mod and add these to all previous options
-                let new_combinations = possibilities
-                    .iter()
-                    .filter(|pos| range.contains(pos))
-                    .flat_map(|pos| {
-                        // This position is a possible location, add this location for this mod to all previously known combinations
-                        let mut new_combinations = previous_combinations
-                            .iter()
-                            .map(|path| {
-                                let mut new = path.clone();
-                                new.push(Some((id, *pos)));
-                                new
-                            })
-                            .collect_vec();
-                        // If there is an option to place this mod outside of this range allow that as well
-                        // by copying all previous options without any alteration
-                        if possibilities.iter().any(|pos| !range.contains(pos)) {
-                            new_combinations.extend_from_slice(&previous_combinations);
-                        }
-                        new_combinations
-                    })
-                    .collect_vec();
-
-                // If no location is possible for this modification keep all known combinations
-                if new_combinations.is_empty() {
-                    previous_combinations
-                } else {
-                    new_combinations
-                }
-                // Returns a list of all combinations of ambiguous modifications that can go together
-            },
-        );
-        let all_ambiguous_options = previous_combinations
-            .into_iter()
+        let all_ambiguous_options = self
+            .ambiguous_combinations(range)
+            .take(max_combinations.max(1))
             .map(|current_selected_ambiguous| {
                 // Determine the formula for all selected ambiguous modifications and create the labels
                 current_selected_ambiguous
-                    .iter()
-                    .copied()
+                    .into_iter()
                     .filter_map(|position| {
                         if let Some((id, pos)) = position {
                             self.sequence[pos]
@@ -697,62 +1040,164 @@ impl<Complexity> LinearPeptide<Complexity> {
         peptide_index: usize,
         all_peptides: &[LinearPeptide<Linked>],
     ) -> Vec<Fragment> {
+        let mut output = Vec::with_capacity(20 * self.sequence.len() + 75); // Empirically derived required size of the buffer (Derived from Hecklib)
+        output.extend(self.theoretical_fragments_iter_inner(
+            max_charge,
+            model,
+            peptidoform_index,
+            peptide_index,
+            all_peptides,
+        ));
+        output
+    }
+
+    /// Lazily generate the theoretical fragments for this peptide, with the given maximal charge
+    /// of the fragments, and the given model. With the global isotope modifications applied.
+    /// The fragments are generated one sequence position at a time, followed by the precursor,
+    /// modification, diagnostic, and labile fragments. Any isotopes of a fragment directly follow
+    /// that fragment.
+    /// # Panics
+    /// Panics if the `max_charge` is bigger than [`isize::MAX`].
+    pub(crate) fn theoretical_fragments_iter_inner<'a>(
+        &'a self,
+        max_charge: Charge,
+        model: &'a Model,
+        peptidoform_index: usize,
+        peptide_index: usize,
+        all_peptides: &'a [LinearPeptide<Linked>],
+    ) -> impl Iterator<Item = Fragment> + 'a {
+        self.fragments_at_positions(
+            0..=self.sequence.len(),
+            max_charge,
+            model,
+            peptidoform_index,
+            peptide_index,
+            all_peptides,
+        )
+    }
+
+    /// Lazily generate the fragments for the given sequence positions, a position equal to the
+    /// length of the peptide results in the peptide wide fragments (precursor, modification,
+    /// diagnostic, and labile fragments).
+    /// # Panics
+    /// Panics if the `max_charge` is bigger than [`isize::MAX`].
+    fn fragments_at_positions<'a>(
+        &'a self,
+        positions: impl Iterator<Item = usize> + 'a,
+        max_charge: Charge,
+        model: &'a Model,
+        peptidoform_index: usize,
+        peptide_index: usize,
+        all_peptides: &'a [LinearPeptide<Linked>],
+    ) -> impl Iterator<Item = Fragment> + 'a {
         let default_charge = MolecularCharge::proton(
             isize::try_from(max_charge.value)
                 .expect("Charge of the precursor cannot be higher then isize::MAX"),
         );
-        let mut charge_carriers: CachedCharge = self
-            .charge_carriers
-            .as_ref()
-            .unwrap_or(&default_charge)
-            .into();
+        let mut charge_carriers =
+            CachedCharge::from(self.charge_carriers.as_ref().unwrap_or(&default_charge))
+                .limit(model.fragment_charge_range.clone());
 
-        let mut output = Vec::with_capacity(20 * self.sequence.len() + 75); // Empirically derived required size of the buffer (Derived from Hecklib)
-        for sequence_index in 0..self.sequence.len() {
-            let position = PeptidePosition::n(SequencePosition::Index(sequence_index), self.len());
-            let mut cross_links = Vec::new();
-            let visited_peptides = vec![peptide_index];
-            let (n_term, n_term_seen) = self.all_masses(
-                ..=sequence_index,
-                ..sequence_index,
-                &self.get_n_term_mass(
-                    all_peptides,
-                    &visited_peptides,
-                    &mut cross_links,
-                    model.allow_cross_link_cleavage,
-                    peptide_index,
-                ),
-                model.modification_specific_neutral_losses,
+        let global = self.unrestricted_global();
+
+        positions
+            .flat_map(move |sequence_index| {
+                if sequence_index < self.sequence.len() {
+                    let mut fragments = self.position_fragments(
+                        sequence_index,
+                        model,
+                        peptidoform_index,
+                        peptide_index,
+                        all_peptides,
+                        &mut charge_carriers,
+                    );
+                    for fragment in &mut fragments {
+                        fragment.formula = fragment
+                            .formula
+                            .with_global_isotope_modifications(&global)
+                            .expect("Invalid global isotope modification");
+                    }
+                    fragments
+                } else {
+                    self.peptide_wide_fragments(
+                        model,
+                        peptidoform_index,
+                        peptide_index,
+                        all_peptides,
+                        &mut charge_carriers,
+                    )
+                }
+            })
+            .flat_map(move |fragment| {
+                let isotopes = (1..=model.fragment_isotopes)
+                    .map(|isotope| fragment.with_isotope(isotope))
+                    .collect_vec();
+                std::iter::once(fragment).chain(isotopes)
+            })
+    }
+
+    /// Generate the fragments originating from the given position in the sequence: the
+    /// backbone fragments and if enabled in the model the precursor side chain losses.
+    fn position_fragments(
+        &self,
+        sequence_index: usize,
+        model: &Model,
+        peptidoform_index: usize,
+        peptide_index: usize,
+        all_peptides: &[LinearPeptide<Linked>],
+        charge_carriers: &mut CachedCharge,
+    ) -> Vec<Fragment> {
+        let mut output = Vec::new();
+        let position = PeptidePosition::n(SequencePosition::Index(sequence_index), self.len());
+        let mut cross_links = Vec::new();
+        let visited_peptides = vec![peptide_index];
+        let (n_term, n_term_seen) = self.all_masses(
+            ..=sequence_index,
+            ..sequence_index,
+            &(self.get_n_term_mass(
                 all_peptides,
                 &visited_peptides,
                 &mut cross_links,
                 model.allow_cross_link_cleavage,
                 peptide_index,
-            );
-            let (c_term, c_term_seen) = self.all_masses(
-                sequence_index..,
-                sequence_index + 1..,
-                &self.get_c_term_mass(
-                    all_peptides,
-                    &visited_peptides,
-                    &mut cross_links,
-                    model.allow_cross_link_cleavage,
-                    peptide_index,
-                ),
-                model.modification_specific_neutral_losses,
+            ) + self.restricted_global(..sequence_index)),
+            model.modification_specific_neutral_losses,
+            all_peptides,
+            &visited_peptides,
+            &mut cross_links,
+            model.allow_cross_link_cleavage,
+            peptide_index,
+            model.max_ambiguous_combinations,
+        );
+        let (c_term, c_term_seen) = self.all_masses(
+            sequence_index..,
+            sequence_index + 1..,
+            &(self.get_c_term_mass(
                 all_peptides,
                 &visited_peptides,
                 &mut cross_links,
                 model.allow_cross_link_cleavage,
                 peptide_index,
-            );
-            if !n_term_seen.is_disjoint(&c_term_seen) {
-                continue; // There is a link reachable from both sides so there is a loop
-            }
-            let (modifications_total, modifications_cross_links) = self.sequence[sequence_index]
-                .modifications
-                .iter()
-                .fold((Multi::default(), HashSet::new()), |acc, m| {
+            ) + self.restricted_global(sequence_index + 1..)),
+            model.modification_specific_neutral_losses,
+            all_peptides,
+            &visited_peptides,
+            &mut cross_links,
+            model.allow_cross_link_cleavage,
+            peptide_index,
+            model.max_ambiguous_combinations,
+        );
+        if !n_term_seen.is_disjoint(&c_term_seen) {
+            return output; // There is a link reachable from both sides so there is a loop
+        }
+        // The restricted global isotopes on this residue are placed with its modifications
+        let (modifications_total, modifications_cross_links) =
+            self.sequence[sequence_index].modifications.iter().fold(
+                (
+                    Multi::from(self.restricted_global(sequence_index..=sequence_index)),
+                    HashSet::new(),
+                ),
+                |acc, m| {
                     let (f, s) = m.formula_inner(
                         all_peptides,
                         &[peptide_index],
@@ -762,75 +1207,107 @@ impl<Complexity> LinearPeptide<Complexity> {
                         peptide_index,
                     );
                     (acc.0 * f, acc.1.union(&s).cloned().collect())
-                });
-
-            output.append(
-                &mut self.sequence[sequence_index]
-                    .aminoacid
-                    .aminoacid()
-                    .fragments(
-                        &n_term,
-                        &c_term,
-                        &modifications_total,
-                        &mut charge_carriers,
-                        SequencePosition::Index(sequence_index),
-                        self.sequence.len(),
-                        &model.ions(position),
-                        peptidoform_index,
-                        peptide_index,
-                        (
-                            // Allow any N terminal fragment if there is no cross-link to the C terminal side
-                            c_term_seen.is_disjoint(&modifications_cross_links),
-                            n_term_seen.is_disjoint(&modifications_cross_links),
-                        ),
-                    ),
+                },
             );
 
-            if model.m {
-                //  p - sX fragment: precursor amino acid side chain losses
-                output.extend(
-                    self.formulas_inner(
-                        peptide_index,
-                        all_peptides,
-                        &[],
-                        &mut Vec::new(),
-                        model.allow_cross_link_cleavage,
-                    )
-                    .0
-                    .iter()
-                    .flat_map(|m| {
-                        self.sequence[sequence_index]
-                            .aminoacid
-                            .formulas_inner(SequencePosition::Index(sequence_index), peptide_index)
-                            .iter()
-                            .flat_map(|aa| {
-                                Fragment::generate_all(
-                                    &((-modifications_total.clone()) + m.clone() - aa.clone()
-                                        + molecular_formula!(C 2 H 2 N 1 O 1)),
-                                    peptidoform_index,
-                                    peptide_index,
-                                    &FragmentType::PrecursorSideChainLoss(
-                                        position,
-                                        self.sequence[sequence_index].aminoacid.aminoacid(),
-                                    ),
-                                    &Multi::default(),
-                                    &[],
-                                    &mut charge_carriers,
-                                    model.precursor.1,
-                                )
-                            })
-                            .collect_vec()
-                    }),
-                );
-            }
+        let mut fragments = self.sequence[sequence_index]
+            .aminoacid
+            .aminoacid()
+            .fragments(
+                &n_term,
+                &c_term,
+                &modifications_total,
+                charge_carriers,
+                SequencePosition::Index(sequence_index),
+                self.sequence.len(),
+                &model.ions(position),
+                peptidoform_index,
+                peptide_index,
+                (
+                    // Allow any N terminal fragment if there is no cross-link to the C terminal side
+                    c_term_seen.is_disjoint(&modifications_cross_links),
+                    n_term_seen.is_disjoint(&modifications_cross_links),
+                ),
+            );
+        if model.chemically_aware_losses {
+            let residues = |range: &[SequenceElement<Complexity>]| {
+                range.iter().map(|s| s.aminoacid.aminoacid()).collect_vec()
+            };
+            let n_residues = residues(&self.sequence[..=sequence_index]);
+            let c_residues = residues(&self.sequence[sequence_index..]);
+            fragments.retain(|fragment| {
+                let Some(loss) = &fragment.neutral_loss else {
+                    return true;
+                };
+                match fragment.ion {
+                    FragmentType::a(_)
+                    | FragmentType::b(_)
+                    | FragmentType::c(_)
+                    | FragmentType::c·(_)
+                    | FragmentType::d(_) => loss.possible_from(n_residues.iter().copied()),
+                    FragmentType::v(_)
+                    | FragmentType::w(_)
+                    | FragmentType::x(_)
+                    | FragmentType::y(_)
+                    | FragmentType::z(_)
+                    | FragmentType::z·(_) => loss.possible_from(c_residues.iter().copied()),
+                    _ => true,
+                }
+            });
         }
-        for fragment in &mut output {
-            fragment.formula = fragment
-                .formula
-                .with_global_isotope_modifications(&self.global)
-                .expect("Invalid global isotope modification");
+        output.append(&mut fragments);
+
+        if model.m {
+            //  p - sX fragment: precursor amino acid side chain losses
+            output.extend(
+                self.formulas_inner(
+                    peptide_index,
+                    all_peptides,
+                    &[],
+                    &mut Vec::new(),
+                    model.allow_cross_link_cleavage,
+                )
+                .0
+                .iter()
+                .flat_map(|m| {
+                    self.sequence[sequence_index]
+                        .aminoacid
+                        .formulas_inner(SequencePosition::Index(sequence_index), peptide_index)
+                        .iter()
+                        .flat_map(|aa| {
+                            Fragment::generate_all(
+                                &((-modifications_total.clone()) + m.clone() - aa.clone()
+                                    + molecular_formula!(C 2 H 2 N 1 O 1)),
+                                peptidoform_index,
+                                peptide_index,
+                                &FragmentType::PrecursorSideChainLoss(
+                                    position,
+                                    self.sequence[sequence_index].aminoacid.aminoacid(),
+                                ),
+                                &Multi::default(),
+                                &[],
+                                charge_carriers,
+                                model.precursor.1,
+                            )
+                        })
+                        .collect_vec()
+                }),
+            );
         }
+        output
+    }
 
+    /// Generate the fragments that are not tied to a single sequence position: the precursor,
+    /// glycan fragments from modifications, diagnostic ions, and labile glycan fragments.
+    fn peptide_wide_fragments(
+        &self,
+        model: &Model,
+        peptidoform_index: usize,
+        peptide_index: usize,
+        all_peptides: &[LinearPeptide<Linked>],
+        charge_carriers: &mut CachedCharge,
+    ) -> Vec<Fragment> {
+        let mut output = Vec::new();
         // Generate precursor peak
         let (full_precursor, _all_cross_links) = self.formulas_inner(
             peptide_index,
@@ -857,7 +1334,7 @@ impl<Complexity> LinearPeptide<Complexity> {
             &FragmentType::precursor,
             &Multi::default(),
             &precursor_neutral_losses,
-            &mut charge_carriers,
+            charge_carriers,
             model.precursor.1,
         ));
 
@@ -880,7 +1357,7 @@ impl<Complexity> LinearPeptide<Complexity> {
                     model,
                     peptidoform_index,
                     peptide_index,
-                    &mut charge_carriers,
+                    charge_carriers,
                     &full_formula,
                     Some(attachment),
                 ));
@@ -890,7 +1367,7 @@ impl<Complexity> LinearPeptide<Complexity> {
                     model,
                     peptidoform_index,
                     peptide_index,
-                    &mut charge_carriers,
+                    charge_carriers,
                     &full_formula,
                     Some(attachment),
                 ));
@@ -908,9 +1385,11 @@ impl<Complexity> LinearPeptide<Complexity> {
                         peptidoform_index,
                         peptide_index,
                         neutral_loss: None,
+                        isotope: 0,
+                        charge_carriers: MolecularFormula::default(),
                     }
                     .with_charge_range(
-                        &mut charge_carriers,
+                        charge_carriers,
                         model.modification_specific_diagnostic_ions.1,
                     ),
                 );
@@ -920,13 +1399,14 @@ impl<Complexity> LinearPeptide<Complexity> {
         // Add labile glycan fragments
         for modification in &self.labile {
             match modification {
-                SimpleModification::Glycan(composition) => {
+                SimpleModification::Glycan(composition)
+                | SimpleModification::Gno(GnoComposition::Composition(composition), _) => {
                     output.extend(MonoSaccharide::theoretical_fragments(
                         composition,
                         model,
                         peptidoform_index,
                         peptide_index,
-                        &mut charge_carriers,
+                        charge_carriers,
                         &full_formula,
                         None,
                     ));
@@ -941,7 +1421,7 @@ impl<Complexity> LinearPeptide<Complexity> {
                                 model,
                                 peptidoform_index,
                                 peptide_index,
-                                &mut charge_carriers,
+                                charge_carriers,
                                 &full_formula,
                                 None,
                             ),
@@ -968,6 +1448,7 @@ impl<Complexity> LinearPeptide<Complexity> {
         applied_cross_links: &mut Vec<CrossLinkName>,
         allow_ms_cleavable: bool,
         peptide_index: usize,
+        max_combinations: usize,
     ) -> (Multi<MolecularFormula>, HashSet<CrossLinkName>) {
         let (ambiguous_mods_masses, seen) = self.ambiguous_patterns(
             range.clone(),
@@ -978,6 +1459,7 @@ impl<Complexity> LinearPeptide<Complexity> {
             applied_cross_links,
             allow_ms_cleavable,
             peptide_index,
+            max_combinations,
         );
         if apply_neutral_losses {
             let neutral_losses =
@@ -994,6 +1476,38 @@ impl<Complexity> LinearPeptide<Complexity> {
         }
     }
 
+    /// The global isotope modifications that are applied to all atoms
+    fn unrestricted_global(&self) -> Vec<(Element, Option<NonZeroU16>)> {
+        self.global
+            .iter()
+            .filter(|(_, _, location)| location.is_none())
+            .map(|(element, isotope, _)| (*element, *isotope))
+            .collect()
+    }
+
+    /// The isotope substitutions made by the restricted global isotope modifications on the given
+    /// residues, these never label any modifications. This has to be added before the unrestricted
+    /// global isotope modifications are applied.
+    fn restricted_global(&self, residues: impl RangeBounds<usize>) -> MolecularFormula {
+        self.global
+            .iter()
+            .filter_map(|(element, isotope, location)| {
+                let location = (*location)?;
+                let count = self
+                    .sequence
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| residues.contains(index))
+                    .map(|(index, seq)| location.atoms(*element, seq.aminoacid.aminoacid(), index))
+                    .sum::<i32>();
+                MolecularFormula::new(
+                    &[(*element, *isotope, count), (*element, None, -count)],
+                    &[],
+                )
+            })
+            .sum()
+    }
+
     /// Get the total amount of ambiguous modifications
     pub(crate) fn number_of_ambiguous_modifications(&self) -> usize {
         self.ambiguous_modifications.len()
@@ -1025,10 +1539,13 @@ impl<Complexity> LinearPeptide<Complexity> {
                 .0;
         }
 
+        let restricted = self.restricted_global(..);
+        let global = self.unrestricted_global();
         formulas
             .iter()
             .map(|f| {
-                f.with_global_isotope_modifications(&self.global)
+                (f + &restricted)
+                    .with_global_isotope_modifications(&global)
                     .expect("Invalid global isotope modification in bare_formulas")
             })
             .collect()
@@ -1080,9 +1597,11 @@ impl<Complexity> LinearPeptide<Complexity> {
             seen.extend(pos_seen);
         }
 
+        let restricted = self.restricted_global(..);
+        let global = self.unrestricted_global();
         (formulas
             .iter()
-            .map(|f| f.with_global_isotope_modifications(&self.global).expect("Global isotope modification invalid in determination of all formulas for a peptide"))
+            .map(|f| (f + &restricted).with_global_isotope_modifications(&global).expect("Global isotope modification invalid in determination of all formulas for a peptide"))
             .collect(), seen)
     }
 
@@ -1098,14 +1617,43 @@ impl<Complexity> LinearPeptide<Complexity> {
         f: &mut impl Write,
         show_global_mods: bool,
         specification_compliant: bool,
+    ) -> std::fmt::Result {
+        self.display_inner(f, show_global_mods, specification_compliant, false)
+    }
+
+    /// Display this peptide with every candidate site of every ambiguous modification written
+    /// out with its localisation score, if known (`T[#g1(0.01)]S[#g1(0.09)]ES[Phospho#g1(0.90)]`).
+    /// Unlike [`Display`] this never uses the compact `[Phospho]?PEPTIDE` notation for
+    /// modifications of unknown position, so all their candidate sites are visible as well.
+    /// # Panics
+    /// If there is an ambiguous modification without a definition, this indicates an error in rustyms.
+    pub fn to_string_verbose(&self) -> String {
+        let mut output = String::new();
+        self.display_inner(&mut output, true, true, true)
+            .expect("Writing to a string cannot fail");
+        output
+    }
+
+    /// Display this peptide, with `verbose` see [`Self::to_string_verbose`].
+    /// # Errors
+    /// If the formatter supplied errors.
+    /// # Panics
+    /// If there is an ambiguous modification without a definition, this indicates an error in rustyms.
+    fn display_inner(
+        &self,
+        f: &mut impl Write,
+        show_global_mods: bool,
+        specification_compliant: bool,
+        verbose: bool,
     ) -> std::fmt::Result {
         if show_global_mods {
-            for (element, isotope) in &self.global {
+            for (element, isotope, location) in &self.global {
                 write!(
                     f,
-                    "<{}{}>",
+                    "<{}{}{}>",
                     isotope.map(|i| i.to_string()).unwrap_or_default(),
-                    element
+                    element,
+                    location.map(|l| format!("@{l}")).unwrap_or_default()
                 )?;
             }
         }
@@ -1113,41 +1661,81 @@ impl<Complexity> LinearPeptide<Complexity> {
             write!(f, "{{{labile}}}")?;
         }
         // Write any modification of unknown position that has no preferred location at the start of the peptide
-        let mut any_ambiguous = false;
-        for (id, ambiguous) in self.ambiguous_modifications.iter().enumerate() {
-            if !ambiguous.is_empty()
-                && ambiguous.iter().all(|i| {
-                    let m = self.sequence[*i]
+        let front = self
+            .ambiguous_modifications
+            .iter()
+            .enumerate()
+            .filter_map(|(id, ambiguous)| {
+                let m = ambiguous.first().map(|i| {
+                    self.sequence[*i]
                         .possible_modifications
                         .iter()
                         .find(|m| m.id == id)
-                        .unwrap();
-                    !m.preferred
-                })
-            {
-                let m = self.sequence[ambiguous[0]]
-                    .possible_modifications
+                        .unwrap()
+                })?;
+                ambiguous
                     .iter()
-                    .find(|m| m.id == id)
-                    .unwrap();
+                    .all(|i| {
+                        !self.sequence[*i]
+                            .possible_modifications
+                            .iter()
+                            .find(|m| m.id == id)
+                            .unwrap()
+                            .preferred
+                    })
+                    .then_some((id, m))
+            })
+            .collect_vec();
+        // Only the consecutive groups `u0`, `u1`, etc. can be written without marked positions as
+        // parsing a modification of unknown position results in exactly these group names
+        let mut unknown_position = Vec::new();
+        for (id, m) in front.iter().filter(|_| !verbose) {
+            if m.group == format!("u{}", unknown_position.len())
+                && self.is_unknown_position_modification(*id, m)
+            {
+                unknown_position.push(*id);
+            }
+        }
+        for (unknown, group) in &front
+            .iter()
+            .chunk_by(|(id, m)| unknown_position.contains(id).then_some(&m.modification))
+        {
+            if let Some(modification) = unknown {
+                // Multiple copies of the same modification of unknown position are written as `[mod]^n`
                 write!(f, "[")?;
-                m.modification.display(f, specification_compliant)?;
-                write!(f, "\x23{}]", m.group)?;
-                any_ambiguous = true;
+                modification.display(f, specification_compliant)?;
+                write!(f, "]")?;
+                let copies = group.count();
+                if copies > 1 {
+                    write!(f, "^{copies}")?;
+                }
+            } else {
+                for (_, m) in group {
+                    write!(f, "[")?;
+                    m.modification.display(f, specification_compliant)?;
+                    write!(f, "\x23{}]", m.group)?;
+                }
             }
         }
-        if any_ambiguous {
+        if !front.is_empty() {
             write!(f, "?")?;
         }
         if let Some(m) = &self.n_term {
             write!(f, "[")?;
             m.display(f, specification_compliant)?;
+            display_info_tags(f, &self.n_term_info_tags)?;
             write!(f, "]-")?;
         }
         let mut placed = Vec::new();
         let mut last_ambiguous = None;
         for position in &self.sequence {
-            placed.extend(position.display(f, &placed, last_ambiguous, specification_compliant)?);
+            placed.extend(position.display(
+                f,
+                &placed,
+                &unknown_position,
+                last_ambiguous,
+                specification_compliant,
+            )?);
             last_ambiguous = position.ambiguous;
         }
         if last_ambiguous.is_some() {
@@ -1156,6 +1744,7 @@ impl<Complexity> LinearPeptide<Complexity> {
         if let Some(m) = &self.c_term {
             write!(f, "-[")?;
             m.display(f, specification_compliant)?;
+            display_info_tags(f, &self.c_term_info_tags)?;
             write!(f, "]")?;
         }
         if let Some(c) = &self.charge_carriers {
@@ -1164,6 +1753,50 @@ impl<Complexity> LinearPeptide<Complexity> {
         Ok(())
     }
 
+    /// Check if the given ambiguous modification can be placed on exactly all positions allowed by
+    /// its placement rules, as happens for modifications of unknown position (`[mod]?PEPTIDE`).
+    fn is_unknown_position_modification(
+        &self,
+        id: usize,
+        modification: &AmbiguousModification,
+    ) -> bool {
+        self.ambiguous_modifications[id]
+            .iter()
+            .copied()
+            .eq((0..self.len()).filter(|i| {
+                modification
+                    .modification
+                    .is_possible(&self.sequence[*i], SequencePosition::Index(*i))
+                    .any_possible()
+            }))
+    }
+
+    /// Get a canonical key for this peptide, useful to deduplicate peptides in a `HashMap`. This
+    /// key is a ProForma string (see [`Self::display`]) with the following normalisations applied:
+    ///  * Modifications are written with their ontology name (e.g. `UNIMOD:35` becomes `U:Oxidation`)
+    ///  * Multiple modifications on the same position are sorted
+    ///  * Global isotope modifications are sorted
+    ///  * Labile modifications and charge carriers are removed as these do not affect the backbone
+    ///
+    /// Note that modifications are not resolved to other definitions, so `[+15.995]` and
+    /// `[Oxidation]` still result in different keys.
+    /// # Panics
+    /// If there is an ambiguous modification without a definition, this indicates an error in rustyms.
+    pub fn sequence_key(&self) -> String {
+        let mut normalised = self.clone();
+        normalised.labile.clear();
+        normalised.charge_carriers = None;
+        normalised.global.sort_unstable();
+        for position in &mut normalised.sequence {
+            position.modifications.sort_unstable();
+        }
+        let mut key = String::new();
+        normalised
+            .display(&mut key, true, false)
+            .expect("Writing to a string cannot fail");
+        key
+    }
+
     /// Look at the provided modifications and see if they match any modification on this peptide with
     /// more information and replace those. Replaces any mass modification within 0.1 Da or any precise
     /// matching formula with the provided modifications.
@@ -1204,31 +1837,137 @@ impl<Complexity> LinearPeptide<Complexity> {
             for m in &mut position.possible_modifications {
                 m.modification = possibly_replace_simple(&m.modification);
             }
+            for (m, _) in &mut position.info_tags {
+                *m = possibly_replace_simple(m);
+            }
         }
         for m in &mut self.labile {
             *m = possibly_replace_simple(m);
         }
     }
 
-    /// Get the reverse of this peptide
+    /// Get the reverse of this peptide. The positions of ambiguous modifications are remapped to
+    /// the reversed sequence. Cross-links and branches move along with the residue (or terminus)
+    /// they are attached to, and for intra-peptide links (both ends on this peptide) the position
+    /// of the other end is remapped as well, so these links still resolve in the reversed peptide.
+    /// Links to other peptides are kept as is, so reversing a single peptide out of a
+    /// [`Peptidoform`] leaves the links from the other peptides pointing to the old positions.
     #[must_use]
     pub fn reverse(&self) -> Self {
+        let flip = |position: SequencePosition| match position {
+            SequencePosition::NTerm => SequencePosition::CTerm,
+            SequencePosition::Index(index) => SequencePosition::Index(self.len() - 1 - index),
+            SequencePosition::CTerm => SequencePosition::NTerm,
+        };
+        // All link ends on this peptide, used to detect which links are intra-peptide links
+        let link_ends: HashSet<(SequencePosition, CrossLinkName)> = self
+            .modifications()
+            .into_iter()
+            .filter_map(|(position, m)| match m {
+                PlacedModification::Defined(Modification::CrossLink { name, .. }) => {
+                    Some((position, name.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+        let remap = |m: &Modification| match m {
+            Modification::CrossLink {
+                peptide,
+                sequence_index,
+                linker,
+                name,
+                side,
+            } if link_ends.contains(&(*sequence_index, name.clone())) => Modification::CrossLink {
+                peptide: *peptide,
+                sequence_index: flip(*sequence_index),
+                linker: linker.clone(),
+                name: name.clone(),
+                side: side.clone(),
+            },
+            m => m.clone(),
+        };
         Self {
-            n_term: self.c_term.clone(),
-            c_term: self.n_term.clone(),
-            sequence: self.sequence.clone().into_iter().rev().collect(),
+            n_term: self.c_term.as_ref().map(remap),
+            c_term: self.n_term.as_ref().map(remap),
+            n_term_info_tags: self.c_term_info_tags.clone(),
+            c_term_info_tags: self.n_term_info_tags.clone(),
+            sequence: self
+                .sequence
+                .iter()
+                .rev()
+                .map(|seq| {
+                    let mut seq = seq.clone();
+                    seq.modifications = seq.modifications.iter().map(remap).collect();
+                    seq
+                })
+                .collect(),
             ambiguous_modifications: self
                 .ambiguous_modifications
                 .clone()
                 .into_iter()
-                .map(|m| m.into_iter().map(|loc| self.len() - loc).collect())
+                .map(|m| {
+                    m.into_iter()
+                        .rev()
+                        .map(|loc| self.len() - 1 - loc)
+                        .collect()
+                })
                 .collect(),
+            internal_n_term: self.internal_c_term,
+            internal_c_term: self.internal_n_term,
             ..self.clone()
         }
     }
+
+    /// Get a copy of this peptide with all terminal, residue, labile, and ambiguous modifications
+    /// removed, leaving the bare amino acid sequence. Useful to compare sequences while ignoring
+    /// any PTMs. The global isotope modifications are only kept if `keep_global` is set. The charge
+    /// carriers and ambiguous amino acid sequences are kept as is.
+    #[must_use]
+    pub fn stripped(&self, keep_global: bool) -> Self {
+        Self {
+            global: if keep_global {
+                self.global.clone()
+            } else {
+                Vec::new()
+            },
+            labile: Vec::new(),
+            n_term: None,
+            c_term: None,
+            n_term_info_tags: Vec::new(),
+            c_term_info_tags: Vec::new(),
+            sequence: self
+                .sequence
+                .iter()
+                .map(|seq| {
+                    let mut seq = seq.clone();
+                    seq.modifications.clear();
+                    seq.possible_modifications.clear();
+                    seq.info_tags.clear();
+                    seq
+                })
+                .collect(),
+            ambiguous_modifications: Vec::new(),
+            charge_carriers: self.charge_carriers.clone(),
+            internal_n_term: self.internal_n_term,
+            internal_c_term: self.internal_c_term,
+            marker: PhantomData,
+        }
+    }
 }
 
 impl LinearPeptide<Linked> {
+    /// Remove all cross-links and branches from this peptide, resulting in a [`Linear`] peptide.
+    /// Note that this also removes the mass of the linker and the mass of any peptide this peptide
+    /// was linked to.
+    pub fn remove_cross_links(mut self) -> LinearPeptide<Linear> {
+        self.n_term = self.n_term.filter(|m| m.simple().is_some());
+        self.c_term = self.c_term.filter(|m| m.simple().is_some());
+        for seq in &mut self.sequence {
+            seq.modifications.retain(|m| m.simple().is_some());
+        }
+        self.mark()
+    }
+
     /// Add a modification to this peptide
     pub(crate) fn add_modification(
         &mut self,
@@ -1253,37 +1992,195 @@ impl LinearPeptide<Linear> {
 }
 
 impl<Complexity: AtMax<Linear>> LinearPeptide<Complexity> {
+    /// Insert a sequence element at the given index, shifting all later elements (and the
+    /// positions of all ambiguous modifications) one place to the right. Any ambiguous
+    /// modification on the new element with an id that is already in use is added as an
+    /// additional possible location for that ambiguous modification. This is only available for
+    /// peptides without cross-links, as the cross-link positions are not updated.
+    /// # Panics
+    /// If the index is bigger than the length of the peptide. Or if the element has an ambiguous
+    /// modification with an id that is not in use in this peptide, as that would leave a dangling
+    /// id.
+    pub fn insert(&mut self, index: usize, element: SequenceElement<Complexity>) {
+        assert!(
+            index <= self.sequence.len(),
+            "Insertion index (is {index}) should be <= len (is {})",
+            self.sequence.len()
+        );
+        for modification in &element.possible_modifications {
+            assert!(
+                modification.id < self.ambiguous_modifications.len(),
+                "Ambiguous modification id (is {}) should be < number of ambiguous modifications (is {})",
+                modification.id,
+                self.ambiguous_modifications.len()
+            );
+        }
+        for positions in &mut self.ambiguous_modifications {
+            for position in positions.iter_mut() {
+                if *position >= index {
+                    *position += 1;
+                }
+            }
+        }
+        for modification in &element.possible_modifications {
+            let positions = &mut self.ambiguous_modifications[modification.id];
+            positions.push(index);
+            positions.sort_unstable();
+        }
+        self.sequence.insert(index, element);
+    }
+
+    /// Remove the sequence element at the given index, shifting all later elements (and the
+    /// positions of all ambiguous modifications) one place to the left. The removed element is
+    /// removed as possible location for all ambiguous modifications. This is only available for
+    /// peptides without cross-links, as the cross-link positions are not updated.
+    /// # Panics
+    /// If the index is out of bounds.
+    pub fn remove(&mut self, index: usize) -> SequenceElement<Complexity> {
+        assert!(
+            index < self.sequence.len(),
+            "Removal index (is {index}) should be < len (is {})",
+            self.sequence.len()
+        );
+        for positions in &mut self.ambiguous_modifications {
+            positions.retain(|p| *p != index);
+            for position in positions.iter_mut() {
+                if *position > index {
+                    *position -= 1;
+                }
+            }
+        }
+        self.sequence.remove(index)
+    }
+
     /// Get a region of this peptide as a new peptide (with all terminal/global/ambiguous modifications).
     #[must_use]
     pub fn sub_peptide(&self, index: impl RangeBounds<usize>) -> Self {
+        let contains_last = self
+            .len()
+            .checked_sub(1)
+            .is_some_and(|last| index.contains(&last));
         Self {
             n_term: if index.contains(&0) {
                 self.n_term.clone()
             } else {
                 None
             },
-            c_term: if index.contains(&(self.len() - 1)) {
+            c_term: if contains_last {
                 self.c_term.clone()
             } else {
                 None
             },
+            n_term_info_tags: if index.contains(&0) {
+                self.n_term_info_tags.clone()
+            } else {
+                Vec::new()
+            },
+            c_term_info_tags: if contains_last {
+                self.c_term_info_tags.clone()
+            } else {
+                Vec::new()
+            },
             sequence: self.sequence[(index.start_bound().cloned(), index.end_bound().cloned())]
                 .to_vec(),
+            internal_n_term: self.internal_n_term || !index.contains(&0),
+            internal_c_term: self.internal_c_term || !contains_last,
             ..self.clone()
         }
     }
 
+    /// Get all N and C terminally truncated forms of this peptide down to the given minimal
+    /// length, for example to explain precursors from ragged peptides or in-source decay. First
+    /// all N terminal truncations are given (`sub_peptide(i..)`, longest first) followed by all C
+    /// terminal truncations (`sub_peptide(..j)`, longest first). The retained terminus keeps its
+    /// modification (see [`Self::sub_peptide`]). The full peptide itself is not included.
+    pub fn truncations(&self, min_length: usize) -> Vec<Self> {
+        let min_length = min_length.max(1);
+        if min_length >= self.len() {
+            return Vec::new();
+        }
+        (1..=self.len() - min_length)
+            .map(|start| self.sub_peptide(start..))
+            .chain(
+                (min_length..self.len())
+                    .rev()
+                    .map(|end| self.sub_peptide(..end)),
+            )
+            .collect()
+    }
+
     /// Digest this sequence with the given protease and the given maximal number of missed cleavages.
     pub fn digest(&self, protease: &Protease, max_missed_cleavages: usize) -> Vec<Self> {
+        self.digest_filtered(protease, max_missed_cleavages, .., ..)
+    }
+
+    /// Get the peptide mass fingerprint of this sequence, the sorted monoisotopic masses of all
+    /// peptides after digestion with the given protease and maximal number of missed cleavages,
+    /// see [`Self::digest`]. If a peptide has multiple possible formulas (for example because of
+    /// B/Z) all are included. Use [`crate::pmf_match`] to compare this to observed masses.
+    pub fn peptide_mass_fingerprint(
+        &self,
+        protease: &Protease,
+        max_missed_cleavages: usize,
+    ) -> Vec<Mass> {
+        self.digest(protease, max_missed_cleavages)
+            .iter()
+            .flat_map(|peptide| {
+                peptide
+                    .formulas()
+                    .iter()
+                    .map(MolecularFormula::monoisotopic_mass)
+                    .collect_vec()
+            })
+            .sorted_unstable_by(|a, b| a.value.total_cmp(&b.value))
+            .collect()
+    }
+
+    /// Digest this sequence with the given protease and the given maximal number of missed cleavages.
+    /// Only peptides with a length (in residues) within the given length range and with a monoisotopic
+    /// mass within the given mass range are returned. If a peptide has multiple possible formulas (for
+    /// example because of B/Z) it is returned if any of these falls within the mass range.
+    ///
+    /// Each returned peptide spans from one cut site (or the N terminus) to a later cut site (or the C
+    /// terminus) with at most `max_missed_cleavages` cut sites within it. Cut sites at the very start
+    /// or end of the sequence are ignored, so no empty peptides are generated.
+    pub fn digest_filtered(
+        &self,
+        protease: &Protease,
+        max_missed_cleavages: usize,
+        length: impl RangeBounds<usize>,
+        mass: impl RangeBounds<Mass>,
+    ) -> Vec<Self> {
         let mut sites = vec![0];
-        sites.extend_from_slice(&protease.match_locations(&self.sequence));
+        sites.extend(
+            protease
+                .match_locations(&self.sequence)
+                .into_iter()
+                .filter(|site| *site > 0 && *site < self.len()),
+        );
         sites.push(self.len());
+        sites.dedup();
 
+        let filter_mass = !matches!(
+            (mass.start_bound(), mass.end_bound()),
+            (Bound::Unbounded, Bound::Unbounded)
+        );
         let mut result = Vec::new();
 
         for (index, start) in sites.iter().enumerate() {
-            for end in sites.iter().skip(index).take(max_missed_cleavages + 1) {
-                result.push(self.sub_peptide((*start)..*end));
+            for end in sites.iter().skip(index + 1).take(max_missed_cleavages + 1) {
+                if !length.contains(&(end - start)) {
+                    continue;
+                }
+                let peptide = self.sub_peptide((*start)..*end);
+                if !filter_mass
+                    || peptide
+                        .formulas()
+                        .iter()
+                        .any(|f| mass.contains(&f.monoisotopic_mass()))
+                {
+                    result.push(peptide);
+                }
             }
         }
         result
@@ -1320,6 +2217,89 @@ impl<Complexity: AtMax<Linear>> LinearPeptide<Complexity> {
         self.generate_theoretical_fragments_inner(max_charge, model, 0, 0, &[])
     }
 
+    /// Get the m/z of a single ion from a primary ion series, for example the y7 2+ ion with
+    /// `ion_mz(FragmentKind::y, 7, Charge::new::<e>(2))`. The ion is charged with the charge
+    /// carriers of this peptide, or protons if these are not specified, and has no neutral losses.
+    /// Multiple options are returned if the ion has multiple possible formulas (for example because
+    /// of B/Z or ambiguous modifications). The radical c· and z· ions are not included.
+    ///
+    /// Returns `None` if the series is not a primary ion series (a, b, c, d, v, w, x, y, or z), if
+    /// the ordinal is outside of `1..=len`, or if the charge is zero.
+    pub fn ion_mz(
+        &self,
+        series: FragmentKind,
+        ordinal: usize,
+        charge: Charge,
+    ) -> Option<Multi<MassOverCharge>> {
+        if ordinal == 0 || ordinal > self.len() || charge.value == 0 {
+            return None;
+        }
+        let sequence_index = match series {
+            FragmentKind::a | FragmentKind::b | FragmentKind::c | FragmentKind::d => ordinal - 1,
+            FragmentKind::v
+            | FragmentKind::w
+            | FragmentKind::x
+            | FragmentKind::y
+            | FragmentKind::z => self.len() - ordinal,
+            _ => return None,
+        };
+        let model = Model::single(
+            series,
+            Location::TakeN {
+                skip: sequence_index,
+                take: 1,
+            },
+        )
+        .c_radical(false)
+        .z_radical(false);
+        let ions: Multi<MassOverCharge> = self
+            .theoretical_fragments_iter(charge, &model)
+            .filter(|f| {
+                f.series() == series && f.neutral_loss.is_none() && f.charge.value == charge.value
+            })
+            .map(|f| f.mz(MassMode::Monoisotopic))
+            .collect();
+        (!ions.is_empty()).then_some(ions)
+    }
+
+    /// Lazily generate the theoretical fragments for this peptide, with the given maximal charge
+    /// of the fragments, and the given model. With the global isotope modifications applied.
+    /// This yields the same fragments as [`Self::generate_theoretical_fragments`] without
+    /// materialising the full list, the fragments are generated one sequence position at a time.
+    ///
+    /// # Panics
+    /// If `max_charge` outside the range `1..=u64::MAX`.
+    pub fn theoretical_fragments_iter<'a>(
+        &'a self,
+        max_charge: Charge,
+        model: &'a Model,
+    ) -> impl Iterator<Item = Fragment> + 'a {
+        self.theoretical_fragments_iter_inner(max_charge, model, 0, 0, &[])
+    }
+
+    /// Generate the theoretical fragments for the cleavage sites within the given range of this
+    /// peptide, with the given maximal charge of the fragments, and the given model. With the
+    /// global isotope modifications applied.
+    ///
+    /// The range selects the sequence indices the fragments originate from, the N terminal ions
+    /// ending at and the C terminal ions starting at these indices. So `2..4` on a peptide of length
+    /// 8 gives b3, b4, y5, and y6 (and all other enabled ion series). The fragments keep the context
+    /// of the full peptide, so C terminal ions still contain the whole C terminal side. The peptide
+    /// wide fragments (precursor, modification, diagnostic, and labile fragments) are not generated.
+    ///
+    /// # Panics
+    /// If `max_charge` outside the range `1..=u64::MAX`.
+    pub fn generate_theoretical_fragments_range(
+        &self,
+        range: Range<usize>,
+        max_charge: Charge,
+        model: &Model,
+    ) -> Vec<Fragment> {
+        let range = range.start.min(self.len())..range.end.min(self.len());
+        self.fragments_at_positions(range, max_charge, model, 0, 0, &[])
+            .collect()
+    }
+
     /// Gives the formulas for the whole peptide. With the global isotope modifications applied. (Any B/Z will result in multiple possible formulas.)
     #[allow(clippy::missing_panics_doc)] // Can not panic (unless state is already corrupted)
     pub fn formulas(&self) -> Multi<MolecularFormula> {
@@ -1341,9 +2321,65 @@ impl<Complexity: AtMax<Linear>> LinearPeptide<Complexity> {
                 .0;
         }
 
+        let restricted = self.restricted_global(..);
+        let global = self.unrestricted_global();
         formulas
             .iter()
-            .map(|f| f.with_global_isotope_modifications(&self.global).expect("Global isotope modification invalid in determination of all formulas for a peptide"))
+            .map(|f| (f + &restricted).with_global_isotope_modifications(&global).expect("Global isotope modification invalid in determination of all formulas for a peptide"))
+            .collect()
+    }
+
+    /// Gives the formula for the whole peptide if there is only a single possible formula, see
+    /// [`Self::formulas`]. Returns None if there are multiple options, for example because the
+    /// peptide contains B or Z. With the global isotope modifications applied.
+    ///
+    /// This is not named `formula` as that name is taken by [`LinearPeptide<UnAmbiguous>::formula`],
+    /// which always gives a single formula for unambiguous peptides.
+    pub fn single_formula(&self) -> Option<MolecularFormula> {
+        let formulas = self.formulas();
+        (formulas.len() == 1).then(|| formulas[0].clone())
+    }
+
+    /// Gives the formulas for the whole peptide including the labile modifications, see
+    /// [`Self::formulas`] and [`LinearPeptide::labile_formula`]. This is the intact precursor, for
+    /// example before in source loss of a glycan, fragments are still generated without the labile
+    /// modifications. With the global isotope modifications applied.
+    #[allow(clippy::missing_panics_doc)] // Can not panic (unless state is already corrupted)
+    pub fn formulas_with_labile(&self) -> Multi<MolecularFormula> {
+        let labile = self
+            .labile_formula()
+            .with_global_isotope_modifications(&self.unrestricted_global())
+            .expect("Global isotope modification invalid in determination of the labile formula for a peptide");
+        self.formulas().iter().map(|f| f + &labile).collect()
+    }
+
+    /// Get the m/z and abundance of the isotope peaks of this peptide as precursor with the given
+    /// charge, with the charge carried by protons. The abundances are normalised to (approximately)
+    /// 1 total area, see [`MolecularFormula::isotopic_distribution`], and only the peaks with an
+    /// abundance of at least `min_abundance` are returned. The peaks are spaced
+    /// [`Fragment::ISOTOPE_SPACING`] Da apart. If the peptide has multiple formulas (because of B
+    /// or Z) the distribution for the first formula is given. A charge of zero gives no peaks.
+    ///
+    /// Only available with crate feature 'isotopes'.
+    #[cfg(feature = "isotopes")]
+    pub fn precursor_isotopes(
+        &self,
+        charge: Charge,
+        min_abundance: f64,
+    ) -> Vec<(MassOverCharge, f64)> {
+        let Some(formula) = self.formulas().first().cloned() else {
+            return Vec::new();
+        };
+        let Some(mz) = formula.mz(charge, crate::IonMode::Positive) else {
+            return Vec::new();
+        };
+        let spacing = da(Fragment::ISOTOPE_SPACING) / charge.to_float();
+        formula
+            .isotopic_distribution(min_abundance)
+            .iter()
+            .enumerate()
+            .filter(|(_, abundance)| **abundance >= min_abundance)
+            .map(|(offset, abundance)| (mz + spacing * offset as f64, *abundance))
             .collect()
     }
 
@@ -1351,6 +2387,42 @@ impl<Complexity: AtMax<Linear>> LinearPeptide<Complexity> {
     pub fn bare_formulas(&self) -> Multi<MolecularFormula> {
         self.bare_formulas_inner(&[], &[], &mut Vec::new(), false, 0)
     }
+
+    /// Check if this peptide matches the given observed precursor. The `mass` is the full observed
+    /// mass of the precursor including charge carriers (so m/z times the charge). The charge carriers
+    /// of this peptide are used if defined, otherwise the precursor is assumed to be protonated with
+    /// the given charge. Any of the formulas of this peptide shifted with any of the allowed isotope
+    /// errors (in number of [isotope spacings](Fragment::ISOTOPE_SPACING)) has to be within the
+    /// tolerance for the peptide to match. This can be used to prefilter candidate peptides before
+    /// generating theoretical fragments.
+    /// # Panics
+    /// If the charge is bigger than [`isize::MAX`].
+    pub fn matches_precursor(
+        &self,
+        mass: Mass,
+        charge: Charge,
+        tolerance: Tolerance<Mass>,
+        isotope_errors: RangeInclusive<i8>,
+    ) -> bool {
+        let default_charge = MolecularCharge::proton(
+            isize::try_from(charge.value)
+                .expect("Charge of the precursor cannot be higher then isize::MAX"),
+        );
+        let charge_carriers = self
+            .charge_carriers
+            .as_ref()
+            .unwrap_or(&default_charge)
+            .formula();
+        self.formulas().iter().any(|formula| {
+            let theoretical = (formula + &charge_carriers).monoisotopic_mass();
+            isotope_errors.clone().any(|isotope| {
+                tolerance.within(
+                    &(theoretical + da(f64::from(isotope) * Fragment::ISOTOPE_SPACING)),
+                    &mass,
+                )
+            })
+        })
+    }
 }
 
 impl LinearPeptide<UnAmbiguous> {
@@ -1377,8 +2449,8 @@ impl LinearPeptide<UnAmbiguous> {
 }
 
 impl<Complexity: AtLeast<Linear>> LinearPeptide<Complexity> {
-    /// Get the global isotope modifications
-    pub fn get_global(&self) -> &[(Element, Option<NonZeroU16>)] {
+    /// Get the global isotope modifications, with the location they are restricted to (if any)
+    pub fn get_global(&self) -> &[(Element, Option<NonZeroU16>, Option<IsotopeLocation>)] {
         &self.global
     }
 
@@ -1386,7 +2458,39 @@ impl<Complexity: AtLeast<Linear>> LinearPeptide<Complexity> {
     #[must_use]
     pub fn add_global(&mut self, modification: (Element, Option<NonZeroU16>)) -> bool {
         if modification.0.is_valid(modification.1) {
-            self.global.push(modification);
+            self.global.push((modification.0, modification.1, None));
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Add a global isotope modification that is only applied to the given location, if the
+    /// isotope is invalid it returns false. Ambiguous amino acids (B/Z) can only be labelled if
+    /// all options contain the same number of atoms of this element.
+    #[must_use]
+    pub fn add_restricted_global(
+        &mut self,
+        modification: (Element, Option<NonZeroU16>),
+        location: IsotopeLocation,
+    ) -> bool {
+        let (element, isotope) = modification;
+        let consistent = match location {
+            IsotopeLocation::AminoAcid(aa) => aa
+                .formulas()
+                .iter()
+                .map(|f| {
+                    f.elements()
+                        .iter()
+                        .filter(|e| e.0 == element && e.1.is_none())
+                        .map(|e| e.2)
+                        .sum::<i32>()
+                })
+                .all_equal(),
+            IsotopeLocation::BackboneAmide => element == Element::H,
+        };
+        if consistent && element.is_valid(isotope) {
+            self.global.push((element, isotope, Some(location)));
             true
         } else {
             false
@@ -1412,6 +2516,63 @@ impl<Complexity: AtLeast<SimpleLinear>> LinearPeptide<Complexity> {
         self.ambiguous_modifications.as_ref()
     }
 
+    /// Get the localisation scores of all ambiguous modifications. For each ambiguous modification
+    /// id this contains all sequence locations where that modification is potentially located
+    /// alongside the localisation score for that location, if one was given.
+    #[allow(clippy::type_complexity)]
+    pub fn ambiguous_modification_scores(&self) -> Vec<(usize, Vec<(usize, Option<f64>)>)> {
+        self.ambiguous_modifications
+            .iter()
+            .enumerate()
+            .map(|(id, positions)| {
+                (
+                    id,
+                    positions
+                        .iter()
+                        .map(|index| {
+                            (
+                                *index,
+                                self.sequence[*index]
+                                    .possible_modifications
+                                    .iter()
+                                    .find(|m| m.id == id)
+                                    .and_then(|m| m.localisation_score.map(|s| s.0)),
+                            )
+                        })
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    /// Get the isoform of this peptide where the ambiguous modification with the given id is
+    /// placed on the given sequence index, all other ambiguous modifications are left untouched.
+    /// Returns None if the index is not a potential location for this ambiguous modification.
+    pub(crate) fn place_ambiguous_modification(&self, id: usize, index: usize) -> Option<Self> {
+        let positions = self.ambiguous_modifications.get(id)?;
+        if !positions.contains(&index) {
+            return None;
+        }
+        let mut isoform = self.clone();
+        for position in positions {
+            let element = &mut isoform.sequence[*position];
+            if let Some(found) = element
+                .possible_modifications
+                .iter()
+                .position(|m| m.id == id)
+            {
+                let modification = element.possible_modifications.remove(found);
+                if *position == index {
+                    element
+                        .modifications
+                        .push(Modification::Simple(modification.modification));
+                }
+            }
+        }
+        isoform.ambiguous_modifications[id].clear();
+        Some(isoform)
+    }
+
     /// Add an ambiguous modification on the given positions, the placement rules are NOT checked.
     /// The `positions` contains all sequence indices where that ambiguous modification is
     /// potentially located alongside the placement probability if known. If there is a preferred
@@ -1435,9 +2596,12 @@ impl<Complexity: AtLeast<SimpleLinear>> LinearPeptide<Complexity> {
                 .possible_modifications
                 .sort_unstable_by(|a, b| a.id.cmp(&b.id));
         }
-        self.ambiguous_modifications
-            .push(positions.iter().map(|(p, _)| *p).collect());
-        self.ambiguous_modifications.sort_unstable();
+        // The ambiguous modifications are indexed by id
+        if self.ambiguous_modifications.len() <= modification.id {
+            self.ambiguous_modifications
+                .resize(modification.id + 1, Vec::new());
+        }
+        self.ambiguous_modifications[modification.id] = positions.iter().map(|(p, _)| *p).collect();
     }
 }
 
@@ -1460,6 +2624,8 @@ impl<OwnComplexity: AtMax<SemiAmbiguous>> LinearPeptide<OwnComplexity> {
                 labile: self.labile.into_iter().chain(other.labile).collect(),
                 n_term: self.n_term,
                 c_term: other.c_term,
+                n_term_info_tags: self.n_term_info_tags,
+                c_term_info_tags: other.c_term_info_tags,
                 sequence: self
                     .sequence
                     .into_iter()
@@ -1468,12 +2634,155 @@ impl<OwnComplexity: AtMax<SemiAmbiguous>> LinearPeptide<OwnComplexity> {
                     .collect(),
                 ambiguous_modifications: Vec::new(),
                 charge_carriers: self.charge_carriers,
+                internal_n_term: self.internal_n_term,
+                internal_c_term: other.internal_c_term,
                 marker: PhantomData,
             })
         } else {
             None
         }
     }
+
+    /// Merge multiple identifications of the same peptide into a consensus peptide, for example
+    /// when combining the results of multiple search engines. All peptides need to have the exact
+    /// same amino acid sequence, otherwise None is returned (this is also returned if no peptides
+    /// are given). The terminal modifications are decided by majority vote. For every modification
+    /// on the sequence the number of copies is decided by majority vote, any copy placed on the
+    /// same location by all peptides is placed there. All other copies are added as ambiguous
+    /// modifications over all locations where the peptides disagree, with the fraction of votes as
+    /// localisation score and the location with the most votes as preferred location.
+    pub fn merge_modifications(peptides: &[Self]) -> Option<LinearPeptide<SimpleLinear>> {
+        // The most common value, on ties the first value encountered wins
+        fn majority<T: Eq>(values: impl IntoIterator<Item = T>) -> Option<T> {
+            let mut counts: Vec<(T, usize)> = Vec::new();
+            for value in values {
+                if let Some(count) = counts.iter_mut().find(|(v, _)| *v == value) {
+                    count.1 += 1;
+                } else {
+                    counts.push((value, 1));
+                }
+            }
+            counts
+                .into_iter()
+                .rev()
+                .max_by_key(|(_, count)| *count)
+                .map(|(value, _)| value)
+        }
+
+        let first = peptides.first()?;
+        if peptides.iter().any(|peptide| {
+            peptide.len() != first.len()
+                || peptide
+                    .sequence
+                    .iter()
+                    .zip(&first.sequence)
+                    .any(|(a, b)| a.aminoacid != b.aminoacid)
+        }) {
+            return None;
+        }
+
+        let mut merged = first.clone().mark::<SimpleLinear>();
+        merged.n_term = majority(peptides.iter().map(|p| p.n_term.clone())).flatten();
+        merged.c_term = majority(peptides.iter().map(|p| p.c_term.clone())).flatten();
+        // The INFO tags of all peptides that agree with the chosen terminal modification
+        merged.n_term_info_tags = peptides
+            .iter()
+            .filter(|p| merged.n_term.is_some() && p.n_term == merged.n_term)
+            .flat_map(|p| p.n_term_info_tags.iter().cloned())
+            .unique()
+            .collect();
+        merged.c_term_info_tags = peptides
+            .iter()
+            .filter(|p| merged.c_term.is_some() && p.c_term == merged.c_term)
+            .flat_map(|p| p.c_term_info_tags.iter().cloned())
+            .unique()
+            .collect();
+        for element in &mut merged.sequence {
+            element.modifications.clear();
+            element.info_tags.clear();
+        }
+
+        let modifications = peptides
+            .iter()
+            .flat_map(|p| p.sequence.iter())
+            .flat_map(|s| s.modifications.iter().filter_map(Modification::simple))
+            .unique()
+            .cloned()
+            .collect_vec();
+        let mut group = 0;
+        for modification in modifications {
+            let info_tags = peptides
+                .iter()
+                .flat_map(|p| p.sequence.iter())
+                .flat_map(|s| s.info_tags(&modification).iter().cloned())
+                .unique()
+                .collect_vec();
+            let count = |peptide: &Self, index: usize| {
+                peptide.sequence[index]
+                    .modifications
+                    .iter()
+                    .filter(|m| m.simple() == Some(&modification))
+                    .count()
+            };
+            let copies = majority(
+                peptides
+                    .iter()
+                    .map(|p| (0..p.len()).map(|i| count(p, i)).sum::<usize>()),
+            )
+            .unwrap_or_default();
+            let mut placed = 0;
+            let mut disputed = Vec::new();
+            for index in 0..merged.len() {
+                let agreed = peptides.iter().map(|p| count(p, index)).min().unwrap_or(0);
+                let votes = peptides
+                    .iter()
+                    .map(|p| count(p, index) - agreed)
+                    .sum::<usize>();
+                for _ in 0..agreed.min(copies - placed) {
+                    merged.sequence[index]
+                        .modifications
+                        .push(Modification::Simple(modification.clone()));
+                }
+                if agreed.min(copies - placed) > 0 {
+                    merged.sequence[index].add_info_tags(&modification, info_tags.clone());
+                }
+                placed += agreed.min(copies - placed);
+                if votes > 0 {
+                    disputed.push((index, votes));
+                }
+            }
+            let total = disputed.iter().map(|(_, votes)| votes).sum::<usize>() as f64;
+            disputed.sort_by_key(|(_, votes)| std::cmp::Reverse(*votes));
+            let positions = disputed
+                .iter()
+                .map(|(index, votes)| (*index, Some(OrderedFloat(*votes as f64 / total))))
+                .sorted()
+                .collect_vec();
+            for preferred in disputed.iter().take(copies - placed) {
+                if positions.len() == 1 {
+                    merged.sequence[preferred.0]
+                        .modifications
+                        .push(Modification::Simple(modification.clone()));
+                    merged.sequence[preferred.0].add_info_tags(&modification, info_tags.clone());
+                } else {
+                    merged.add_ambiguous_modification(
+                        &AmbiguousModification {
+                            id: merged.ambiguous_modifications.len(),
+                            modification: modification.clone(),
+                            localisation_score: None,
+                            group: format!("g{group}"),
+                            preferred: true,
+                            info_tags: info_tags.clone(),
+                        },
+                        &positions,
+                        Some(preferred.0),
+                    );
+                    group += 1;
+                }
+            }
+        }
+        Some(merged)
+    }
 }
 
 impl<Complexity> Display for LinearPeptide<Complexity> {
@@ -1493,9 +2802,13 @@ where
             labile: Vec::new(),
             n_term: None,
             c_term: None,
+            n_term_info_tags: Vec::new(),
+            c_term_info_tags: Vec::new(),
             sequence: value.into_iter().map(std::convert::Into::into).collect(),
             ambiguous_modifications: Vec::new(),
             charge_carriers: None,
+            internal_n_term: false,
+            internal_c_term: false,
             marker: PhantomData,
         }
     }
@@ -1573,3 +2886,152 @@ into!(UnAmbiguous => Linear);
 into!(SemiAmbiguous => SimpleLinear);
 into!(UnAmbiguous => SimpleLinear);
 into!(UnAmbiguous => SemiAmbiguous);
+
+#[cfg(test)]
+#[allow(clippy::missing_panics_doc)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use crate::{system::usize::Charge, AminoAcid, LinearPeptide, Protease};
+
+    #[test]
+    fn protein_terminal_flags() {
+        let protein = LinearPeptide::pro_forma("MAAKPEPTIDEKGGR", None)
+            .unwrap()
+            .into_linear()
+            .unwrap();
+        let peptides = protein.digest(
+            &Protease::c_terminal_of(&[AminoAcid::Lysine, AminoAcid::Arginine]),
+            0,
+        );
+        // Equality and ordering ignore the protein terminal flags
+        let reparsed = LinearPeptide::pro_forma(&peptides[1].to_string(), None)
+            .unwrap()
+            .into_linear()
+            .unwrap();
+        assert!(!peptides[1].is_protein_n_term());
+        assert_eq!(peptides[1], reparsed);
+        assert_eq!(BTreeSet::from([peptides[1].clone(), reparsed]).len(), 1);
+        // The flags survive serialisation
+        let round_trip: LinearPeptide<crate::peptide::Linear> =
+            serde_json::from_str(&serde_json::to_string(&peptides[1]).unwrap()).unwrap();
+        assert!(!round_trip.is_protein_n_term());
+        assert!(!round_trip.is_protein_c_term());
+        // Sub peptides of empty peptides do not underflow
+        let empty = LinearPeptide::<crate::peptide::Linear>::default();
+        assert!(empty.sub_peptide(..).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "isotopes")]
+    fn precursor_isotopes() {
+        let peptide = LinearPeptide::pro_forma("PEPTIDE", None)
+            .unwrap()
+            .into_linear()
+            .unwrap();
+        let isotopes = peptide.precursor_isotopes(Charge::new::<crate::system::e>(2), 0.01);
+        // C34H53N7O15 at 2+, with the M+1 and M+2 peaks half the isotope spacing further
+        let expected = [
+            (400.687_258, 0.648),
+            (401.188_433, 0.254),
+            (401.689_608, 0.068),
+        ];
+        assert_eq!(isotopes.len(), expected.len());
+        for ((mz, abundance), (expected_mz, expected_abundance)) in isotopes.iter().zip(expected) {
+            assert!(
+                (mz.value - expected_mz).abs() < 1e-5,
+                "{} != {expected_mz}",
+                mz.value
+            );
+            assert!(
+                (abundance - expected_abundance).abs() < 0.005,
+                "{abundance} != {expected_abundance}"
+            );
+        }
+        assert!(peptide
+            .precursor_isotopes(Charge::new::<crate::system::e>(0), 0.01)
+            .is_empty());
+    }
+
+    #[test]
+    fn merge_modifications() {
+        let peptide = |text: &str| {
+            LinearPeptide::pro_forma(text, None)
+                .unwrap()
+                .into_semi_ambiguous()
+                .unwrap()
+        };
+        let merged = LinearPeptide::merge_modifications(&[
+            peptide("[Acetyl]-M[Oxidation]S[Phospho]TYK"),
+            peptide("M[Oxidation]S[Phospho]TYK"),
+            peptide("[Acetyl]-M[Oxidation]ST[Phospho]YK"),
+        ])
+        .unwrap();
+        assert_eq!(
+            merged.ambiguous_modification_scores(),
+            vec![(0, vec![(1, Some(2.0 / 3.0)), (2, Some(1.0 / 3.0))])]
+        );
+        assert_eq!(
+            merged.to_string(),
+            format!(
+                "[U:Acetyl]-M[U:Oxidation]S[U:Phospho#g0({})]T[#g0({})]YK",
+                2.0 / 3.0,
+                1.0 / 3.0
+            )
+        );
+        // Full agreement gives the same peptide back
+        let agreed = peptide("M[Oxidation]S[Phospho]TYK");
+        assert_eq!(
+            LinearPeptide::merge_modifications(&[agreed.clone(), agreed.clone()]).unwrap(),
+            agreed
+        );
+        assert!(LinearPeptide::merge_modifications(&[agreed, peptide("MSTYR")]).is_none());
+        assert!(LinearPeptide::<crate::SemiAmbiguous>::merge_modifications(&[]).is_none());
+    }
+
+    #[test]
+    fn labile_formula() {
+        let labile = LinearPeptide::pro_forma("{Glycan:Hex}PEPTIDE", None)
+            .unwrap()
+            .into_linear()
+            .unwrap();
+        let bare = LinearPeptide::pro_forma("PEPTIDE", None)
+            .unwrap()
+            .into_linear()
+            .unwrap();
+        assert_eq!(labile.labile_formula(), molecular_formula!(C 6 H 10 O 5));
+        assert_eq!(bare.labile_formula(), crate::MolecularFormula::default());
+        assert_eq!(labile.formulas(), bare.formulas());
+        assert_eq!(
+            labile.formulas_with_labile()[0],
+            &bare.formulas()[0] + &molecular_formula!(C 6 H 10 O 5)
+        );
+        assert_eq!(bare.formulas_with_labile(), bare.formulas());
+    }
+
+    #[test]
+    fn single_formula() {
+        let isotope = LinearPeptide::pro_forma("<13C>PEPT[Phospho]IDE", None)
+            .unwrap()
+            .into_linear()
+            .unwrap();
+        assert_eq!(
+            isotope.single_formula(),
+            Some(isotope.formulas()[0].clone())
+        );
+        let peptide = LinearPeptide::pro_forma("PEPT[Phospho]IDE", None)
+            .unwrap()
+            .into_linear()
+            .unwrap();
+        assert_eq!(
+            peptide.single_formula(),
+            Some(peptide.into_unambiguous().unwrap().formula())
+        );
+        let ambiguous = LinearPeptide::pro_forma("PEPBIDE", None)
+            .unwrap()
+            .into_linear()
+            .unwrap();
+        assert_eq!(ambiguous.formulas().len(), 2);
+        assert_eq!(ambiguous.single_formula(), None);
+    }
+}
@@ -16,7 +16,10 @@ use crate::{
     Peptidoform, SequenceElement, SequencePosition,
 };
 
-use super::{GlobalModification, Linear, ReturnModification, SemiAmbiguous};
+use super::{
+    parse_modification::parse_info_tags, GlobalModification, IsotopeLocation, Linear,
+    ReturnModification, SemiAmbiguous,
+};
 
 #[derive(Debug, PartialEq, Eq)]
 enum End {
@@ -94,24 +97,63 @@ impl CompoundPeptidoform {
     /// Parse a compound peptidoform in the [ProForma specification](https://github.com/HUPO-PSI/ProForma).
     ///
     /// # Errors
-    /// It fails when the string is not a valid ProForma string.
-    #[allow(clippy::too_many_lines)]
+    /// It fails when the string is not a valid ProForma string. If there are multiple errors only
+    /// the first error is returned, see [`Self::pro_forma_all_errors`] to get all errors.
     pub fn pro_forma(
         value: &str,
         custom_database: Option<&CustomDatabase>,
+    ) -> Result<Self, CustomError> {
+        Self::pro_forma_all_errors(value, custom_database).map_err(|mut errors| errors.remove(0))
+    }
+
+    /// Parse a compound peptidoform in the [ProForma specification](https://github.com/HUPO-PSI/ProForma)
+    /// and report all errors instead of only the first one.
+    ///
+    /// Parsing continues past errors in a single modification (for example an unknown modification
+    /// name), so all invalid modifications are reported. Any other error stops the parsing, so
+    /// that error is the last one in the list.
+    /// # Errors
+    /// It fails when the string is not a valid ProForma string, the returned list is never empty.
+    pub fn pro_forma_all_errors(
+        value: &str,
+        custom_database: Option<&CustomDatabase>,
+    ) -> Result<Self, Vec<CustomError>> {
+        let mut errors = Vec::new();
+        match Self::parse(value, custom_database, &mut errors) {
+            Ok(peptidoform) if errors.is_empty() => Ok(peptidoform),
+            Ok(_) => Err(errors),
+            Err(error) => {
+                errors.push(error);
+                Err(errors)
+            }
+        }
+    }
+
+    /// Parse a compound peptidoform, any errors that could be recovered from are stored in `errors`.
+    /// # Errors
+    /// It fails when the string is not a valid ProForma string.
+    fn parse(
+        value: &str,
+        custom_database: Option<&CustomDatabase>,
+        errors: &mut Vec<CustomError>,
     ) -> Result<Self, CustomError> {
         let mut peptidoforms = Vec::new();
         // Global modification(s)
         let (mut start, global_modifications) = global_modifications(value, 0, custom_database)?;
         let (peptidoform, tail) =
-            Self::parse_peptidoform(value, start, &global_modifications, custom_database)?;
+            Self::parse_peptidoform(value, start, &global_modifications, custom_database, errors)?;
         start = tail;
         peptidoforms.push(peptidoform);
 
         // Parse any following chimeric species
         while start < value.len() {
-            let (peptidoform, tail) =
-                Self::parse_peptidoform(value, start, &global_modifications, custom_database)?;
+            let (peptidoform, tail) = Self::parse_peptidoform(
+                value,
+                start,
+                &global_modifications,
+                custom_database,
+                errors,
+            )?;
             peptidoforms.push(peptidoform);
             start = tail;
         }
@@ -134,7 +176,9 @@ impl CompoundPeptidoform {
         mut index: usize,
         global_modifications: &[GlobalModification],
         custom_database: Option<&CustomDatabase>,
+        errors: &mut Vec<CustomError>,
     ) -> Result<(Peptidoform, usize), CustomError> {
+        let recovered_errors = errors.len();
         let mut peptides = Vec::new();
         let mut ending = End::CrossLink;
         let mut cross_link_lookup = Vec::new();
@@ -143,8 +187,13 @@ impl CompoundPeptidoform {
 
         // Parse any following cross-linked species
         while index < line.len() && ending == End::CrossLink {
-            let mut result =
-                Self::parse_linear_peptide(line, index, custom_database, &mut cross_link_lookup)?;
+            let mut result = Self::parse_linear_peptide(
+                line,
+                index,
+                custom_database,
+                &mut cross_link_lookup,
+                errors,
+            )?;
             if !result
                 .peptide
                 .apply_global_modifications(global_modifications)
@@ -178,6 +227,15 @@ impl CompoundPeptidoform {
                 "The peptidoform definition is empty",
                 Context::full_line(0, line),
             ))
+        } else if ending == End::Chimeric && index >= line.len() {
+            Err(CustomError::error(
+                "No peptide found",
+                "A chimeric separator ('+') has to be followed by another peptide",
+                Context::line(None, line, index.saturating_sub(1), 1),
+            ))
+        } else if errors.len() > recovered_errors {
+            // The peptides are incomplete so cannot be validated, the result is discarded anyway
+            Ok((Peptidoform(Vec::new()), index))
         } else {
             let peptidoform = super::validate::cross_links(
                 peptides,
@@ -197,7 +255,9 @@ impl CompoundPeptidoform {
         mut index: usize,
         custom_database: Option<&CustomDatabase>,
         cross_link_lookup: &mut CrossLinkLookup,
+        errors: &mut Vec<CustomError>,
     ) -> Result<LinearPeptideResult, CustomError> {
+        let recovered_errors = errors.len();
         if line.trim().is_empty() {
             return Err(CustomError::error(
                 "Peptide sequence is empty",
@@ -212,8 +272,14 @@ impl CompoundPeptidoform {
         let mut ambiguous_aa = None;
         let mut ambiguous_lookup = Vec::new();
         let mut cross_link_found_positions: Vec<(usize, SequencePosition)> = Vec::new();
-        let mut ambiguous_found_positions: Vec<(usize, bool, usize, Option<OrderedFloat<f64>>)> =
-            Vec::new();
+        #[allow(clippy::type_complexity)]
+        let mut ambiguous_found_positions: Vec<(
+            usize,
+            bool,
+            usize,
+            Option<OrderedFloat<f64>>,
+            Vec<String>,
+        )> = Vec::new();
         let mut unknown_position_modifications = Vec::new();
         let mut ranged_unknown_position_modifications = Vec::new();
         let mut ending = End::Empty;
@@ -248,28 +314,32 @@ impl CompoundPeptidoform {
                     "No valid closing delimiter, an N terminal modification should be closed by ']-'",
                     Context::line(None, line, index, 1),
                 ))?;
-            peptide.set_simple_n_term(
-                SimpleModification::try_from(
-                    line,
-                    index + 1..end_index - 1,
-                    &mut ambiguous_lookup,
-                    cross_link_lookup,
-                    custom_database,
-                )
-                .and_then(|m| match m {
-                    ReturnModification::Defined(simple) => Ok(Some(simple)),
-                    ReturnModification::CrossLinkReferenced(id) => {
-                        cross_link_found_positions.push((id, SequencePosition::NTerm));
-                        Ok(None)
-                    }
-                    ReturnModification::AmbiguousPreferred(_, _)
-                    | ReturnModification::AmbiguousReferenced(_, _) => Err(CustomError::error(
-                        "Invalid N terminal modification",
-                        "An N terminal modification cannot be ambiguous",
-                        Context::line(None, line, index + 1, end_index - 2 - index),
-                    )),
-                })?,
-            );
+            match SimpleModification::try_from(
+                line,
+                index + 1..end_index - 1,
+                &mut ambiguous_lookup,
+                cross_link_lookup,
+                custom_database,
+            )
+            .and_then(|m| match m {
+                ReturnModification::Defined(simple) => Ok(Some(simple)),
+                ReturnModification::CrossLinkReferenced(id) => {
+                    cross_link_found_positions.push((id, SequencePosition::NTerm));
+                    Ok(None)
+                }
+                ReturnModification::AmbiguousPreferred(_, _)
+                | ReturnModification::AmbiguousReferenced(_, _) => Err(CustomError::error(
+                    "Invalid N terminal modification",
+                    "An N terminal modification cannot be ambiguous",
+                    Context::line(None, line, index + 1, end_index - 2 - index),
+                )),
+            }) {
+                Ok(modification) => {
+                    peptide.set_simple_n_term(modification);
+                    peptide.set_n_term_info_tags(parse_info_tags(line, index + 1..end_index - 1));
+                }
+                Err(error) => errors.push(error),
+            }
             index = end_index + 1;
         }
 
@@ -319,8 +389,7 @@ impl CompoundPeptidoform {
                     index += 1;
                 }
                 (false, b')') if braces_start.is_some() => {
-                    let start = braces_start.unwrap();
-                    braces_start = None;
+                    let start = braces_start.take().unwrap_or_default();
                     index += 1;
                     while chars.get(index) == Some(&b'[') {
                         let end_index = end_of_enclosure(line, index+1, b'[', b']').ok_or_else(||CustomError::error(
@@ -331,17 +400,20 @@ impl CompoundPeptidoform {
                         let modification = SimpleModification::try_from(
                             line, index + 1..end_index,
                             &mut ambiguous_lookup, cross_link_lookup, custom_database,
-                        )?.defined().ok_or_else(|| CustomError::error(
+                        ).and_then(|m| m.defined().ok_or_else(|| CustomError::error(
                             "Invalid ranged ambiguous modification",
                             "A ranged ambiguous modification has to be fully defined, so no ambiguous modification is allowed",
                             Context::line(None, line, index, 1),
-                        ))?;
+                        )));
                         index = end_index + 1;
-                        ranged_unknown_position_modifications.push((
-                            start,
-                            peptide.len().saturating_sub(1),
-                            modification,
-                        ));
+                        match modification {
+                            Ok(modification) => ranged_unknown_position_modifications.push((
+                                start,
+                                peptide.len().saturating_sub(1),
+                                modification,
+                            )),
+                            Err(error) => errors.push(error),
+                        }
                     }
                 }
                 (false, b'/') => {
@@ -366,10 +438,19 @@ impl CompoundPeptidoform {
                         "No valid closing delimiter",
                         Context::line(None, line, index, 1),
                     ))?;
-                    let modification = SimpleModification::try_from(
+                    let modification = match SimpleModification::try_from(
                         line, index + 1..end_index,
                         &mut ambiguous_lookup, cross_link_lookup, custom_database,
-                    )?;
+                    ) {
+                        Ok(modification) => modification,
+                        // An invalid modification on a side chain can be skipped to continue parsing
+                        Err(error) if !is_c_term => {
+                            errors.push(error);
+                            index = end_index + 1;
+                            continue;
+                        }
+                        Err(error) => return Err(error),
+                    };
                     let start_index = index +1;
                     index = end_index + 1;
                     if is_c_term {
@@ -385,6 +466,9 @@ impl CompoundPeptidoform {
                                         Context::line(None, line, index + 1, end_index.saturating_sub(2 + index)),
                                     )),
                             }?);
+                        if peptide.get_c_term().is_some() {
+                            peptide.set_c_term_info_tags(parse_info_tags(line, start_index..end_index));
+                        }
 
                         if index + 1 < chars.len() && chars[index] == b'/' && chars[index+1] != b'/' {
                             let (buf, charge_carriers) = parse_charge_state(line, index)?;
@@ -403,12 +487,16 @@ impl CompoundPeptidoform {
                     }
 
                     if let Some((sequence_index, aa)) = peptide.sequence_mut().iter_mut().enumerate().next_back() {
+                        let info_tags = parse_info_tags(line, start_index..end_index);
                         match modification {
-                            ReturnModification::Defined(m) => aa.modifications.push(Modification::Simple(m)),
+                            ReturnModification::Defined(m) => {
+                                aa.add_info_tags(&m, info_tags);
+                                aa.modifications.push(Modification::Simple(m));
+                            }
                             ReturnModification::AmbiguousPreferred(id, localisation_score) =>
-                                ambiguous_found_positions.push((sequence_index, true, id, localisation_score)),
+                                ambiguous_found_positions.push((sequence_index, true, id, localisation_score, info_tags)),
                             ReturnModification::AmbiguousReferenced(id, localisation_score) =>
-                                ambiguous_found_positions.push((sequence_index, false, id, localisation_score)),
+                                ambiguous_found_positions.push((sequence_index, false, id, localisation_score, info_tags)),
                             ReturnModification::CrossLinkReferenced(id) =>
                                 cross_link_found_positions.push((id, SequencePosition::Index(sequence_index))),
                         }
@@ -482,6 +570,15 @@ impl CompoundPeptidoform {
                 Context::full_line(0, line),
             ));
         }
+        if errors.len() > recovered_errors {
+            // Not all modifications are known so the peptide cannot be validated
+            return Ok(LinearPeptideResult {
+                peptide,
+                index,
+                ending,
+                cross_links: cross_link_found_positions,
+            });
+        }
 
         // Fill in ambiguous positions, ambiguous contains (index, preferred, id, localisation_score)
         for (id, ambiguous) in ambiguous_found_positions
@@ -500,10 +597,15 @@ impl CompoundPeptidoform {
                 group: ambiguous_lookup[id].0.clone(),
                 localisation_score: None,
                 preferred: false,
+                info_tags: ambiguous
+                    .iter()
+                    .flat_map(|p| p.4.iter().cloned())
+                    .unique()
+                    .collect(),
             };
             let positions = ambiguous
                 .iter()
-                .map(|(index, _, _, score)| (*index, *score))
+                .map(|(index, _, _, score, _)| (*index, *score))
                 .collect_vec();
             let preferred = ambiguous.iter().find(|p| p.1).map(|p| p.0);
             peptide.add_ambiguous_modification(&modification, &positions, preferred);
@@ -512,7 +614,7 @@ impl CompoundPeptidoform {
         peptide.apply_unknown_position_modification(&unknown_position_modifications)?;
         peptide.apply_ranged_unknown_position_modification(
             &ranged_unknown_position_modifications,
-            ambiguous_lookup.len(),
+            peptide.get_ambiguous_modifications().len(),
             unknown_position_modifications.len(),
         )?;
         peptide.enforce_modification_rules()?;
@@ -545,25 +647,51 @@ pub(super) fn global_modifications(
                     Context::line(None, line, index, 1),
                 )
             })?;
-        if let Some(offset) = next_char(chars, index, b'@') {
-            let at_index = index + 1 + offset;
-            if at_index > end_index {
+        // The index of the first character after the at sign, if there is an at sign
+        let at_index = next_char(&chars[..end_index], index + 1, b'@').map(|i| i + 1);
+        if chars.get(index + 1) == Some(&b'[') {
+            let Some(at_index) = at_index else {
                 return Err(CustomError::error(
                     "Invalid global modification",
                     "A global modification should have an at '@' sign inside the enclosing angle brackets '<>'",
-                    Context::line(None, line, index + 1, at_index - index - 2),
+                    Context::line(None, line, index + 1, end_index - index - 1),
                 ));
-            }
-            if chars[index + 1] != b'[' || chars[at_index - 2] != b']' {
+            };
+            // The index of the closing square bracket, optionally followed by a copy number `^n`
+            let close_index = line[index + 1..at_index - 1]
+                .rfind(']')
+                .map(|i| i + index + 1)
+                .ok_or_else(|| {
+                    CustomError::error(
+                        "Invalid global modification",
+                        "A global modification should always be enclosed in square brackets '[]'",
+                        Context::line(None, line, index + 1, at_index - index - 2),
+                    )
+                })?;
+            let copies = if close_index == at_index - 2 {
+                1
+            } else if chars.get(close_index + 1) == Some(&b'^') {
+                line[close_index + 2..at_index - 1]
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|n| *n > 0)
+                    .ok_or_else(|| {
+                        CustomError::error(
+                            "Invalid global modification",
+                            "A global modification with multiple copies needs a positive copy number after the caret ('^') symbol",
+                            Context::line(None, line, close_index + 1, at_index - close_index - 2),
+                        )
+                    })?
+            } else {
                 return Err(CustomError::error(
                     "Invalid global modification",
                     "A global modification should always be enclosed in square brackets '[]'",
                     Context::line(None, line, index + 1, at_index - index - 2),
                 ));
-            }
+            };
             let modification = SimpleModification::try_from(
                 line,
-                index + 2..at_index - 2,
+                index + 2..close_index,
                 &mut Vec::new(),
                 &mut Vec::new(),
                 custom_database,
@@ -573,11 +701,12 @@ pub(super) fn global_modifications(
                     CustomError::error(
                         "Invalid global modification",
                         "A global modification cannot be ambiguous or a cross-linker",
-                        Context::line(None, line, index + 2, at_index - index - 4),
+                        Context::line(None, line, index + 2, close_index - index - 2),
                     )
                 })
             })
             .flat_err()?;
+            let first = global_modifications.len();
             for aa in line[at_index..end_index].split(',') {
                 if aa.to_ascii_lowercase().starts_with("n-term") {
                     if let Some((_, aa)) = aa.split_once(':') {
@@ -643,43 +772,40 @@ pub(super) fn global_modifications(
                     ));
                 }
             }
-        } else if &line[index + 1..end_index].to_ascii_lowercase() == "d" {
-            global_modifications.push(GlobalModification::Isotope(Element::H, NonZeroU16::new(2)));
-        } else {
-            let num = &line[index + 1..end_index]
-                .chars()
-                .take_while(char::is_ascii_digit)
-                .collect::<String>();
-            let el = &line[index + 1 + num.len()..end_index];
-            let el: Element = el.try_into().map_err(|()| {
-                CustomError::error(
-                    "Invalid global modification",
-                    "Could not determine the element",
-                    Context::line(
-                        None,
-                        line,
-                        index + num.len(),
-                        end_index - (index + 1 + num.len()),
-                    ),
-                )
-            })?;
-            let num = Some(num.parse::<NonZeroU16>().map_err(|err| {
-                CustomError::error(
-                    "Invalid global modification",
-                    format!("The isotope number is {}", explain_number_error(&err)),
-                    Context::line(None, line, index + 1, end_index - index),
-                )
-            })?);
-            if !el.is_valid(num) {
-                return Err(CustomError::error(
-                    "Invalid global modification",
-                    format!(
-                        "This element {el} does not have a defined weight {}",
-                        num.map_or_else(String::new, |num| format!("for isotope {num}"))
-                    ),
-                    Context::line(None, line, index + 1, end_index - index),
-                ));
+            let defined = global_modifications[first..].to_vec();
+            for _ in 1..copies {
+                global_modifications.extend_from_slice(&defined);
+            }
+        } else if let Some(at_index) = at_index {
+            let (el, num) = global_isotope(line, index + 1..at_index - 1)?;
+            let mut offset = at_index;
+            for location in line[at_index..end_index].split(',') {
+                let location_context = Context::line(None, line, offset, location.len());
+                offset += location.len() + 1;
+                let location = if location.eq_ignore_ascii_case("backbone-amide") {
+                    if el != Element::H {
+                        return Err(CustomError::error(
+                            "Invalid global modification",
+                            "Only hydrogen isotopes can be placed on the backbone amide",
+                            location_context,
+                        ));
+                    }
+                    IsotopeLocation::BackboneAmide
+                } else {
+                    IsotopeLocation::AminoAcid(TryInto::<AminoAcid>::try_into(location).map_err(
+                        |()| {
+                            CustomError::error(
+                                "Invalid global modification",
+                                "The location could not be read as an amino acid or 'backbone-amide'",
+                                location_context,
+                            )
+                        },
+                    )?)
+                };
+                global_modifications.push(GlobalModification::RestrictedIsotope(el, num, location));
             }
+        } else {
+            let (el, num) = global_isotope(line, index + 1..end_index)?;
             global_modifications.push(GlobalModification::Isotope(el, num));
         }
 
@@ -688,14 +814,61 @@ pub(super) fn global_modifications(
     Ok((index, global_modifications))
 }
 
+/// Parse the isotope of a global isotope modification, either `D` or an isotope number followed by
+/// an element (eg `13C`)
+/// # Errors
+/// If the element or isotope number is not valid
+fn global_isotope(
+    line: &str,
+    range: std::ops::Range<usize>,
+) -> Result<(Element, Option<NonZeroU16>), CustomError> {
+    if line[range.clone()].eq_ignore_ascii_case("d") {
+        return Ok((Element::H, NonZeroU16::new(2)));
+    }
+    let num = &line[range.clone()]
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect::<String>();
+    let el: Element = line[range.start + num.len()..range.end]
+        .try_into()
+        .map_err(|()| {
+            CustomError::error(
+                "Invalid global modification",
+                "Could not determine the element",
+                Context::line(
+                    None,
+                    line,
+                    range.start + num.len(),
+                    range.end - (range.start + num.len()),
+                ),
+            )
+        })?;
+    let num = Some(num.parse::<NonZeroU16>().map_err(|err| {
+        CustomError::error(
+            "Invalid global modification",
+            format!("The isotope number is {}", explain_number_error(&err)),
+            Context::line(None, line, range.start, range.len()),
+        )
+    })?);
+    if !el.is_valid(num) {
+        return Err(CustomError::error(
+            "Invalid global modification",
+            format!(
+                "This element {el} does not have a defined weight {}",
+                num.map_or_else(String::new, |num| format!("for isotope {num}"))
+            ),
+            Context::line(None, line, range.start, range.len()),
+        ));
+    }
+    Ok((el, num))
+}
+
 /// A list of found modifications
 type UnknownPositionMods = (usize, Vec<SimpleModification>);
 /// If the text is recognised as a unknown mods list it is Some(..), if it has errors during parsing Some(Err(..))
 /// The returned happy path contains the mods and the index from where to continue parsing.
 /// # Errors
 /// Give all errors when the text cannot be read as mods of unknown position.
-/// # Panics
-/// Breaks if the text is not valid UTF-8
 pub(super) fn unknown_position_mods(
     chars: &[u8],
     start: usize,
@@ -711,9 +884,9 @@ pub(super) fn unknown_position_mods(
     // Parse until no new modifications are found
     while chars.get(index) == Some(&b'[') {
         let start_index = index;
-        index = next_char(chars, index + 1, b']')? + 1;
+        index = end_of_enclosure(line, index + 1, b'[', b']')? + 1;
         let modification = match SimpleModification::try_from(
-            std::str::from_utf8(chars).unwrap(),
+            line,
             start_index + 1..index - 1,
             ambiguous_lookup,
             &mut cross_link_lookup,
@@ -742,18 +915,18 @@ pub(super) fn unknown_position_mods(
                 index += len + 1;
                 if num < 0 {
                     errs.push(
-                        CustomError::error("Invalid unknown position modification", "A modification of unknown position with multiple copies cannot have more a negative number of copies", Context::line(None, std::str::from_utf8(chars).unwrap(), index, 1)));
+                        CustomError::error("Invalid unknown position modification", "A modification of unknown position with multiple copies cannot have more a negative number of copies", Context::line(None, line, index, 1)));
                     0
                 } else if num > i16::MAX as isize {
                     errs.push(
-                        CustomError::error("Invalid unknown position modification", format!("A modification of unknown position with multiple copies cannot have more then {} copies", i16::MAX), Context::line(None, std::str::from_utf8(chars).unwrap(), index, 1)));
+                        CustomError::error("Invalid unknown position modification", format!("A modification of unknown position with multiple copies cannot have more then {} copies", i16::MAX), Context::line(None, line, index, 1)));
                     0
                 } else {
                     num as usize
                 }
             } else {
                 errs.push(
-                    CustomError::error("Invalid unknown position modification", "A modification of unknown position with multiple copies needs the copy number after the caret ('^') symbol", Context::line(None, std::str::from_utf8(chars).unwrap(), index, 1)));
+                    CustomError::error("Invalid unknown position modification", "A modification of unknown position with multiple copies needs the copy number after the caret ('^') symbol", Context::line(None, line, index, 1)));
                 0
             }
         } else {
@@ -812,6 +985,40 @@ fn labile_modifications(
     Ok((index, labile))
 }
 
+impl MolecularCharge {
+    /// Parse charge carriers from the ProForma adduct ion notation, for example `[+2Na+,-H+]`.
+    /// The adduct ions can be preceded by the total charge as in a peptide charge state
+    /// `1[+2Na+,-H+]`, in which case it has to be equal to the sum of the adduct ions. A bare
+    /// number is interpreted as that number of protons. This accepts the output of
+    /// [`Display`](std::fmt::Display), so it can be used to store charge carriers in configuration.
+    /// # Errors
+    /// If the text is not valid adduct ion notation.
+    pub fn from_pro_forma_adducts(value: &str) -> Result<Self, CustomError> {
+        let value = value.trim();
+        let (end, charge) = if value.starts_with('[') {
+            let end_index = end_of_enclosure(value, 1, b'[', b']').ok_or_else(|| {
+                CustomError::error(
+                    "Invalid adduct ion",
+                    "No valid closing delimiter",
+                    Context::line(None, value, 0, 1),
+                )
+            })?;
+            (end_index + 1, parse_adduct_ions(value, 1..end_index)?.1)
+        } else {
+            parse_charge(value, 0)?
+        };
+        if end == value.len() {
+            Ok(charge)
+        } else {
+            Err(CustomError::error(
+                "Invalid adduct ion",
+                "There is text after the end of the adduct ions",
+                Context::line(None, value, end, value.len() - end),
+            ))
+        }
+    }
+}
+
 /// Parse a charge state `/2` or more complex ones like `/2[+2Na+]`.
 /// Assumes the text starts with `/`.
 /// # Errors
@@ -822,127 +1029,144 @@ pub(super) fn parse_charge_state(
     line: &str,
     index: usize,
 ) -> Result<(usize, MolecularCharge), CustomError> {
+    parse_charge(line, index + 1)
+}
+
+/// Parse a charge `2` or more complex ones like `2[+2Na+]`, starting at the given index.
+/// # Errors
+/// If the charge is not following the specification.
+fn parse_charge(line: &str, index: usize) -> Result<(usize, MolecularCharge), CustomError> {
     let chars = line.as_bytes();
-    let (charge_len, total_charge) = next_num(chars, index + 1, false).ok_or_else(|| {
+    let (charge_len, total_charge) = next_num(chars, index, false).ok_or_else(|| {
         CustomError::error(
             "Invalid peptide charge state",
             "There should be a number dictating the total charge of the peptide",
-            Context::line(None, line, index + 1, 1),
+            Context::line(None, line, index, 1),
         )
     })?;
-    if chars.get(index + 1 + charge_len) == Some(&b'[') {
+    if chars.get(index + charge_len) == Some(&b'[') {
         let end_index =
-            end_of_enclosure(line, index + 2 + charge_len, b'[', b']').ok_or_else(|| {
+            end_of_enclosure(line, index + 1 + charge_len, b'[', b']').ok_or_else(|| {
                 CustomError::error(
                     "Invalid adduct ion",
                     "No valid closing delimiter",
-                    Context::line(None, line, index + 2 + charge_len, 1),
+                    Context::line(None, line, index + 1 + charge_len, 1),
                 )
             })?;
-        let mut offset = index + 2 + charge_len;
-        let mut charge_carriers = Vec::new();
-        let mut found_charge: isize = 0;
+        let (found_charge, charge) = parse_adduct_ions(line, index + 1 + charge_len..end_index)?;
+        if total_charge == found_charge {
+            Ok((end_index + 1, charge))
+        } else {
+            Err(CustomError::error(
+                "Invalid peptide charge state",
+                "The peptide charge state number has to be equal to the sum of all separate adduct ions",
+                Context::line(None, line, index, end_index + 1 - index),
+            ))
+        }
+    } else {
+        // If no adduct ions are provided assume it is just protons
+        Ok((index + charge_len, MolecularCharge::proton(total_charge)))
+    }
+}
 
-        for set in chars[index + 2 + charge_len..end_index].split(|c| *c == b',') {
-            // num
-            let (count_len, count) = next_num(chars, offset, true).ok_or_else(|| {
-                CustomError::error(
-                    "Invalid adduct ion",
-                    "Invalid adduct ion count",
-                    Context::line(None, line, offset, 1),
-                )
-            })?;
+/// Parse a list of adduct ions `+2Na+,-H+`, the range should only contain the ions not the
+/// enclosing square brackets. Returns the total charge of the adduct ions and the charge carriers.
+/// # Errors
+/// If the adduct ions are not following the specification.
+fn parse_adduct_ions(
+    line: &str,
+    range: std::ops::Range<usize>,
+) -> Result<(isize, MolecularCharge), CustomError> {
+    let chars = line.as_bytes();
+    let mut offset = range.start;
+    let mut charge_carriers = Vec::new();
+    let mut found_charge: isize = 0;
 
-            // charge
-            let charge_len = set.iter().rev().take_while(|c| c.is_ascii_digit()).count();
-            let charge = if charge_len == 0 {
-                1
-            } else {
-                line[offset + set.len() - charge_len..offset + set.len()]
-                    .parse::<i32>()
-                    .map_err(|err| {
-                        CustomError::error(
-                            "Invalid adduct ion",
-                            format!("The adduct ion number {err}"),
-                            Context::line(None, line, offset + set.len() - charge_len, charge_len),
-                        )
-                    })?
-            };
-            let (charge_len, charge) = match (set.len() - charge_len)
-                .checked_sub(1)
-                .and_then(|i| set.get(i))
-            {
-                Some(b'+') => (charge_len + 1, charge),
-                Some(b'-') => (charge_len + 1, -charge),
-                _ => {
-                    return Err(CustomError::error(
-                        "Invalid adduct ion",
-                        "The adduct ion number should be preceded by a sign",
-                        Context::line(None, line, offset + set.len() - charge_len - 1, 1),
-                    ))
-                }
-            };
+    for set in chars[range.clone()].split(|c| *c == b',') {
+        // num
+        let (count_len, count) = next_num(chars, offset, true).ok_or_else(|| {
+            CustomError::error(
+                "Invalid adduct ion",
+                "Invalid adduct ion count",
+                Context::line(None, line, offset, 1),
+            )
+        })?;
 
-            // Check for empty formula
-            if count_len + charge_len == set.len() {
+        // charge
+        let charge_len = set.iter().rev().take_while(|c| c.is_ascii_digit()).count();
+        let charge = if charge_len == 0 {
+            1
+        } else {
+            line[offset + set.len() - charge_len..offset + set.len()]
+                .parse::<i32>()
+                .map_err(|err| {
+                    CustomError::error(
+                        "Invalid adduct ion",
+                        format!("The adduct ion number {err}"),
+                        Context::line(None, line, offset + set.len() - charge_len, charge_len),
+                    )
+                })?
+        };
+        let (charge_len, charge) = match (set.len() - charge_len)
+            .checked_sub(1)
+            .and_then(|i| set.get(i))
+        {
+            Some(b'+') => (charge_len + 1, charge),
+            Some(b'-') => (charge_len + 1, -charge),
+            _ => {
                 return Err(CustomError::error(
                     "Invalid adduct ion",
-                    "The adduct ion should have a formula defined",
-                    Context::line(None, line, offset, set.len()),
-                ));
+                    "The adduct ion number should be preceded by a sign",
+                    Context::line(None, line, offset + set.len() - charge_len - 1, 1),
+                ))
             }
+        };
 
-            // formula
-            let mut formula = MolecularFormula::from_pro_forma(
-                line,
-                offset + count_len..offset + set.len() - charge_len,
-                true,
-                false,
-            )?;
-            let _ = formula.add((
-                Element::Electron,
-                None,
-                formula.charge().value as i32 - charge,
+        // Check for empty formula
+        if count_len + charge_len == set.len() {
+            return Err(CustomError::error(
+                "Invalid adduct ion",
+                "The adduct ion should have a formula defined",
+                Context::line(None, line, offset, set.len()),
             ));
+        }
 
-            // Deduplicate
-            if let Some((amount, _)) = charge_carriers.iter_mut().find(|(_, f)| *f == formula) {
-                *amount += count;
-            } else {
-                charge_carriers.push((count, formula));
-            }
+        // formula
+        let mut formula = MolecularFormula::from_pro_forma(
+            line,
+            offset + count_len..offset + set.len() - charge_len,
+            true,
+            false,
+        )?;
+        let _ = formula.add((
+            Element::Electron,
+            None,
+            formula.charge().value as i32 - charge,
+        ));
 
-            offset += set.len() + 1;
-            found_charge = found_charge
-                .checked_add(count.checked_mul(charge as isize).ok_or_else(|| {
-                    CustomError::error(
-                        "Invalid peptide charge state",
-                        "The peptide charge state is too big to store inside an isize",
-                        Context::line(None, line, index, offset),
-                    )
-                })?)
-                .ok_or_else(|| {
-                    CustomError::error(
-                        "Invalid peptide charge state",
-                        "The peptide charge state is too big to store inside an isize",
-                        Context::line(None, line, index, offset),
-                    )
-                })?;
-        }
-        if total_charge == found_charge {
-            Ok((end_index + 1, MolecularCharge::new(&charge_carriers)))
+        // Deduplicate
+        if let Some((amount, _)) = charge_carriers.iter_mut().find(|(_, f)| *f == formula) {
+            *amount += count;
         } else {
-            Err(CustomError::error(
-                "Invalid peptide charge state",
-                "The peptide charge state number has to be equal to the sum of all separate adduct ions",
-                Context::line(None, line, index, offset),
-            ))
+            charge_carriers.push((count, formula));
         }
-    } else {
-        // If no adduct ions are provided assume it is just protons
-        Ok((
-            index + charge_len + 1,
-            MolecularCharge::proton(total_charge),
-        ))
+
+        offset += set.len() + 1;
+        found_charge = found_charge
+            .checked_add(count.checked_mul(charge as isize).ok_or_else(|| {
+                CustomError::error(
+                    "Invalid peptide charge state",
+                    "The peptide charge state is too big to store inside an isize",
+                    Context::line(None, line, range.start, offset - range.start),
+                )
+            })?)
+            .ok_or_else(|| {
+                CustomError::error(
+                    "Invalid peptide charge state",
+                    "The peptide charge state is too big to store inside an isize",
+                    Context::line(None, line, range.start, offset - range.start),
+                )
+            })?;
     }
+    Ok((found_charge, MolecularCharge::new(&charge_carriers)))
 }
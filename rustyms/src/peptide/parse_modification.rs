@@ -15,7 +15,7 @@ use crate::{
     ontologies::CustomDatabase,
     placement_rule::Position,
     system::{dalton, Mass, OrderedMass},
-    AminoAcid, Element, MolecularFormula,
+    AminoAcid, Element, MolecularFormula, MultiChemical,
 };
 
 impl SimpleModification {
@@ -39,7 +39,7 @@ impl SimpleModification {
         let mut last_result = Ok(None);
         let mut last_error = None;
         let mut offset = range.start;
-        for part in line[range].split('|') {
+        for part in split_unescaped(&line[range], b'|') {
             last_result = parse_single_modification(
                 line,
                 part,
@@ -69,6 +69,19 @@ impl SimpleModification {
     }
 }
 
+/// Get the text of all `INFO` tags in the modification definition in the given range, with any
+/// escaping removed.
+pub fn parse_info_tags(line: &str, range: Range<usize>) -> Vec<String> {
+    split_unescaped(&line[range], b'|')
+        .into_iter()
+        .filter_map(|part| {
+            part.get(..5)
+                .filter(|tag| tag.eq_ignore_ascii_case("info:"))
+                .map(|_| unescape(&part[5..]).into_owned())
+        })
+        .collect()
+}
+
 static MOD_REGEX: OnceLock<Regex> = OnceLock::new();
 
 /// # Errors
@@ -84,19 +97,25 @@ fn parse_single_modification(
 ) -> Result<Option<ReturnModification>, CustomError> {
     // Parse the whole intricate structure of the single modification (see here in action: https://regex101.com/r/pW5gsj/1)
     let regex = MOD_REGEX.get_or_init(|| {
-        Regex::new(r"^(([^:#]*)(?::([^#]+))?)(?:#([0-9A-Za-z]+)(?:\((\d+\.\d+)\))?)?$").unwrap()
+        Regex::new(
+            r"^(((?:[^:#\\]|\\.)*)(?::((?:[^#\\]|\\.)+))?)(?:#([0-9A-Za-z]+)(?:\((\d+\.\d+)\))?)?$",
+        )
+        .unwrap()
     });
     if let Some(groups) = regex.captures(full_modification) {
         // Capture the full mod name (head:tail), head, tail, ambiguous group, and localisation score
+        // Any escaped characters in the name are unescaped
         let (full, head, tail, label_group, localisation_score) = (
             groups
                 .get(1)
-                .map(|m| (m.as_str(), m.start(), m.len()))
+                .map(|m| (unescape(m.as_str()), m.start(), m.len()))
                 .unwrap_or_default(),
             groups
                 .get(2)
                 .map(|m| (m.as_str().to_ascii_lowercase(), m.start(), m.len())),
-            groups.get(3).map(|m| (m.as_str(), m.start(), m.len())),
+            groups
+                .get(3)
+                .map(|m| (unescape(m.as_str()), m.start(), m.len())),
             groups.get(4).map(|m| (m.as_str(), m.start(), m.len())),
             groups
                 .get(5)
@@ -115,13 +134,13 @@ fn parse_single_modification(
                 .transpose()?,
         );
 
-        let modification = if let (Some(head), Some(tail)) = (head.as_ref(), tail) {
+        let modification = if let (Some(head), Some(tail)) = (head.as_ref(), &tail) {
             let basic_error = CustomError::error(
                 "Invalid modification",
                 "..",
                 Context::line(None, line, offset + tail.1, tail.2),
             );
-            match (head.0.as_str(), tail.0) {
+            match (head.0.as_str(), tail.0.as_ref()) {
                 ("unimod", tail) => {
                     let id = tail.parse::<usize>().map_err(|_| {
                         basic_error
@@ -258,24 +277,24 @@ fn parse_single_modification(
                     )
                 }),
                 (_, _tail) => Ontology::Unimod
-                    .find_name(full.0, custom_database)
-                    .or_else(|| Ontology::Psimod.find_name(full.0, custom_database))
+                    .find_name(&full.0, custom_database)
+                    .or_else(|| Ontology::Psimod.find_name(&full.0, custom_database))
                     .map(Some)
                     .ok_or_else(||
-                        Ontology::find_closest_many(&[Ontology::Unimod, Ontology::Psimod, Ontology::Gnome, Ontology::Xlmod, Ontology::Custom], full.0, custom_database)
+                        Ontology::find_closest_many(&[Ontology::Unimod, Ontology::Psimod, Ontology::Gnome, Ontology::Xlmod, Ontology::Custom], &full.0, custom_database)
                     .with_long_description("This modification cannot be read as a valid Unimod or PSI-MOD name, or as a numerical modification. Or did you intent to use a ontology that is not yet supported?")
                     .with_context(Context::line(None, line, offset+full.1, full.2)))
             }
         } else if full.0.is_empty() {
             Ok(None)
         } else {
-            Ontology::Unimod.find_name(full.0 , custom_database)
-                .or_else(|| Ontology::Psimod.find_name(full.0, custom_database))
-                .ok_or_else(|| numerical_mod(full.0))
+            Ontology::Unimod.find_name(&full.0 , custom_database)
+                .or_else(|| Ontology::Psimod.find_name(&full.0, custom_database))
+                .ok_or_else(|| numerical_mod(&full.0))
                 .flat_err()
                 .map(Some)
                 .map_err(|_|
-                    Ontology::find_closest_many(&[Ontology::Unimod, Ontology::Psimod, Ontology::Gnome, Ontology::Xlmod, Ontology::Custom], full.0, custom_database)
+                    Ontology::find_closest_many(&[Ontology::Unimod, Ontology::Psimod, Ontology::Gnome, Ontology::Xlmod, Ontology::Custom], &full.0, custom_database)
                     .with_long_description("This modification cannot be read as a valid Unimod or PSI-MOD name, or as a numerical modification.")
                     .with_context(Context::line(None, line, offset+full.1, full.2))
                 )
@@ -436,10 +455,56 @@ impl ReturnModification {
 pub enum GlobalModification {
     /// A global isotope modification
     Isotope(Element, Option<NonZeroU16>),
+    /// A global isotope modification that is only applied to the given location (`<2H@K>`)
+    RestrictedIsotope(Element, Option<NonZeroU16>, IsotopeLocation),
     /// Can be placed on any place it fits, if that is the correct aminoacid and it fits according to the placement rules of the modification itself
     Fixed(Position, Option<AminoAcid>, SimpleModification),
 }
 
+/// The locations a restricted global isotope modification can be applied to
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize, Hash)]
+pub enum IsotopeLocation {
+    /// All atoms of the residue of this amino acid, any modifications are not labelled
+    AminoAcid(AminoAcid),
+    /// Only the backbone amide hydrogen (`backbone-amide`), present on every residue except the N
+    /// terminal residue and proline, as exchanged in hydrogen deuterium exchange experiments
+    BackboneAmide,
+}
+
+impl IsotopeLocation {
+    /// The number of atoms of the given element that are labelled by a restricted global isotope
+    /// modification on this location, for the given residue at the given index in the peptide.
+    pub(crate) fn atoms(self, element: Element, aminoacid: AminoAcid, index: usize) -> i32 {
+        match self {
+            Self::AminoAcid(aa) if aa == aminoacid => {
+                aminoacid.formulas().first().map_or(0, |formula| {
+                    formula
+                        .elements()
+                        .iter()
+                        .filter(|e| e.0 == element && e.1.is_none())
+                        .map(|e| e.2)
+                        .sum()
+                })
+            }
+            Self::BackboneAmide
+                if element == Element::H && index > 0 && aminoacid != AminoAcid::Proline =>
+            {
+                1
+            }
+            _ => 0,
+        }
+    }
+}
+
+impl std::fmt::Display for IsotopeLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AminoAcid(aa) => write!(f, "{aa}"),
+            Self::BackboneAmide => write!(f, "backbone-amide"),
+        }
+    }
+}
+
 /// # Errors
 /// It returns an error when the text is not numerical
 pub(super) fn numerical_mod(text: &str) -> Result<SimpleModification, String> {
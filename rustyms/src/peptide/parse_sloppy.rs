@@ -116,6 +116,29 @@ impl LinearPeptide<SemiAmbiguous> {
         peptide.enforce_modification_rules()?;
         Ok(peptide)
     }
+
+    /// Create a peptide from a sequence of one letter amino acid codes, as found in for example
+    /// FASTA files. No modifications or any other ProForma features are allowed.
+    ///
+    /// # Errors
+    /// If any of the bytes is not a valid amino acid code, the error points at the first invalid byte.
+    pub fn from_bytes(sequence: &[u8]) -> Result<Self, CustomError> {
+        sequence
+            .iter()
+            .enumerate()
+            .map(|(index, byte)| {
+                AminoAcid::try_from(byte)
+                    .map(|aa| SequenceElement::new(CheckedAminoAcid::new(aa), None))
+                    .map_err(|()| {
+                        CustomError::error(
+                            "Invalid amino acid",
+                            "This is not a valid amino acid code",
+                            Context::line(None, String::from_utf8_lossy(sequence), index, 1),
+                        )
+                    })
+            })
+            .collect()
+    }
 }
 
 static SLOPPY_MOD_OPAIR_REGEX: OnceLock<Regex> = OnceLock::new();
@@ -223,12 +223,13 @@ impl Peptidoform {
                 .peptides()
                 .first()
                 .map_or(empty.as_slice(), |p| p.get_global());
-            for (element, isotope) in global {
+            for (element, isotope, location) in global {
                 write!(
                     f,
-                    "<{}{}>",
+                    "<{}{}{}>",
                     isotope.map(|i| i.to_string()).unwrap_or_default(),
-                    element
+                    element,
+                    location.map(|l| format!("@{l}")).unwrap_or_default()
                 )?;
             }
         }
@@ -169,3 +169,19 @@ parse_test!(ne "-[#2][#22222222F2222222][#2222222222222;2222] SEK(?AA)A-[XLMOD:0
 parse_test!(ne "D[]-[X:XLMOD:0O/:|||||||||||||mou|||||||||:||||||||||||||||||||||||||||||||#bx]@O-N-term>ASO-N-term>ASQDVNQDVNbbbpeAAA", fuzz_129);
 parse_test!(ne r#"FK/3[+E21NC-1NC-21NC-1NC-2222222NC-22,2C-21NC-1NC-2222222NC-22,2222222222222222222NC-22,222222+C-22,222]ESK[#XL1]PEKSK[XLMOD:02009#XL1]SEK[XLMOD:02009#Xn1]UENCE//EMESEK(?AA)A-[XLMOD:02001#Xd1]K(?AVTk[XLMOD:02001#XL1]SESPEKQEK(?AA)A-[XLMOD:02001#Xi1]UENCE//EMEVTK[XfM[]^3?SEK[XLMOD:HFFFF2113#XL1]SENCE//E[XLMM[XLMOD:02113#MZ1]S0FF{Glycan:HFFFF2113#XL1]SENCE//E[XLMM[XLMOD:02113#MZ1]SMEVTEVTK[XLMOD:02113K[U:iTRAQ4plex]-[Glycan:HHHHHSSSSSSSSSSSSSSSSSSSSSSSSSSS9SSSSSFFFFFHgggggggHHnnnHnnnnnHHHGgHHHHnnHFFFFFFFFHHHHHHHHGgggHHHGgHHgggggggggggggggnHnnnnnHSg0SSSSSSSSSSSSSSSSSSS1SSSSSS0SSSSSppS0SnnnnnnnnnnonnnnpSSSSSSSSSSSSSSSSSSSSS1SSSSSppS0S0SSg0SSSSSSSSSSSSSSSSSSS1SSSSSS0SSSSSppS0SnnnnnnnnnnonnnnpSSSSSSSSSSSSSSHF8105#XI1]P[Formula:k13C2][000#XLMEV1][R:0"0T1#cLXLMEV1]MAFormula:1]-HHkSSSSSSSSSSSSSSSSSSlSSSSrrrrrrSEK(?AA)A-[XLMOD:02001#XL1]UENCSAI[XLMOD:02001#XL1]UENCSEK(?AA)A-[XLMOD:02001#Xe1]UENCE//EMEVTK[XLMOD:02001#XL1]UENCE//EMoSEK[XLMOD:02000#GJ1]UENCE//EZ[XLMOD:0]UENCE//EMEVTZ[XLMOD:00101#XL1]M[XLMOD:02000#GJ1]UENCE//EMEVTZ[XLMOD:00101#XL1]MENCE//EMEVTL[XLMOD:00101#XL1]UENCE//EMEVTZ[XLMOD:00101#XL1]MENCE//EMEVTL[XLMOD:00101#XL1]EENCE//EMEVTL[XLMOD:00101#XL1]M[XLMOD:02000#GJ1]UENCE//EMEVTZ[XLMOD:0]UENCE//EMEVTZ[XLMOD:00101#XL1]-[XLMOD:02000#GJ1]UENCE//EMEVTZ[XLMOD:00101#XL1]MENCE//EMEVTL[XLMOD:00101#1]EENCE//EMEVTL[XLMOD:00101#XL1]M[XLMOD:02000#GJ1]UENCE//EMEVTZ[XLMOD:0]UENCE//EMEVTZ[XLMOD:00101#XL1]M[XLMOD:02000#GJ1]UENCE//EMEVTZ[XLMOD:00101#XL1]MENCE//EMEVTL[XLMOD:00101#XL1]EENCE//EMEVTL[XLMOD:00101#XL1]M[XLMOD:02000#GJ1]UENCE//EMEVTZ[XLMOD:00101#XL1]MENCE//EME//EMEVTZ[XLMOD:00101#XL1]M[XLMOD:02000#GJ1]UENCE//EMEVTZ[XLMOD:00101#XL1]MENCE//EMEVTL[XLMOD:00101#XL1]UENCE//EMEVTZ[XLMOD:00101#XL1]MENCE//EMEVTL[XLMOD:00101#XL1]EENCE//EMEVTL[XLMOD:00101#XL1]M[XLMOD:02000#GJ1]UENCE//EMEVTZ[XLMOD:0]UENCE//EMEVTZ[XLMOD:00101#XL1]M[XLMOD:02000#GJ1]UENCE//EMEVTZ[XLMOD:00101#XL1]MENCE//EMEVTL[XLMOD:00101#1]EENCE//EMEVTL[XLMOD:00101#XL1]M[XLMOD:02000#GJ1]UENCE//EMEVTZ[XLMOD:0]UENCE//EMEVTZ[XLMOD:00101#XL1]M[XLMOD:02000#GJ1]UENCE//EMEVTZ[XLMOD:00101#XL1]MENCE//EMEVTL[XLMOD:00101#XL1]EENCE//EMEVTL[XLMOD:00101#XL1]M[XLMOD:02000#GJ1]UENCE//EMEVTZ[XLMOD:00101#XL1]MENCE//EM[XLMOD:00101#XL1]0VTSEK(?AY)A-[XLMOD:02001#XE1]UENCA-[XL[X::02001#XLK]UOD:02001#XE1]UENCA-[XL[X::02001XLK]UENCE//LNCA-MOD0OENCE//LNCA-MOD0OD[Glu][]^3?SEK[XLMOD:02201#XLllXLlll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLn1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLllllllll1]UENC[M:1#XLn1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1]UEE//EMK[XLMOD:02201#XLllXLlllllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLlllllXll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UEN[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1]UEE//EMK[XLMOD:02201#XLllXLlllllll1][M:1#XLlllllllll1]UENC[M:1#XLLlllllllll1]UEN-[M:1#XSLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1]UEE//EMK[XLMOD:02201#XLllXLlllllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLlllllXll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UEN[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllmlllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1llllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1]UEE//EMK[XLMOD:02201#XLllXLlllllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLlllllXll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UEN[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1]UEE//EMK[XLMOD:02201#XLllXLlllllll1][M:1#XLlllllllll1]UENC[M:1#XLLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1]UEE//EMK[XLMOD:02201#XLllXLlllllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xwllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLlllllXll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UEN[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UEE//EMK[XLMOD:02201#XLllXLlllllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1C[M::1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XMW1]UENC[MC[M:1#:1#X[M:1#XLLEV1V1]UEE//E[Glycan:HHHHHSUG7HH]^8?SEK(?AA)A-[XLMOD:02001#XU1]UENCSEK(?AA)A-[XLMOD:02001#XL1]UENCE//EMEVTK[XLMOD:02001#XLC]UENCE//EMEVTK[XLMMD:02001#XL1]SEE//EMEVTK[XLMOD:02001#XL1]UENCE//EMEVSEK(?AA)A-[XLMOD:02001#XL  O/:|||#X1]UENCE//EMEVTK[XLMOD:02001#XL1]SESPEK<d>mAIP+IEMEVE/3Q+-[MOD:02060#XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXUXXXXXXXXXXXXXXXXXXXXXXXXXXUXXXXXXXXXXXXXXLMEf1][M          -3HX     [FormulXXXXXXXXXLMEf1][M          -2HX     [Formula:u ^3?QNCE//[]TK                      2 ()[]SEK(?AA)A-[XLMOD:02001#XL1]UENCSA-[XLMOD:02001#XL1]UEK(?AAA)A-[XLMOD:02001#XL1]UEUENNCSA-[XLMOD:02001#XL1]UEK(?AA)A-[XLMOD:02001#XF1]UENCEA-[XLMOD:02001#XL1]UENCSA-[XLMOD:02001#XL1]UENCSEK(?AA)A-[XLMOD:02001#XL1]UENCE//enld[]^3?SEK[XLMOD:02113#XL1]SENCE//E[XLMOD:02113#XL1]SM[XLMOD:02113#MZ1]SMEVTEVTK[XLMOD:02113#XL1]SE[XLMOD:02113#XL1]SM[XLMOD:02113#XL1]SMEVTEVT-[XLMOD:02113#1]SENCE//E[XLMOD:02113#mL1]SM[XLMOD:02113#MZ1]SMEVTEVTK[XLMOD:02113#XL1]SE[XLMOD:02113#XL1]SM[XLMOD:02113#XL1]SMEVTEVTK[XLMOD:02113#XXL1]S[Formula:u13ClycanY]^8[Glu][]^3?SEK[XLMOD:02201#XLllXLlll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLn1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLllllllll1]UENC[M:1#XLn1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllzl1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1]UEE//EMK[XLMOD:02201#XLllXLlllllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UEUENC[M:1#XLW1]UENC[M:1#XLLLlllllXll]UENC[M:1#XLl1]UET/3NC[M:1#WLLLllllllll1]UEN[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1]UEE//NMK[XLMOD:02201#XLllXLlllllll1][M:1#XLlllllllll1]UENC[M:1#XLLlllllllll1]UEN-[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1]UEE//EMK[XLMOD:02201#XLllXLlllllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLlllllXll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UEN[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]NC[M:1#XLLLlllllXll]UENC[M:1#XLl1]UET/3NC[M:1#WLLLllllllll1]UEN[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1]UEE//EMK[XLMOD:02201#XLllXLlllllll1][M:1#XLlllllllll1]UENC[M:1#XLLlllllllll1]UEN-[M:1#KLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1]UEE//EMK[XLMOD:02201#XLllXLlllllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLlllllXll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UEN[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllmlllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1llllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1]UEE//EMK[XLMOD:02201#XLllXLlllllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLlllllXll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UEN[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1]UEE//EMK[XLMOD:02201#XLllXLlllllll1][M:1#XLlllllllll1]UENC[M:1#XLLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1]UEE//EMK[XLMOD:02201#XLllXLlllllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xwllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLlllllXll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UEN[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UEE//EMK[XLMOD:02201#XLllXLlllllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3lllll1]UENC[M:1#XMW1]UEN[Formula:u13ClycanY]^8[Glu][]^3?SEK[XLMOD:02201#XLllXLlll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLn1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLllllllll1]UENC[M:1#XLn1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllzl1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1]UEE//EMK[XLMOD:02201#XLllXLlllllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UEUENC[M:1#XLW1]UENC[M:1#XLLLlllllXll]UENC[M:1#XLl1]UET/3NC[M:1#WLLLllllllll1]UEN[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1]UEE//EMK[XLMOD:02201#XLllXLlllllll1][M:1#XLlllllllll1]UENC[M:1#XLLlllllllll1]UEN-[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1]UEE//EMK[XLMOD:02201#XLllXLlllllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLlllllXll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UEN[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]NC[M:1#XLLLlllllXll]UENC[M:1#XLl1]UET/3NC[M:1#WLLLllllllll1]UEN[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1]UEE//EMK[XLMOD:02201#XLllXLlllllll1][M:1#XLlllllllll1]UENC[M:1#XLLlllllllll1]UEN-[M:1#XuLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1]UEE//EMK[XLMOD:02201#Xl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1]UEE//EMK[XLMOD:02201#XLllXLlllllll1][M:1#XLlllllllll1]UENC[M:1#XLLlllllllll1]UEN-[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1]UEE//EMK[XLMOD:02201#XLllXLlllllll1][M:1#XLll3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLlllllXll]LLEV1V1llllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1]UEE//EMK[XLMOD:02201#XLllXLlllllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLlllllXll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UEN[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1]UEE//EMK[XLMOD:02201#XLllXLlllllll1][M:1#XLlllllllll1]UENC[M:1#XLLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1]UEE//EMK[XLMOD:02201#XLllXLlllllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xwllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLlllllXll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UEN[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UEE//EMK[XLMOD:02201#XLllXLlllllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3lllll1]UENC[M:1#XMW1]UEN[XLMOD:171|]?[]-GGfGGGGGGGGGGGGGGGGGGGGGGGGGGVTK[XLMOD:02001#XL1]SESmEKSEK(?AA)A-[XLMOD:02001#Xd1]UTK[XLMOD:0200<d>mAIP+IEMEVE/3Q+E[MOD:02060#XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXLMEf1][#XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXLMEf1][MOD:02060#8LXXXXXXXLMEfXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXLMEf1]/3Q+E[MOD:02060#XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXLMEf1][#XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXLMEf1][MOD:02060#8LXXXXXXXLMEfXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXLMEf1][#XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXLMEf1][MOD:02060#8LXXXXXXXLMEfXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXLMEf1][#XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXLMEf1][MOD:02060#8LXXXXXXXLMEfXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXLMEf1]/3Q+E[MOD:02060#XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXLMEf1][#XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXLMEf1][MOD:02060#8LXXXXXXXLMEfXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXLMEf1][#XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXLMEf1][MOD:02060#8LXXXXXXXLMEfXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXLMEf1]/3Q+E[MOD:02060#XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXLMEf1][#XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXLMEf1][MOD:02060#8LXXXXXXXLMEfXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXLMEf1][#XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXLMEf1][MOD:02060#8LXXXXXXXLMEfXXXXXXXXXXXXXXXXXXXXXXXXLMEf1][#XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXLMEf1][MOD:02060#8LXXXXXXXLMEf1][#XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXLMEf1][MOD:02060#8LMEf1][MOD:02060#WL0]AGGGG+GG+GSppSSSppN[Formula:[13C2][12C-2222E2222]H2#XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXLMEf1]/3Q+-[MOD:02060#XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXLMEf1][#XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXLMEf1][MOD:02060#8LXXXXXXXLMEfXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXLMXXXXXXXXXXXLMEf1][MOD:02060#8LXXXXXXXLMEf1][#XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXLMEf1][MOD:02060#8LMEf1WL0]AGGGG+GG+GSppSSSppN[Formula:[13C2][11][#XXXXXXXXXXXXXXXXXXXXD[]-[X:rou^3?Q[gl  hydrou^3?Q[gl򬰮]/3+n-SQDVNbbbpeZpax]@O-N-tem-Zbaj^<G.H.kfqt?DQP.Z200F#Phospho|INFO:newlym-Zbaj^<G.H.kfqt?DQP.Z200F#Phospho|INFO:newlyXLMEV1]UE,pfqw=DRMBMTUEXGSEK(?AA)A-[XLMOD:02001#Xo1]UENCE//EME[MOD:02121#0150#XLMEf1][MOD:02#XLMEf1][MOD:02060#XLMEf:0E[MOf1][MOD:020X1][MOD:054140#60$JJe1][MOD1][M:0260#QP'LLMEf1][MOD:02060#XLf1][MOD:02060#XL 	][MOD:0060#XLMEf1][M:0791#XL#HHKDa0][MOD:02060#XLf1][MOD:02060#XLMEf1][TEK[Oxidation]EESPSEK(?AA)A-[XLMOD:02001#dL1]UENCA-[FLMOD:02'01#XL1]ElEBTEVTZ[X:02]-[U:05#XI1]9MODLMOD:001A-[XLMOD:02001#XL1]UEK(?AA)A-[XLMOD:02001#XL1]UENCEA-[XLMOD:02001#MG1]UENCSA-[XLMOD:02001#XL1]UENCSEK(?AA)A-[XLM#〙’A-[XLMOD:02001#XL1]UEK(?AA)A-[XLmOD:02001#XL1]UENCEA-[XLMOD:02001#OL1]UENCrA-[XLMOD:02001#XL1]UENCSEK(?AA)A-[XLM#〣〙VTK[XLMOD:02001#XL1]SESmEKSEK(?AA)A-[XLMOD:02001#XL1]UTK[XLMOD:02001#XL1]SESmEKSEg(?AA)A-[XLMOD:02001#tL1]UE2113{Glycan:Hex}[iTRAQ4plex]-EM[Oxidation]EVNES[Phospho]PEK[iTRAQ4plex]-[#ethyl]EMEVEESPPhdsA-[XLMOD:02001#XL1]UEK(?AA)A-[XLMOD:02001#XL1]UENCEA-[XLMOD:02001#XL1]UENCSA-[XLMOD:02001#lL1]UENCSEK(?AA)A<[TMT6plex]@K,K,K,K,K,K,N>ySSSSSSSSSSSMSSSSSEK(?AA)A-[XLMOD:02001#DL1]UENCE//EMEVTK[XLMOD:020SEK(?AA)A-[XLMOD:02001#X]1]UEdCE//EMEV"#, fuzz_130);
 parse_test!(ne "K[U:iTRAQ4plex]-[Glycan:HHHHHSSSSSSSSSSSSSSSSSSSSSSSSSSS9SSSSSSPSSSSSSSSSSSSSSSSSSSSSSSSSSSSSSSSSSSSSSSSSSSSSSSSSSSSSSSSSSSnnnnnnnnnnnnnnnnnSSnnnnnnnnnnnnnnnnnpSSSSSSSSSSSSSSSSSSSSS1SSSSSppS0S0SSg0SSSSSSSSSSSSSSSSSSS1SSSSSS0SSSSSppS0SnnnnnnSSSSSSSSSSSS1SSSSSppS0S0SSg0SSSSSSSSSSSSSSSSSSS1SSSSSS0SSSSSppS0SnnnnnnnnSSSppS0SnnnnnnnnnnonnnnpSSS#22222222F2222222][#22222222F2222222][#22222222222222222222]Sgn0SSSSSSSSSSSSSSSSSSS1nnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnpSSSSSSSSSSSSSSSSSSSSS1SSSSSppS0S0SSg0SSSSSSSSSSSSSSSSSSS1SSSSSS0SSSSSppS0SnnnnnnnnnnonnnnpSSSSSSSSSSSSSSHFFFFFFFFFFFFFFFFFFFFFFFFFDECFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF81]-HHkSSSSSSSSSSSSSSSS0SnnnnnnSSSSSSSSSSSS1SSSSSppS0S0SSg0SSSSSSSSSSSSSSSSSSS1SSSSSS0SSSSSppS0SnnnnnnnnSSSppS0SnnnnnnnnnnonnnnpSSS#22222222F2222222][#22222222F2222222][#22222222222222222222]Sgn0SSSSSSSSSSSnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnnpSSSSSSSSSSSSSSSSSSSSS1SSSSSppS0S0SSg0SSSSSSSSSSSSSSSSSSS1SSSSSS0SSSSSppS0SnnnnnnnnnnonnnnpSSSSSSSSSSSSSSHFFFFFFFFFFFFFFFFFFFFFFFFSSlSS{Glycan:Hex}[iTRAQ4plex]-EM[Oxidation]VTC[XLMOD:02001#XL1]SESmEmEKSEK(?AA)A-[XLMOD:02001#XL1]UTK[XLMOD:02001#XL1]SESmE(?AA)A-[XLMOD:02001#X51]KSEK(?AA)A-[XLMOD:02001#XL1]UTK[XLMOD:02001#XLHHXWa(cooWZXe)[]ecWa(ooW)[XLMOD:110][][XLMOD:110]SESSEK(?AA)A-[XLMOD:02101#XL1]UENCSK(?AA)A-[XLMOD:02101#XL1]UA-[XLMOD:02001#Xd1]UENCSEK(?AA)A-[XLMOD:02[]^3?SEK[XLMOD:02201#XL1]UENC[M:1#XLLEV1V1]UEE//EMEVT-[X:1#XKLEV1V1]UEE/[Formula:u13ClycanY]^8[Glu][]^3?SEK[XLMOD:02201#XLllXLlll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLn1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLllllllll1]UENC[M:1#XLn1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllzl1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1]UEE//EMK[XLMOD:02201#XLllXLlllllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UEUENC[M:1#XLW1]UENC[M:1#XLLLlllllXll]UENC[M:1#XLl1]UET/3NC[M:1#WLLLllllllll1]UEN[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1]UEE//EMK[XLMOD:02201#XLllXLlllllll1][M:1#XLlllllllll1]UENC[M:1#XLLlllllllll1]UEN-[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1]UEE//EMK[XLMOD:02201#XLllXLllllllN1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLlllllXll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UEN[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]NC[M:1#XLLLlllllXll]UENC[M:1#XLl1]UET/3NC[M:1#WLLLllllllll1]UEN[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1]UEE//EMK[XLMOD:02201#XLllXLlllllll1][M:1#XLlllllllll1]UENC[M:1#XLLlllllllll1]UEN-[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1]UEE//EMK[XLMOD:02201#Xl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1]UEE//EMK[XLMOD:02201#XLllXLlllllll1][M:1#XLlllllllll1]UENC[M:1#XLLlllllllll1]UEN-[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLlllllllllll1]UENC[M:1#XLLlllllllll1]UEN-[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1]UEE//EMK[XLMOD:02201#Xl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1]UEE//EMK[XLMOD:02201#XLllXLlllllll1][M:1#XLlllllllll1]UENC[M:1#XLLlllllllll1]UEN-[M:1#XKLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#XLLEV1V1]UEE//EMK[XLMOD:02201#XLllXLlllllll1][M:1#XLll3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:1#XLW1]UENC[M:1#XLLLlllllXll]LLEV1V1llllll1]UENC[M:1#XLLllllllll1]UENC[M:1NC[M:1#XLLLllllllll]UENC[M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:M:1#XLl1]UET/3NC[M:1#XLLLllllllll1]UENC[M:1#XLl11]UENC[M:1#XLl1]UENC[M:1#Xlllll1][M:1#XLlllllllll1]UENC[M:1#XLLllllllll1]UENC[M:A-[XLMOD:02001#XK1]UEK[]-[U:Mp[Formula:u1[Formd", fuzz_131);
+parse_test!(no_panic "<[", fuzz_global_unclosed);
+parse_test!(no_panic "<[Oxidation]", fuzz_global_no_location);
+parse_test!(no_panic "<[Oxidation]^", fuzz_global_caret_end);
+parse_test!(no_panic "<[Oxidation]@M", fuzz_global_unclosed_location);
+parse_test!(no_panic "<13C", fuzz_global_isotope_unclosed);
+parse_test!(no_panic "[Phospho]^", fuzz_unknown_caret_end);
+parse_test!(no_panic "[Phospho]^2", fuzz_unknown_count_end);
+parse_test!(no_panic "[Phospho]^2?", fuzz_unknown_no_sequence);
+parse_test!(no_panic "[Phospho|INFO:", fuzz_unknown_unclosed_info);
+parse_test!(no_panic "AA(AA", fuzz_ranged_unclosed);
+parse_test!(no_panic "AA(AA)[", fuzz_ranged_unclosed_modification);
+parse_test!(no_panic "AA(?AA", fuzz_ambiguous_unclosed);
+parse_test!(no_panic "AA{", fuzz_labile_unclosed);
+parse_test!(no_panic "ELVIS[Phospho|INFO:", fuzz_info_unclosed);
+parse_test!(no_panic "EMEVEESPEK/3[+2Na+,+H+", fuzz_charge_unclosed);
+parse_test!(no_panic "SEK[XLMOD:02001#XL1]UENCE//", fuzz_cross_link_empty_peptide);
@@ -26,6 +26,28 @@ macro_rules! parse_test {
             assert_eq!(res, res_back, "{} != {back}", $case);
         }
     };
+    (case_sensitive $case:literal, $name:ident) => {
+        #[test]
+        fn $name() {
+            let res = $crate::CompoundPeptidoform::pro_forma($case, None);
+            println!("{}", $case);
+            dbg!(&res);
+            assert!(res.is_ok());
+            let back = res.as_ref().unwrap().to_string();
+            let res_back = $crate::CompoundPeptidoform::pro_forma(&back, None);
+            assert_eq!(res, res_back, "{} != {back}", $case);
+        }
+    };
+    (no_panic $case:literal, $name:ident) => {
+        #[test]
+        fn $name() {
+            let res = $crate::CompoundPeptidoform::pro_forma($case, None);
+            println!("{}\n{:?}", $case, res);
+            if let Ok(peptide) = res {
+                let _ = peptide.to_string();
+            }
+        }
+    };
     (ne $case:literal, $name:ident) => {
         #[test]
         fn $name() {
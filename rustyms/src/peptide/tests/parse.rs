@@ -1,15 +1,20 @@
 use std::num::NonZeroU16;
 
 use crate::{
-    model::PrimaryIonSeries,
-    modification::{self, ModificationId, SimpleModification},
+    fragment::FragmentKind,
+    model::{Location, PrimaryIonSeries},
+    modification::{
+        self, Modification, ModificationId, Ontology, PlacedModification, SimpleModification,
+    },
     peptide::{
         parse::{global_modifications, parse_charge_state},
-        GlobalModification,
+        GlobalModification, IsotopeLocation,
     },
     placement_rule::{self, PlacementRule},
     system::{da, usize::Charge},
-    AminoAcid, CompoundPeptidoform, Element, LinearPeptide, Model, MolecularCharge, MultiChemical,
+    AminoAcid, CheckedAminoAcid, CompoundPeptidoform, Element, Fragment, LinearPeptide, Model,
+    MolecularCharge, MultiChemical, Peptidoform, Protease, SequenceElement, SequencePosition,
+    Tolerance,
 };
 
 #[test]
@@ -98,6 +103,172 @@ fn parse_global_modifications() {
     assert!(parse("<@D,E,R,T>").is_err());
     assert!(parse("<[+5]@D,E,R,Te>").is_err());
     assert!(parse("<[+5]@D,E,R,N-term:OO>").is_err());
+    assert_eq!(
+        parse("<D@K,backbone-amide>"),
+        Ok((
+            20,
+            vec![
+                GlobalModification::RestrictedIsotope(
+                    Element::H,
+                    NonZeroU16::new(2),
+                    IsotopeLocation::AminoAcid(AminoAcid::Lysine)
+                ),
+                GlobalModification::RestrictedIsotope(
+                    Element::H,
+                    NonZeroU16::new(2),
+                    IsotopeLocation::BackboneAmide
+                )
+            ]
+        ))
+    );
+    assert_eq!(
+        parse("<13C@R><[+5]@D>"),
+        Ok((
+            15,
+            vec![
+                GlobalModification::RestrictedIsotope(
+                    Element::C,
+                    NonZeroU16::new(13),
+                    IsotopeLocation::AminoAcid(AminoAcid::Arginine)
+                ),
+                GlobalModification::Fixed(
+                    crate::placement_rule::Position::Anywhere,
+                    Some(AminoAcid::AsparticAcid),
+                    SimpleModification::Mass(da(5.0).into())
+                )
+            ]
+        ))
+    );
+    assert_eq!(
+        parse("<[+5]^2@D,E>"),
+        Ok((
+            12,
+            [AminoAcid::AsparticAcid, AminoAcid::GlutamicAcid]
+                .repeat(2)
+                .into_iter()
+                .map(|aa| GlobalModification::Fixed(
+                    crate::placement_rule::Position::Anywhere,
+                    Some(aa),
+                    SimpleModification::Mass(da(5.0).into())
+                ))
+                .collect()
+        ))
+    );
+    assert!(parse("<[+5]^0@D>").is_err());
+    assert!(parse("<[+5]^@D>").is_err());
+    assert!(parse("<[+5]^2x@D>").is_err());
+    assert!(parse("<[+5]2@D>").is_err());
+    assert!(parse("<13C@backbone-amide>").is_err());
+    assert!(parse("<D@backbone>").is_err());
+    assert!(parse("<D@>").is_err());
+    assert!(parse("<C12@K>").is_err());
+}
+
+#[test]
+fn overlapping_global_fixed_modifications() {
+    let modifications = |peptide: &str, index: usize| {
+        LinearPeptide::pro_forma(peptide, None).unwrap().sequence()[index]
+            .modifications
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+    };
+    // Both are applied in the order of definition
+    assert_eq!(
+        modifications("<[Carbamidomethyl]@C><[+1]@C>ACK", 1),
+        vec!["U:Carbamidomethyl", "+1"]
+    );
+    assert_eq!(
+        modifications("<[+1]@C><[Carbamidomethyl]@C>ACK", 1),
+        vec!["+1", "U:Carbamidomethyl"]
+    );
+    // Modifications in the sequence come first
+    assert_eq!(
+        modifications("<[Carbamidomethyl]@C><[+1]@C>AC[+5]K", 1),
+        vec!["+5", "U:Carbamidomethyl", "+1"]
+    );
+    // Repeated copies
+    assert_eq!(modifications("<[+1]^3@C>ACK", 1), vec!["+1", "+1", "+1"]);
+    assert!(modifications("<[+1]^3@C>ACK", 0).is_empty());
+    let repeated = LinearPeptide::pro_forma("<[+1]^2@C>ACK", None).unwrap();
+    assert_eq!(
+        repeated,
+        LinearPeptide::pro_forma("<[+1]@C><[+1]@C>ACK", None).unwrap()
+    );
+}
+
+#[test]
+fn restricted_global_isotopes() {
+    let mass = |peptide: &str| {
+        LinearPeptide::pro_forma(peptide, None)
+            .unwrap()
+            .into_linear()
+            .unwrap()
+            .formulas()
+            .to_vec()[0]
+            .monoisotopic_mass()
+            .value
+    };
+    let shift = |element: Element, isotope: u16| {
+        element.mass(NonZeroU16::new(isotope)).unwrap().value - element.mass(None).unwrap().value
+    };
+    // Only the amide hydrogens are labelled: not on the N terminal residue and not on proline
+    assert!(
+        (mass("<D@backbone-amide>PEPTIDE") - mass("PEPTIDE") - 5.0 * shift(Element::H, 2)).abs()
+            < 1e-6
+    );
+    assert!(
+        (mass("<D@backbone-amide>AAAA") - mass("AAAA") - 3.0 * shift(Element::H, 2)).abs() < 1e-6
+    );
+    // All carbons of lysine residues but not of the modifications on them
+    assert!(
+        (mass("<13C@K>AKAK[Acetyl]") - mass("AKAK[Acetyl]") - 12.0 * shift(Element::C, 13)).abs()
+            < 1e-6
+    );
+    assert!((mass("<15N@K,R>AKR") - mass("AKR") - 6.0 * shift(Element::N, 15)).abs() < 1e-6);
+    // The isotopes are placed on the residues so they are taken into account for fragments
+    let peptide = LinearPeptide::pro_forma("<D@backbone-amide>AAAA", None)
+        .unwrap()
+        .into_linear()
+        .unwrap();
+    assert!(peptide
+        .sequence()
+        .iter()
+        .all(|seq| seq.modifications.is_empty()));
+    let fragments = peptide.generate_theoretical_fragments(
+        Charge::new::<crate::system::e>(1),
+        &Model::none().b(PrimaryIonSeries::default()),
+    );
+    let unlabelled = LinearPeptide::pro_forma("AAAA", None)
+        .unwrap()
+        .into_linear()
+        .unwrap()
+        .generate_theoretical_fragments(
+            Charge::new::<crate::system::e>(1),
+            &Model::none().b(PrimaryIonSeries::default()),
+        );
+    let b2 = |fragments: &[Fragment]| {
+        fragments
+            .iter()
+            .find(|f| f.ion.to_string() == "b2")
+            .unwrap()
+            .formula
+            .monoisotopic_mass()
+            .value
+    };
+    assert!((b2(&fragments) - b2(&unlabelled) - shift(Element::H, 2)).abs() < 1e-6);
+    // The labelled peptide can be written and read back
+    let peptide = LinearPeptide::pro_forma("<D@backbone-amide><13C@K>PEPTIDEK", None).unwrap();
+    assert_eq!(peptide.to_string(), "<2H@backbone-amide><13C@K>PEPTIDEK");
+    assert_eq!(
+        LinearPeptide::pro_forma(&peptide.to_string(), None).unwrap(),
+        peptide
+    );
+    assert_eq!(
+        peptide.stripped(true).to_string(),
+        "<2H@backbone-amide><13C@K>PEPTIDEK"
+    );
+    assert_eq!(peptide.stripped(false).to_string(), "PEPTIDEK");
 }
 
 #[test]
@@ -220,6 +391,40 @@ fn charge_state_negative() {
     assert!(parse("/ 1 [ 1 H 1]").is_err());
 }
 
+#[test]
+fn charge_carriers_from_adducts() {
+    let charge = MolecularCharge::from_pro_forma_adducts("[+2Na+,-H+]").unwrap();
+    assert_eq!(charge.total_charge(), 1);
+    assert_eq!(
+        charge,
+        MolecularCharge::new(&[
+            (2, molecular_formula!(Na 1 Electron -1)),
+            (-1, molecular_formula!(H 1 Electron -1)),
+        ])
+    );
+    assert_eq!(
+        MolecularCharge::from_pro_forma_adducts(&charge.to_string()),
+        Ok(charge)
+    );
+    for adducts in ["2", "-3", "2[+2Na+]", "1[+2Na+,-H+]", "3[+Fe+3]", "[+e-]"] {
+        let charge = MolecularCharge::from_pro_forma_adducts(adducts).unwrap();
+        assert_eq!(
+            MolecularCharge::from_pro_forma_adducts(&charge.to_string()),
+            Ok(charge),
+            "{adducts}"
+        );
+    }
+    assert_eq!(
+        MolecularCharge::from_pro_forma_adducts("3"),
+        Ok(MolecularCharge::proton(3))
+    );
+    assert!(MolecularCharge::from_pro_forma_adducts("2[+2Na+,-H+]").is_err());
+    assert!(MolecularCharge::from_pro_forma_adducts("[+2Na+").is_err());
+    assert!(MolecularCharge::from_pro_forma_adducts("[+2Na+]A").is_err());
+    assert!(MolecularCharge::from_pro_forma_adducts("[+2Na]").is_err());
+    assert!(MolecularCharge::from_pro_forma_adducts("").is_err());
+}
+
 #[test]
 fn parse_glycan() {
     let glycan = LinearPeptide::pro_forma("A[Glycan:Hex]", None).unwrap();
@@ -365,6 +570,38 @@ fn parse_chimeric() {
         .is_some());
 }
 
+#[test]
+fn parse_chimeric_with_adducts() {
+    let chimeric = CompoundPeptidoform::pro_forma("EMEVEESPEK/1[+2Na+,-H+]+PEPTIDE", None).unwrap();
+    assert_eq!(chimeric.peptidoforms().len(), 2);
+    assert_eq!(
+        chimeric.peptidoforms()[0].peptides()[0]
+            .get_charge_carriers()
+            .map(|c| c.charge().value),
+        Some(1)
+    );
+    assert_eq!(chimeric.peptidoforms()[1].peptides()[0].len(), 7);
+    let next = CompoundPeptidoform::pro_forma("PEPTIDE/1[+2Na+,-H+]+NEXTPEP", None).unwrap();
+    assert_eq!(next.peptidoforms().len(), 2);
+    let first = &next.peptidoforms()[0].peptides()[0];
+    assert_eq!(first.sequence().len(), 7);
+    assert_eq!(
+        first.get_charge_carriers().map(|c| c.charge().value),
+        Some(1)
+    );
+    let second = &next.peptidoforms()[1].peptides()[0];
+    assert_eq!(second.to_string(), "NEXTPEP");
+    assert!(second.get_charge_carriers().is_none());
+    let single = CompoundPeptidoform::pro_forma("EMEVEESPEK/3[+2Na+,+H+]", None).unwrap();
+    assert_eq!(single.peptidoforms().len(), 1);
+    let terminal = CompoundPeptidoform::pro_forma("PEK-[Amidated]/2[+2H+]+AAA", None).unwrap();
+    assert_eq!(terminal.peptidoforms().len(), 2);
+    assert_eq!(terminal.peptidoforms()[1].peptides()[0].len(), 3);
+    assert!(CompoundPeptidoform::pro_forma("PEK+", None).is_err());
+    assert!(CompoundPeptidoform::pro_forma("PEK/2[+2H+]+", None).is_err());
+    assert!(CompoundPeptidoform::pro_forma("PEK-[Amidated]+", None).is_err());
+}
+
 #[test]
 fn parse_unimod() {
     let peptide = dbg!(CompoundPeptidoform::pro_forma(
@@ -427,6 +664,42 @@ fn parse_xl_intra() {
     );
 }
 
+#[test]
+fn reverse_xl_intra() {
+    let peptide = CompoundPeptidoform::pro_forma("AK[XLMOD:02001#XLTEST]GK[#XLTEST]S", None)
+        .unwrap()
+        .singular_peptide()
+        .unwrap();
+    let reversed = peptide.reverse();
+    assert_eq!(reversed.reverse(), peptide);
+    assert!(matches!(
+        reversed.sequence()[1].modifications[..],
+        [Modification::CrossLink { .. }]
+    ));
+    assert!(matches!(
+        reversed.sequence()[3].modifications[..],
+        [Modification::CrossLink { .. }]
+    ));
+    assert_eq!(
+        Peptidoform::from(reversed.clone()).formulas(),
+        Peptidoform::from(peptide).formulas()
+    );
+    let reparsed = CompoundPeptidoform::pro_forma(&reversed.to_string(), None)
+        .unwrap()
+        .singular_peptide()
+        .unwrap();
+    assert_eq!(reparsed, reversed);
+
+    let ambiguous = LinearPeptide::pro_forma("AS[Phospho#u1]GS[#u1]K", None)
+        .unwrap()
+        .into_linear()
+        .unwrap();
+    let reversed = ambiguous.reverse();
+    assert_eq!(reversed.reverse(), ambiguous);
+    assert_eq!(reversed.formulas(), ambiguous.formulas());
+    assert_eq!(reversed.to_string(), "KS[#u1]GS[U:Phospho#u1]A");
+}
+
 #[test]
 fn parse_xl_inter() {
     let peptide =
@@ -447,10 +720,55 @@ fn parse_xl_inter() {
     );
 }
 
+#[test]
+fn protein_terminal_global_modification() {
+    let protein = LinearPeptide::pro_forma("MAAKPEPTIDEKGGR", None)
+        .unwrap()
+        .into_linear()
+        .unwrap();
+    assert!(protein.is_protein_n_term());
+    assert!(protein.is_protein_c_term());
+    let acetyl = Ontology::Unimod.find_name("acetyl", None).unwrap();
+    let peptides = protein.digest(
+        &Protease::c_terminal_of(&[AminoAcid::Lysine, AminoAcid::Arginine]),
+        0,
+    );
+    assert_eq!(peptides.len(), 3);
+    assert_eq!(
+        peptides
+            .iter()
+            .map(|p| (p.is_protein_n_term(), p.is_protein_c_term()))
+            .collect::<Vec<_>>(),
+        vec![(true, false), (false, false), (false, true)]
+    );
+    for (index, mut peptide) in peptides.into_iter().enumerate() {
+        assert!(
+            peptide.apply_global_modifications(&[GlobalModification::Fixed(
+                placement_rule::Position::ProteinNTerm,
+                None,
+                acetyl.clone(),
+            )])
+        );
+        assert_eq!(
+            peptide.get_n_term().is_some(),
+            index == 0,
+            "Protein N terminal acetylation on peptide {index}: {peptide}"
+        );
+        assert!(
+            peptide.apply_global_modifications(&[GlobalModification::Fixed(
+                placement_rule::Position::AnyNTerm,
+                None,
+                acetyl.clone(),
+            )])
+        );
+        assert!(peptide.get_n_term().is_some());
+    }
+}
+
 #[test]
 fn dimeric_peptide() {
     // Only generate a single series, easier to reason about
-    let test_model = Model::none().a(PrimaryIonSeries::default());
+    let test_model = Model::single(FragmentKind::a, Location::All);
 
     // With two different sequences
     let dimeric = CompoundPeptidoform::pro_forma("AA+CC", None).unwrap();
@@ -495,3 +813,529 @@ fn hydrolysed_xl() {
 
     assert_eq!(peptide_xl.formula(), peptide_mod.formula());
 }
+
+#[test]
+fn list_modifications() {
+    let peptide = LinearPeptide::pro_forma("[+1]-A[+2]S[+3][+4]T[+6#g1]S[#g1]-[+5]", None)
+        .unwrap()
+        .into_linear()
+        .unwrap();
+    let mass = |m: f64| Modification::Simple(SimpleModification::Mass(da(m).into()));
+    let modifications = peptide.modifications();
+    assert_eq!(
+        modifications
+            .iter()
+            .map(|(p, m)| (*p, m.is_ambiguous()))
+            .collect::<Vec<_>>(),
+        vec![
+            (SequencePosition::NTerm, false),
+            (SequencePosition::Index(0), false),
+            (SequencePosition::Index(1), false),
+            (SequencePosition::Index(1), false),
+            (SequencePosition::Index(2), true),
+            (SequencePosition::Index(3), true),
+            (SequencePosition::CTerm, false),
+        ]
+    );
+    assert_eq!(modifications[0].1, PlacedModification::Defined(&mass(1.0)));
+    assert_eq!(modifications[3].1, PlacedModification::Defined(&mass(4.0)));
+    assert_eq!(modifications[6].1, PlacedModification::Defined(&mass(5.0)));
+    let PlacedModification::Ambiguous(ambiguous) = modifications[5].1 else {
+        panic!("Expected an ambiguous modification")
+    };
+    assert_eq!(
+        ambiguous.modification,
+        SimpleModification::Mass(da(6.0).into())
+    );
+}
+
+#[test]
+fn edit_sequence() {
+    let mut peptide = LinearPeptide::pro_forma("A[U:Phospho#g1]S[#g1]T[#g1]", None)
+        .unwrap()
+        .into_linear()
+        .unwrap();
+    assert_eq!(peptide.get_ambiguous_modifications(), &[vec![0, 1, 2]]);
+    peptide.insert(
+        1,
+        SequenceElement::new(CheckedAminoAcid::new(AminoAcid::Glycine), None).cast(),
+    );
+    assert_eq!(peptide.get_ambiguous_modifications(), &[vec![0, 2, 3]]);
+    assert_eq!(peptide.to_string(), "A[U:Phospho#g1]GS[#g1]T[#g1]");
+    let removed = peptide.remove(2);
+    assert_eq!(removed.aminoacid.aminoacid(), AminoAcid::Serine);
+    assert_eq!(peptide.get_ambiguous_modifications(), &[vec![0, 2]]);
+    assert_eq!(peptide.to_string(), "A[U:Phospho#g1]GT[#g1]");
+    peptide.insert(0, removed);
+    assert_eq!(peptide.get_ambiguous_modifications(), &[vec![0, 1, 3]]);
+    assert_eq!(peptide.to_string(), "S[#g1]A[U:Phospho#g1]GT[#g1]");
+    let original = peptide.substitute(2, CheckedAminoAcid::new(AminoAcid::Tyrosine));
+    assert_eq!(original.aminoacid(), AminoAcid::Glycine);
+    assert_eq!(peptide.get_ambiguous_modifications(), &[vec![0, 1, 3]]);
+    assert_eq!(peptide.to_string(), "S[#g1]A[U:Phospho#g1]YT[#g1]");
+}
+
+#[test]
+fn ambiguous_modification_scores() {
+    let peptide = LinearPeptide::pro_forma(
+        "EM[Oxidation]EVT[#g1(0.01)]S[#g1(0.09)]ES[Phospho#g1(0.90)]PEK",
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        peptide.ambiguous_modification_scores(),
+        vec![(0, vec![(4, Some(0.01)), (5, Some(0.09)), (7, Some(0.90))])]
+    );
+    let peptide = LinearPeptide::pro_forma("EMEVT[#g1]S[#g1]ES[Phospho#g1]PEK", None).unwrap();
+    assert_eq!(
+        peptide.ambiguous_modification_scores(),
+        vec![(0, vec![(4, None), (5, None), (7, None)])]
+    );
+    let peptide = LinearPeptide::pro_forma("EMEVTSESPEK", None).unwrap();
+    assert!(peptide.ambiguous_modification_scores().is_empty());
+}
+
+#[test]
+fn nested_brackets() {
+    let peptide = LinearPeptide::pro_forma("EME[Formula:[13C2]H2][Cation:Mg[II]]K", None)
+        .unwrap()
+        .into_linear()
+        .unwrap();
+    assert_eq!(peptide.len(), 4);
+    assert_eq!(peptide.sequence()[2].modifications.len(), 2);
+    assert_eq!(
+        peptide.sequence()[2].modifications[0],
+        Modification::Simple(SimpleModification::Formula(
+            molecular_formula!([13 C 2] H 2)
+        ))
+    );
+    assert_eq!(
+        peptide.sequence()[2].modifications[1],
+        Modification::Simple(Ontology::Unimod.find_name("Cation:Mg[II]", None).unwrap())
+    );
+}
+
+#[test]
+fn matches_precursor() {
+    let peptide = LinearPeptide::pro_forma("EMEVEESPEK", None)
+        .unwrap()
+        .into_linear()
+        .unwrap();
+    let charge = Charge::new::<crate::system::e>(2);
+    let tolerance = Tolerance::new_ppm(10.0);
+    let mass =
+        (peptide.formulas()[0].clone() + molecular_formula!(H 2 Electron -2)).monoisotopic_mass();
+    assert!(peptide.matches_precursor(mass, charge, tolerance, 0..=0));
+    assert!(!peptide.matches_precursor(mass, Charge::new::<crate::system::e>(1), tolerance, 0..=0));
+    let isotope = mass + da(Fragment::ISOTOPE_SPACING);
+    assert!(!peptide.matches_precursor(isotope, charge, tolerance, 0..=0));
+    assert!(peptide.matches_precursor(isotope, charge, tolerance, 0..=1));
+    assert!(peptide.matches_precursor(isotope, charge, tolerance, -1..=2));
+    assert!(!peptide.matches_precursor(isotope, charge, tolerance, -2..=-1));
+}
+
+#[test]
+fn gno_composition_mass() {
+    let mass = |peptide: &str| {
+        LinearPeptide::pro_forma(peptide, None)
+            .unwrap()
+            .into_linear()
+            .unwrap()
+            .formulas()[0]
+            .monoisotopic_mass()
+            .value
+    };
+    let composition = mass("NEEYN[Glycan:HexNAc4Hex5NeuAc1]K");
+    // Only the composition is known for G59626AS
+    assert!((mass("NEEYN[GNO:G59626AS]K") - composition).abs() < 1e-6);
+    assert!((composition - 2_709.016_921).abs() < 1e-5);
+    // Only the (rounded) mass of the free glycan is known for G15175QG
+    assert!((mass("NEEYN[GNO:G15175QG]K") - composition).abs() < 0.01);
+}
+
+#[test]
+fn sequence_key() {
+    let key = |peptide: &str| {
+        LinearPeptide::pro_forma(peptide, None)
+            .unwrap()
+            .sequence_key()
+    };
+    let reference = key("<13C><15N>AM[Oxidation]K[Acetyl][Methyl]");
+    assert_eq!(
+        reference,
+        key("<15N><13C>AM[UNIMOD:35]K[U:Methyl][U:Acetyl]")
+    );
+    assert_eq!(
+        reference,
+        key("<13C><15N>{Hex}AM[Oxidation]K[Methyl][UNIMOD:1]/2[+2H+]")
+    );
+    assert_ne!(reference, key("<13C><15N>AM[Oxidation]K[Acetyl]"));
+    assert_ne!(reference, key("<13C><15N>AM[Oxidation]R[Acetyl][Methyl]"));
+}
+
+#[test]
+fn unknown_position_copies() {
+    let peptide =
+        CompoundPeptidoform::pro_forma("[Phospho]^2?[Acetyl]-EM[Oxidation]EVTSESPEK", None)
+            .unwrap();
+    assert_eq!(
+        peptide.to_string(),
+        "[U:Phospho]^2?[U:Acetyl]-EM[U:Oxidation]EVTSESPEK"
+    );
+    let linear = peptide.singular_peptide().unwrap();
+    assert_eq!(linear.get_ambiguous_modifications().len(), 2);
+    let mixed = CompoundPeptidoform::pro_forma("[Phospho][Oxidation]?EMEVTSESPEK", None).unwrap();
+    assert_eq!(mixed.to_string(), "[U:Phospho][U:Oxidation]?EMEVTSESPEK");
+}
+
+#[test]
+fn verbose_display() {
+    let peptide = |text: &str| {
+        CompoundPeptidoform::pro_forma(text, None)
+            .unwrap()
+            .singular_peptide()
+            .unwrap()
+    };
+    let scored = peptide("EM[Oxidation]EVT[#g1(0.01)]S[#g1(0.09)]ES[Phospho#g1(0.90)]PEK");
+    assert_eq!(
+        scored.to_string_verbose(),
+        "EM[U:Oxidation]EVT[#g1(0.01)]S[#g1(0.09)]ES[U:Phospho#g1(0.9)]PEK"
+    );
+    let unknown = peptide("[Phospho]?EMEVTSESPEK");
+    assert_eq!(unknown.to_string(), "[U:Phospho]?EMEVTSESPEK");
+    let verbose = unknown.to_string_verbose();
+    assert_eq!(
+        verbose,
+        "[U:Phospho#u0]?E[#u0]ME[#u0]VT[#u0]S[#u0]E[#u0]S[#u0]PE[#u0]K[#u0]"
+    );
+    assert_eq!(peptide(&verbose), unknown);
+}
+
+#[test]
+fn all_errors() {
+    let errors = CompoundPeptidoform::pro_forma_all_errors(
+        "[Wrong1]-AM[Wrong2]K(SS)[Wrong3]R[Oxidation]",
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(errors.len(), 3);
+    assert_eq!(
+        CompoundPeptidoform::pro_forma("[Wrong1]-AM[Wrong2]K(SS)[Wrong3]R[Oxidation]", None)
+            .unwrap_err(),
+        errors[0]
+    );
+    // A syntax error outside of a modification stops parsing
+    let errors = CompoundPeptidoform::pro_forma_all_errors("AM[Wrong]K-", None).unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[1].short_description(), "Invalid peptide");
+    // Valid peptides are unaffected
+    assert!(CompoundPeptidoform::pro_forma_all_errors("AM[Oxidation]K", None).is_ok());
+}
+
+#[test]
+fn find_subsequence() {
+    let peptide = |p: &str| LinearPeptide::pro_forma(p, None).unwrap();
+    let protein = peptide("AAAAM[Oxidation]KAAMKNDK");
+    assert_eq!(
+        protein.find_subsequence(&peptide("AA"), false, false),
+        vec![0, 1, 2, 6]
+    );
+    assert_eq!(
+        protein.find_subsequence(&peptide("MK"), false, false),
+        vec![4, 8]
+    );
+    assert_eq!(
+        protein.find_subsequence(&peptide("M[Oxidation]K"), true, false),
+        vec![4]
+    );
+    assert_eq!(
+        protein.find_subsequence(&peptide("MK"), true, false),
+        vec![8]
+    );
+    assert!(protein
+        .find_subsequence(&peptide("BK"), false, false)
+        .is_empty());
+    assert_eq!(
+        protein.find_subsequence(&peptide("BK"), false, true),
+        vec![11]
+    );
+    assert_eq!(
+        protein.find_subsequence(&peptide("XB"), false, true),
+        vec![9, 10]
+    );
+    assert!(protein
+        .find_subsequence(&peptide("AAAAMKAAMKNDKA"), false, false)
+        .is_empty());
+}
+
+#[test]
+fn ion_mz() {
+    let peptide = LinearPeptide::pro_forma("PEPTIDE", None)
+        .unwrap()
+        .into_linear()
+        .unwrap();
+    let check = |series, ordinal, charge, expected: f64| {
+        let mz = peptide
+            .ion_mz(series, ordinal, Charge::new::<crate::system::e>(charge))
+            .unwrap();
+        assert_eq!(mz.len(), 1);
+        assert!(
+            (mz[0].value - expected).abs() < 1e-6,
+            "{series}{ordinal} {charge}+: {} != {expected}",
+            mz[0].value
+        );
+    };
+    // y1 = E + H2O + H+, y3 = IDE + H2O + H+, b2 = PE + H+
+    check(FragmentKind::y, 1, 1, 148.060_434);
+    check(FragmentKind::y, 3, 1, 376.171_441);
+    check(FragmentKind::y, 3, 2, 188.589_359);
+    check(FragmentKind::b, 2, 1, 227.102_633);
+    // The full length y7 is the protonated precursor
+    check(FragmentKind::y, 7, 1, 800.367_241);
+    assert!(peptide
+        .ion_mz(FragmentKind::y, 0, Charge::new::<crate::system::e>(1))
+        .is_none());
+    assert!(peptide
+        .ion_mz(FragmentKind::y, 8, Charge::new::<crate::system::e>(1))
+        .is_none());
+    assert!(peptide
+        .ion_mz(
+            FragmentKind::precursor,
+            1,
+            Charge::new::<crate::system::e>(1)
+        )
+        .is_none());
+    assert!(peptide
+        .ion_mz(FragmentKind::y, 1, Charge::new::<crate::system::e>(0))
+        .is_none());
+    let ambiguous = LinearPeptide::pro_forma("PEPTIDB", None)
+        .unwrap()
+        .into_linear()
+        .unwrap();
+    assert_eq!(
+        ambiguous
+            .ion_mz(FragmentKind::y, 1, Charge::new::<crate::system::e>(1))
+            .unwrap()
+            .len(),
+        2
+    );
+}
+
+#[test]
+fn truncations() {
+    let peptide = LinearPeptide::pro_forma("[Acetyl]-PEPTIDE-[Amidated]", None)
+        .unwrap()
+        .into_linear()
+        .unwrap();
+    let truncations = peptide.truncations(3);
+    assert_eq!(truncations.len(), 8);
+    assert_eq!(
+        truncations
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        vec![
+            "EPTIDE-[U:Amidated]",
+            "PTIDE-[U:Amidated]",
+            "TIDE-[U:Amidated]",
+            "IDE-[U:Amidated]",
+            "[U:Acetyl]-PEPTID",
+            "[U:Acetyl]-PEPTI",
+            "[U:Acetyl]-PEPT",
+            "[U:Acetyl]-PEP",
+        ]
+    );
+    assert!(truncations[..4].iter().all(|p| p.get_n_term().is_none()));
+    assert!(truncations[4..].iter().all(|p| p.get_c_term().is_none()));
+    assert_eq!(peptide.truncations(0).len(), 12);
+    assert!(peptide.truncations(7).is_empty());
+    assert!(peptide.truncations(10).is_empty());
+}
+
+#[test]
+fn glycosylation_sites() {
+    let peptide = |p: &str| LinearPeptide::pro_forma(p, None).unwrap();
+    assert_eq!(peptide("NGTWEM").n_glycosylation_sites(), vec![0]);
+    assert_eq!(peptide("NGTWEM").o_glycosylation_sites(), vec![2]);
+    assert_eq!(
+        peptide("NGTWEMNPSNKSNST").n_glycosylation_sites(),
+        vec![0, 9, 12]
+    );
+    assert_eq!(
+        peptide("NGTWEMNPSNKSNST").o_glycosylation_sites(),
+        vec![2, 8, 11, 13, 14]
+    );
+    // B could be N so it counts, modifications are ignored
+    assert_eq!(
+        peptide("BGSWEN[HexNAc]XT").n_glycosylation_sites(),
+        vec![0, 5]
+    );
+    // Sequons that run off the end are not reported
+    assert!(peptide("WEMNG").n_glycosylation_sites().is_empty());
+    assert!(peptide("NPT").n_glycosylation_sites().is_empty());
+    assert!(peptide("NGC").n_glycosylation_sites().is_empty());
+    assert!(peptide("WEMNG").o_glycosylation_sites().is_empty());
+}
+
+#[test]
+fn formula_element_casing() {
+    let formula = |text: &str| {
+        CompoundPeptidoform::pro_forma(text, None)
+            .unwrap()
+            .singular_peptide()
+            .unwrap()
+            .sequence()[0]
+            .modifications
+            .first()
+            .cloned()
+    };
+    assert_eq!(
+        formula("A[Formula:CO]"),
+        Some(Modification::Simple(SimpleModification::Formula(
+            molecular_formula!(C 1 O 1)
+        )))
+    );
+    assert_eq!(
+        formula("A[Formula:Co]"),
+        Some(Modification::Simple(SimpleModification::Formula(
+            molecular_formula!(Co 1)
+        )))
+    );
+    assert!(CompoundPeptidoform::pro_forma("A[Formula:co]", None).is_err());
+}
+
+#[test]
+fn stripped() {
+    let peptide = |text: &str| {
+        CompoundPeptidoform::pro_forma(text, None)
+            .unwrap()
+            .singular_peptide()
+            .unwrap()
+    };
+    let full = peptide("<15N>{Glycan:Hex}[Acetyl]-PEM[Oxidation]T[Phospho#g1]S[#g1]-[Amidated]/2");
+    assert_eq!(full.stripped(false), peptide("PEMTS/2"));
+    assert_eq!(full.stripped(true), peptide("<15N>PEMTS/2"));
+    assert_eq!(
+        full.stripped(false).sequence_key(),
+        peptide("PEMTS").sequence_key()
+    );
+}
+
+#[test]
+fn info_tags() {
+    let peptide = |text: &str| {
+        CompoundPeptidoform::pro_forma(text, None)
+            .unwrap()
+            .singular_peptide()
+            .unwrap()
+    };
+    let tagged = peptide("[Acetyl|INFO:n]-S[Phospho|INFO:foo|INFO:bar]K-[INFO:c|Amidated]");
+    let phospho = tagged.sequence()[0].modifications[0].simple().unwrap();
+    assert_eq!(tagged.sequence()[0].info_tags(phospho), ["foo", "bar"]);
+    assert_eq!(tagged.get_n_term_info_tags(), ["n"]);
+    assert_eq!(tagged.get_c_term_info_tags(), ["c"]);
+    let shown = tagged.to_string();
+    assert!(shown.contains("|INFO:foo|INFO:bar]"), "{shown}");
+    let back = peptide(&shown);
+    assert_eq!(back.sequence()[0].info_tags(phospho), ["foo", "bar"]);
+    assert_eq!(back.get_n_term_info_tags(), ["n"]);
+    assert_eq!(back.get_c_term_info_tags(), ["c"]);
+    // The tags are not part of the identity of the peptide
+    assert_eq!(tagged, peptide("[Acetyl]-S[Phospho]K-[Amidated]"));
+    assert!(peptide("S[Phospho]K").sequence()[0]
+        .info_tags(phospho)
+        .is_empty());
+
+    let ambiguous = peptide("S[Phospho#g1|INFO:site]T[#g1]K");
+    assert_eq!(
+        ambiguous.sequence()[0].possible_modifications[0].info_tags,
+        ["site"]
+    );
+    let shown = ambiguous.to_string();
+    assert!(shown.contains("#g1|INFO:site]"), "{shown}");
+    assert_eq!(
+        peptide(&shown).sequence()[1].possible_modifications[0].info_tags,
+        ["site"]
+    );
+}
+
+#[test]
+fn from_bytes() {
+    let peptide = LinearPeptide::from_bytes(b"PEPTIDEb").unwrap();
+    assert_eq!(
+        peptide,
+        LinearPeptide::pro_forma("PEPTIDEB", None)
+            .unwrap()
+            .into_semi_ambiguous()
+            .unwrap()
+    );
+    assert!(LinearPeptide::from_bytes(b"").unwrap().is_empty());
+    let error = LinearPeptide::from_bytes(b"PEP1IDE").unwrap_err();
+    assert_eq!(error.short_description(), "Invalid amino acid");
+    assert_eq!(
+        error.context(),
+        &crate::error::Context::line(None, "PEP1IDE", 3, 1)
+    );
+}
+
+#[test]
+fn cross_links() {
+    let dss = molecular_formula!(C 8 H 10 O 2);
+    let peptide = CompoundPeptidoform::pro_forma(
+        "SEK[XLMOD:02001#XL1]UENCE//EMEVTK[XLMOD:02001#XL1]SESPEK",
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        peptide.cross_links(),
+        [(
+            (0, SequencePosition::Index(2)),
+            (1, SequencePosition::Index(5)),
+            dss.clone()
+        )]
+    );
+    let peptide =
+        CompoundPeptidoform::pro_forma("A[X:DSS#XL1]//B[#XL1]+C[X:DSS#XL1]//D[#XL1]", None)
+            .unwrap();
+    assert_eq!(
+        peptide.cross_links(),
+        [
+            (
+                (0, SequencePosition::Index(0)),
+                (1, SequencePosition::Index(0)),
+                dss.clone()
+            ),
+            (
+                (2, SequencePosition::Index(0)),
+                (3, SequencePosition::Index(0)),
+                dss
+            )
+        ]
+    );
+    let peptide = CompoundPeptidoform::pro_forma(
+        "EMK[XLMOD:02000#XL1]EVTKSE[XLMOD:02010#XL2]SK[#XL1]PEK[#XL2]AR",
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        peptide
+            .cross_links()
+            .into_iter()
+            .map(|(a, b, _)| (a, b))
+            .collect::<Vec<_>>(),
+        [
+            (
+                (0, SequencePosition::Index(2)),
+                (0, SequencePosition::Index(10))
+            ),
+            (
+                (0, SequencePosition::Index(8)),
+                (0, SequencePosition::Index(13))
+            )
+        ]
+    );
+    assert!(CompoundPeptidoform::pro_forma("PEPTIDE", None)
+        .unwrap()
+        .cross_links()
+        .is_empty());
+}
@@ -65,9 +65,9 @@ parse_test!(
     "PROT(EOC[Carbamidomethyl]FORMS)[+19.0523]ISK",
     positive_example_29
 );
-parse_test!("SEQUEN[Formula:C12H20O2]CE", positive_example_30);
-parse_test!("SEQUEN[Formula:HN-1O2]CE", positive_example_31);
-parse_test!("SEQUEN[Formula:[13C2][12C-2]H2N]CE", positive_example_32);
+parse_test!(case_sensitive "SEQUEN[Formula:C12H20O2]CE", positive_example_30);
+parse_test!(case_sensitive "SEQUEN[Formula:HN-1O2]CE", positive_example_31);
+parse_test!(case_sensitive "SEQUEN[Formula:[13C2][12C-2]H2N]CE", positive_example_32);
 parse_test!("SEQUEN[Glycan:HexNAc]CE", positive_example_33);
 parse_test!("EMEVTK[XLMOD:02001#XL1]SESPEK[#XL1]", positive_example_34);
 parse_test!("EMEVTK[XLMOD:02001#XL1]SESPEK", positive_example_35);
@@ -214,9 +214,9 @@ parse_test!("EM[U:+15.995]EVEES[U:+79.966]PEK", positive_example_92);
 parse_test!("EM[U:+15.995]EVEES[Obs:+79.978]PEK", positive_example_93);
 parse_test!("EM[U:+15.995]EVEES[Obs:+79.978]PEK", positive_example_94);
 parse_test!("RTAAX[+367.0537]WT", positive_example_95);
-parse_test!("SEQUEN[Formula:C12H20O2]CE", positive_example_96);
-parse_test!("SEQUEN[Formula:[13C2]CH6N]CE", positive_example_97);
-parse_test!("SEQUEN[Formula:[13C2][12C-2]H2N]CE", positive_example_98);
+parse_test!(case_sensitive "SEQUEN[Formula:C12H20O2]CE", positive_example_96);
+parse_test!(case_sensitive "SEQUEN[Formula:[13C2]CH6N]CE", positive_example_97);
+parse_test!(case_sensitive "SEQUEN[Formula:[13C2][12C-2]H2N]CE", positive_example_98);
 parse_test!("SEQUEN[Glycan:HexNAc1Hex2]CE", positive_example_99);
 parse_test!(
     "[iTRAQ4plex]-EM[Oxidation]EVNES[Phospho]PEK",
@@ -330,11 +330,34 @@ parse_test!(
     "[U:iTRAQ4plex]-EM[U:Oxidation]EVNES[U:Phospho]PEK[U:iTRAQ4plex]-[U:Methyl]/3",
     positive_example_142
 );
-parse_test!("EMEVEESPEK/3[+2Na+,+H+]", positive_example_143);
-parse_test!("EMEVEESPEK/1[+2Na+,-H+]", positive_example_144);
-parse_test!("EMEVEESPEK/-2[2I-]", positive_example_145);
+parse_test!(case_sensitive "EMEVEESPEK/3[+2Na+,+H+]", positive_example_143);
+parse_test!(case_sensitive "EMEVEESPEK/1[+2Na+,-H+]", positive_example_144);
+parse_test!(case_sensitive "EMEVEESPEK/-2[2I-]", positive_example_145);
 parse_test!("EMEVEESPEK/-1[+e-]", positive_example_146);
 parse_test!("EMEVEESPEK/2+ELVISLIVER/3", positive_example_147);
 parse_test!("AA(?AA)", positive_example_148);
 parse_test!("AA(?AA)AA", positive_example_149);
 parse_test!("[dehydro]^3?[gln->pyro-glu]-QSC", positive_example_150);
+parse_test!(
+    r"ELV[INFO:with \[escaped\] brackets]IS",
+    positive_example_151
+);
+parse_test!(
+    r"ELVIS[Phospho|INFO:pipe \| and hash \# and backslash \\]K",
+    positive_example_152
+);
+parse_test!(
+    case_sensitive r"ELVIS[Formula:C2H2O|INFO:Custom:a\]b\[c]K",
+    positive_example_153
+);
+parse_test!(case_sensitive "[Formula:[13C2]H2]-EMEVEK", positive_example_154);
+parse_test!(case_sensitive "EMEVEK-[Formula:[13C2]H2]", positive_example_155);
+parse_test!(
+    case_sensitive "EME[Formula:[13C2]H2][Cation:Mg[II]]VEK",
+    positive_example_156
+);
+parse_test!(case_sensitive "<[Formula:[13C2]H2]@E>EMEVEK", positive_example_157);
+parse_test!(case_sensitive "[Formula:[13C2]H2]?EMEVEK", positive_example_158);
+parse_test!(case_sensitive "{Formula:[13C2]H2}EMEVEK", positive_example_159);
+parse_test!(case_sensitive "EM(EV)[Formula:[13C2]H2]EK", positive_example_160);
+parse_test!(case_sensitive "EMEV[Formula:[13C2]H2#g1]E[#g1]K", positive_example_161);
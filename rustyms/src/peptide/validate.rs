@@ -3,7 +3,7 @@ use std::collections::BTreeMap;
 
 use crate::{
     error::{Context, CustomError},
-    modification::{AmbiguousModification, CrossLinkName, SimpleModification},
+    modification::{AmbiguousModification, CrossLinkName, RulePossible, SimpleModification},
     LinearPeptide, Modification, Peptidoform, SequencePosition,
 };
 
@@ -124,6 +124,15 @@ pub fn cross_links(
 
 impl LinearPeptide<Linear> {
     /// Apply a global modification if this is a global isotope modification with invalid isotopes it returns false
+    ///
+    /// Fixed modifications are applied in the order they are defined, after any modifications
+    /// that were placed on the residue in the sequence itself. When multiple fixed modifications
+    /// (or multiple copies of the same modification `<[mod]^2@C>`) target the same residue, all are
+    /// applied. A terminus can only hold a single modification, so there the last defined global
+    /// modification is kept.
+    ///
+    /// Fixed modifications on the protein N or C terminus are only placed if the terminus of this
+    /// peptide is also the terminus of the protein, see [`Self::is_protein_n_term`].
     #[must_use]
     pub(super) fn apply_global_modifications(
         &mut self,
@@ -135,7 +144,7 @@ impl LinearPeptide<Linear> {
                     let positions = self
                         .iter(..)
                         .filter(|(position, seq)| {
-                            pos.is_possible(position.sequence_index)
+                            pos.is_possible_in(position.sequence_index, self)
                                 && aa.map_or(true, |aa| aa == seq.aminoacid.aminoacid())
                                 && modification
                                     .is_possible(seq, position.sequence_index)
@@ -150,6 +159,11 @@ impl LinearPeptide<Linear> {
                 GlobalModification::Isotope(el, isotope) if el.is_valid(*isotope) => {
                     let _ = self.add_global((*el, *isotope)); // Already validated
                 }
+                GlobalModification::RestrictedIsotope(el, isotope, location) => {
+                    if !self.add_restricted_global((*el, *isotope), *location) {
+                        return false;
+                    }
+                }
                 GlobalModification::Isotope(..) => return false,
             }
         }
@@ -184,6 +198,7 @@ impl LinearPeptide<Linear> {
                     localisation_score: None,
                     group: format!("u{unknown_mod_index}"),
                     preferred: false,
+                    info_tags: Vec::new(),
                 },
                 &positions,
                 None,
@@ -225,6 +240,7 @@ impl LinearPeptide<Linear> {
                     localisation_score: None,
                     group: format!("u{start_ambiguous_group_id}"),
                     preferred: false,
+                    info_tags: Vec::new(),
                 },
                 &positions,
                 None,
@@ -245,4 +261,89 @@ impl<T> LinearPeptide<T> {
         }
         Ok(())
     }
+
+    /// Validate the placement of all modifications on this peptide, including the terminal
+    /// modifications. Parsing a peptide always validates it, but building a peptide with the
+    /// builder methods (like [`Self::new`], [`Self::n_term`], and [`Self::c_term`]) does not, so
+    /// use this to validate a programmatically built peptide.
+    /// # Errors
+    /// If any modification is placed on a location not allowed by its placement rules.
+    pub fn validate(&self) -> Result<(), CustomError> {
+        self.enforce_modification_rules()?;
+        for (position, modification) in [
+            (SequencePosition::NTerm, self.get_n_term()),
+            (SequencePosition::CTerm, self.get_c_term()),
+        ] {
+            if let Some(modification) = modification {
+                if !self.is_empty()
+                    && modification.is_possible(&self[position], position) == RulePossible::No
+                {
+                    let rules = modification
+                        .simple()
+                        .map(SimpleModification::placement_rules)
+                        .unwrap_or_default();
+                    return Err(CustomError::error(
+                        "Modification incorrectly placed",
+                        format!(
+                            "Modification {modification} is not allowed on the {}{}",
+                            if position == SequencePosition::NTerm {
+                                "N-terminus"
+                            } else {
+                                "C-terminus"
+                            },
+                            if rules.is_empty() {
+                                String::new()
+                            } else {
+                                format!(", this modification is only allowed at the following locations: {}", rules.join(", "))
+                            }
+                        ),
+                        Context::none(),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::missing_panics_doc)]
+mod tests {
+    use crate::{
+        modification::Ontology, peptide::UnAmbiguous, CheckedAminoAcid, LinearPeptide,
+        SequenceElement,
+    };
+
+    #[test]
+    fn validate() {
+        let sequence = || {
+            [
+                SequenceElement::new(CheckedAminoAcid::<UnAmbiguous>::G, None),
+                SequenceElement::new(CheckedAminoAcid::S, None),
+                SequenceElement::new(CheckedAminoAcid::G, None),
+            ]
+        };
+        let phospho = Ontology::Unimod.find_id(21, None).unwrap();
+        let acetyl = Ontology::Unimod.find_id(1, None).unwrap();
+        let amidated = Ontology::Unimod.find_id(2, None).unwrap();
+
+        assert!(LinearPeptide::<UnAmbiguous>::new(sequence())
+            .validate()
+            .is_ok());
+
+        let mut peptide = LinearPeptide::<UnAmbiguous>::new(sequence());
+        peptide.add_simple_modification(crate::SequencePosition::Index(1), phospho.clone());
+        assert!(peptide.validate().is_ok());
+        peptide.add_simple_modification(crate::SequencePosition::Index(0), phospho);
+        assert!(peptide.validate().is_err());
+
+        let peptide = LinearPeptide::<UnAmbiguous>::new(sequence())
+            .n_term(Some(acetyl.clone().into()))
+            .c_term(Some(amidated.clone().into()));
+        assert!(peptide.validate().is_ok());
+        let peptide = LinearPeptide::<UnAmbiguous>::new(sequence()).c_term(Some(acetyl.into()));
+        assert!(peptide.validate().is_err());
+        let peptide = LinearPeptide::<UnAmbiguous>::new(sequence()).n_term(Some(amidated.into()));
+        assert!(peptide.validate().is_err());
+    }
 }
@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 use crate::{
     error::{Context, CustomError},
     modification::{Modification, ModificationId, Ontology, SimpleModification},
-    AminoAcid, SequenceElement, SequencePosition,
+    AminoAcid, LinearPeptide, SequenceElement, SequencePosition,
 };
 
 include!("shared/placement_rule.rs");
@@ -106,6 +106,18 @@ impl Position {
             Self::AnyCTerm | Self::ProteinCTerm => position == SequencePosition::CTerm,
         }
     }
+
+    /// See if the given position in the given peptide is a valid position given this [`Position`]
+    /// as placement rule. In contrast to [`Self::is_possible`] this only allows the protein
+    /// terminal positions if the terminus of the peptide is also the terminus of the protein (see
+    /// [`LinearPeptide::is_protein_n_term`]).
+    pub fn is_possible_in<T>(self, position: SequencePosition, peptide: &LinearPeptide<T>) -> bool {
+        match self {
+            Self::ProteinNTerm => peptide.is_protein_n_term() && self.is_possible(position),
+            Self::ProteinCTerm => peptide.is_protein_c_term() && self.is_possible(position),
+            _ => self.is_possible(position),
+        }
+    }
 }
 
 impl FromStr for Position {
@@ -126,7 +138,9 @@ impl FromStr for Position {
 #[allow(clippy::missing_panics_doc)]
 mod tests {
 
-    use crate::{checked_aminoacid::CheckedAminoAcid, modification::RulePossible};
+    use crate::{
+        checked_aminoacid::CheckedAminoAcid, fragment::PeptidePosition, modification::RulePossible,
+    };
 
     use super::*;
     #[test]
@@ -180,4 +194,21 @@ mod tests {
             "unimod deamidated at end"
         );
     }
+
+    #[test]
+    fn allowed_positions() {
+        let phospho = Modification::Simple(Ontology::Unimod.find_id(21, None).unwrap());
+        let rules = phospho.allowed_positions();
+        assert!(rules.iter().any(|rule| matches!(
+            rule,
+            PlacementRule::AminoAcid(aas, Position::Anywhere) if aas.contains(&AminoAcid::Serine)
+        )));
+        let middle = PeptidePosition::n(SequencePosition::Index(3), 10);
+        assert!(phospho.can_place_on(AminoAcid::Serine, &middle));
+        assert!(phospho.can_place_on(AminoAcid::Tyrosine, &middle));
+        assert!(!phospho.can_place_on(AminoAcid::Glycine, &middle));
+        let mass = Modification::Simple(SimpleModification::Mass(crate::system::da(10.0).into()));
+        assert!(mass.allowed_positions().is_empty());
+        assert!(mass.can_place_on(AminoAcid::Glycine, &middle));
+    }
 }
@@ -1,6 +1,18 @@
+#[cfg(feature = "rayon")]
+use std::{collections::HashSet, ops::RangeBounds};
+
 use itertools::Itertools;
 
-use crate::{AminoAcid, SequenceElement};
+use crate::{
+    error::{Context, CustomError},
+    system::Mass,
+    AminoAcid, SequenceElement, Tolerance, WithinTolerance,
+};
+#[cfg(feature = "rayon")]
+use crate::{
+    peptide::{AtMax, Linear},
+    LinearPeptide,
+};
 
 /// A protease defined by it ability to cut at any site identified by the right amino acids at the n and c terminal.
 /// Each position is identified by an option, a none means that there is no specificity at this position. If there is
@@ -38,9 +50,124 @@ impl Protease {
         }
     }
 
+    /// Define a protease that cuts between the amino acids `before` and `after`. A cut is only made
+    /// if the amino acid before the cut is not in `not_before` and the amino acid after the cut is
+    /// not in `not_after`. An empty list of allowed amino acids means that any amino acid is
+    /// allowed at that side, so cutting after D but not before P is `from_sites(vec![D], Vec::new(),
+    /// Vec::new(), vec![P])`.
+    pub fn from_sites(
+        before: Vec<AminoAcid>,
+        after: Vec<AminoAcid>,
+        not_before: Vec<AminoAcid>,
+        not_after: Vec<AminoAcid>,
+    ) -> Self {
+        let site = |allowed: Vec<AminoAcid>, excluded: Vec<AminoAcid>| {
+            if allowed.is_empty() && excluded.is_empty() {
+                Vec::new()
+            } else if allowed.is_empty() {
+                vec![Some(Self::all_except(&excluded))]
+            } else {
+                vec![Some(
+                    allowed
+                        .into_iter()
+                        .filter(|aa| !excluded.contains(aa))
+                        .collect(),
+                )]
+            }
+        };
+        Self {
+            n_term: site(before, not_before),
+            c_term: site(after, not_after),
+        }
+    }
+
+    /// Parse an Expasy (PROSITE) style cleavage rule, for example `[KR]|{P}` for trypsin. The cut
+    /// site is indicated with `|` and every position around it is either a single amino acid
+    /// (`D`), one of a set of amino acids (`[KR]`), any amino acid except a set of amino acids
+    /// (`{P}`), or any amino acid (`X`). Positions can optionally be separated by `-`.
+    ///
+    /// # Errors
+    /// If the rule does not contain exactly one cut site, if any amino acid is not recognised, or
+    /// if a set is not closed.
+    pub fn from_rule_str(rule: &str) -> Result<Self, CustomError> {
+        let error = |long: &str, offset: usize, length: usize| {
+            CustomError::error(
+                "Invalid cleavage rule",
+                long,
+                Context::line(None, rule, offset, length),
+            )
+        };
+        let amino_acid = |offset: usize| {
+            AminoAcid::try_from(rule.as_bytes()[offset])
+                .map_err(|()| error("This is not a valid amino acid", offset, 1))
+        };
+        let mut n_term = Vec::new();
+        let mut c_term = None;
+        let mut index = 0;
+        while index < rule.len() {
+            let position = match rule.as_bytes()[index] {
+                b'-' | b' ' => {
+                    index += 1;
+                    continue;
+                }
+                b'|' if c_term.is_none() => {
+                    c_term = Some(Vec::new());
+                    index += 1;
+                    continue;
+                }
+                b'|' => return Err(error("A rule can only contain one cut site", index, 1)),
+                open @ (b'[' | b'{') => {
+                    let close = if open == b'[' { b']' } else { b'}' };
+                    let end = rule[index..]
+                        .bytes()
+                        .position(|c| c == close)
+                        .map(|end| index + end)
+                        .ok_or_else(|| error("This set is not closed", index, 1))?;
+                    let set = (index + 1..end)
+                        .map(amino_acid)
+                        .collect::<Result<Vec<_>, _>>()?;
+                    index = end + 1;
+                    Some(if open == b'[' {
+                        set
+                    } else {
+                        Self::all_except(&set)
+                    })
+                }
+                b'X' | b'x' => {
+                    index += 1;
+                    None
+                }
+                _ => {
+                    let aa = amino_acid(index)?;
+                    index += 1;
+                    Some(vec![aa])
+                }
+            };
+            c_term.as_mut().unwrap_or(&mut n_term).push(position);
+        }
+        let c_term = c_term.ok_or_else(|| {
+            error(
+                "A rule should contain a cut site indicated with '|'",
+                0,
+                rule.len(),
+            )
+        })?;
+        Ok(Self { n_term, c_term })
+    }
+
+    /// All amino acids (the canonical amino acids, selenocysteine, and pyrrolysine) except the given ones
+    fn all_except(excluded: &[AminoAcid]) -> Vec<AminoAcid> {
+        AminoAcid::CANONICAL_AMINO_ACIDS
+            .iter()
+            .chain(&[AminoAcid::Selenocysteine, AminoAcid::Pyrrolysine])
+            .filter(|aa| !excluded.contains(aa))
+            .copied()
+            .collect()
+    }
+
     /// All locations in the given sequence where this protease could cut
     pub fn match_locations<T>(&self, sequence: &[SequenceElement<T>]) -> Vec<usize> {
-        (self.n_term.len()..sequence.len() - self.c_term.len())
+        (self.n_term.len()..sequence.len().saturating_sub(self.c_term.len()))
             .filter(|i| self.matches_at(&sequence[i - self.n_term.len()..i + self.c_term.len()]))
             .collect_vec()
     }
@@ -63,3 +190,268 @@ impl Protease {
         true
     }
 }
+
+/// Only available if the feature `rayon` is turned on.
+/// Digest all given proteins in parallel.
+///
+/// See [`LinearPeptide::digest_filtered`] for the meaning of the parameters. The resulting peptides are deduplicated based on their
+/// [`LinearPeptide::sequence_key`], only the first occurrence of each peptide is kept. The order of
+/// the output is the same as digesting the proteins one by one in the given order.
+#[cfg(feature = "rayon")]
+pub fn digest_proteome<Complexity: AtMax<Linear> + Send + Sync>(
+    proteins: &[LinearPeptide<Complexity>],
+    protease: &Protease,
+    max_missed_cleavages: usize,
+    length: impl RangeBounds<usize> + Clone + Sync,
+    mass: impl RangeBounds<Mass> + Clone + Sync,
+) -> Vec<LinearPeptide<Complexity>> {
+    use rayon::prelude::*;
+
+    let digested = proteins
+        .par_iter()
+        .map(|protein| {
+            protein
+                .digest_filtered(protease, max_missed_cleavages, length.clone(), mass.clone())
+                .into_iter()
+                .map(|peptide| (peptide.sequence_key(), peptide))
+                .collect_vec()
+        })
+        .collect::<Vec<_>>();
+    let mut seen = HashSet::new();
+    digested
+        .into_iter()
+        .flatten()
+        .filter_map(|(key, peptide)| seen.insert(key).then_some(peptide))
+        .collect()
+}
+
+/// Peptide mass fingerprint matching, count the number of observed masses that match.
+///
+/// An observed mass matches if it is within the given tolerance of any of the theoretical masses,
+/// every observed mass is counted at most once. See
+/// [`LinearPeptide::peptide_mass_fingerprint`](crate::LinearPeptide::peptide_mass_fingerprint) to
+/// generate the theoretical masses for a protein.
+pub fn pmf_match(observed: &[Mass], theoretical: &[Mass], tolerance: Tolerance<Mass>) -> usize {
+    observed
+        .iter()
+        .filter(|mass| theoretical.iter().any(|t| tolerance.within(t, *mass)))
+        .count()
+}
+
+#[cfg(test)]
+#[allow(clippy::missing_panics_doc)]
+mod tests {
+    use super::*;
+    use crate::{peptide::SimpleLinear, system::da, CompoundPeptidoform, LinearPeptide};
+
+    #[test]
+    fn digest_filtered() {
+        let peptide = LinearPeptide::pro_forma("AKAAAAAAKAAAAAAAAAAAAR", None)
+            .unwrap()
+            .into_simple_linear()
+            .unwrap();
+        let trypsin = Protease::new(&[AminoAcid::Lysine], &[]);
+        let all: Vec<LinearPeptide<SimpleLinear>> = peptide.digest(&trypsin, 1);
+        assert_eq!(all.len(), 5);
+        assert_eq!(
+            peptide
+                .digest_filtered(&trypsin, 1, 6..=40, ..)
+                .iter()
+                .map(LinearPeptide::len)
+                .collect_vec(),
+            vec![9, 7, 20, 13]
+        );
+        assert_eq!(
+            peptide
+                .digest_filtered(&trypsin, 1, 6..10, ..)
+                .iter()
+                .map(LinearPeptide::len)
+                .collect_vec(),
+            vec![9, 7]
+        );
+        assert_eq!(
+            peptide
+                .digest_filtered(&trypsin, 1, .., da(500.0)..da(1000.0))
+                .iter()
+                .map(LinearPeptide::len)
+                .collect_vec(),
+            vec![9, 7]
+        );
+        assert!(peptide
+            .digest_filtered(&trypsin, 1, .., da(5000.0)..)
+            .is_empty());
+    }
+
+    #[test]
+    fn peptide_mass_fingerprint() {
+        let protein = LinearPeptide::pro_forma("AKAAAAAAKAAAAAAAAAAAAR", None)
+            .unwrap()
+            .into_simple_linear()
+            .unwrap();
+        let trypsin = Protease::c_terminal_of(&[AminoAcid::Lysine, AminoAcid::Arginine]);
+        let theoretical = protein.peptide_mass_fingerprint(&trypsin, 0);
+        assert_eq!(theoretical.len(), 3);
+        assert!(theoretical.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(protein.peptide_mass_fingerprint(&trypsin, 1).len(), 5);
+
+        let observed = [
+            theoretical[0] * (1.0 + 2e-6),
+            theoretical[2] * (1.0 - 2e-6),
+            da(1234.5),
+        ];
+        assert_eq!(
+            pmf_match(&observed, &theoretical, Tolerance::new_ppm(10.0)),
+            2
+        );
+        assert_eq!(
+            pmf_match(&observed, &theoretical, Tolerance::new_ppm(1.0)),
+            0
+        );
+        assert_eq!(pmf_match(&[], &theoretical, Tolerance::new_ppm(10.0)), 0);
+        assert_eq!(pmf_match(&observed, &[], Tolerance::new_ppm(10.0)), 0);
+    }
+
+    #[test]
+    fn digest_missed_cleavages() {
+        let after_kr = Protease {
+            n_term: vec![Some(vec![AminoAcid::Lysine, AminoAcid::Arginine])],
+            c_term: Vec::new(),
+        };
+        let peptide = LinearPeptide::pro_forma("AKRAAK", None)
+            .unwrap()
+            .into_simple_linear()
+            .unwrap();
+        let digest = |missed| {
+            peptide
+                .digest(&after_kr, missed)
+                .iter()
+                .map(ToString::to_string)
+                .collect_vec()
+        };
+        assert_eq!(digest(0), vec!["AK", "R", "AAK"]);
+        assert_eq!(digest(1), vec!["AK", "AKR", "R", "RAAK", "AAK"]);
+        assert_eq!(digest(2).len(), 6);
+        assert_eq!(digest(10).len(), 6);
+
+        // A cut site at the very start of the sequence does not give empty peptides
+        let before_kr = Protease::c_terminal_of(&[AminoAcid::Lysine, AminoAcid::Arginine]);
+        let peptide = LinearPeptide::pro_forma("KAAKRA", None)
+            .unwrap()
+            .into_simple_linear()
+            .unwrap();
+        let digested = peptide.digest(&before_kr, 1);
+        assert!(digested.iter().all(|p| !p.is_empty()));
+        assert_eq!(
+            digested.iter().map(ToString::to_string).collect_vec(),
+            vec!["KAA", "KAAK", "K", "KRA", "RA"]
+        );
+        assert!(LinearPeptide::pro_forma("K", None)
+            .unwrap()
+            .into_simple_linear()
+            .unwrap()
+            .digest(
+                &Protease::new(&[], &[AminoAcid::Lysine, AminoAcid::Lysine]),
+                0
+            )
+            .iter()
+            .all(|p| p.len() == 1));
+    }
+
+    #[test]
+    fn custom_rules() {
+        let peptide = LinearPeptide::pro_forma("ADPADKADAKPR", None)
+            .unwrap()
+            .into_simple_linear()
+            .unwrap();
+        let digest = |protease: &Protease| {
+            peptide
+                .digest(protease, 0)
+                .iter()
+                .map(ToString::to_string)
+                .collect_vec()
+        };
+        let after_d_not_p = Protease::from_sites(
+            vec![AminoAcid::AsparticAcid],
+            Vec::new(),
+            Vec::new(),
+            vec![AminoAcid::Proline],
+        );
+        assert_eq!(digest(&after_d_not_p), vec!["ADPAD", "KAD", "AKPR"]);
+        assert_eq!(
+            Protease::from_rule_str("D|{P}")
+                .unwrap()
+                .match_locations(peptide.sequence()),
+            after_d_not_p.match_locations(peptide.sequence())
+        );
+        let trypsin = Protease::from_rule_str("[KR]|{P}").unwrap();
+        assert_eq!(trypsin.match_locations(peptide.sequence()), vec![6]);
+        assert_eq!(digest(&trypsin), vec!["ADPADK", "ADAKPR"]);
+        let not_after_a = Protease::from_sites(
+            Vec::new(),
+            vec![AminoAcid::Lysine],
+            vec![AminoAcid::Alanine],
+            Vec::new(),
+        );
+        assert_eq!(not_after_a.match_locations(peptide.sequence()), vec![5]);
+        assert_eq!(
+            Protease::from_rule_str("X-{A}|K")
+                .unwrap()
+                .match_locations(peptide.sequence()),
+            vec![5]
+        );
+        assert!(Protease::from_rule_str("[KR]{P}").is_err());
+        assert!(Protease::from_rule_str("[KR]|{P}|").is_err());
+        assert!(Protease::from_rule_str("[KR|P").is_err());
+        assert!(Protease::from_rule_str("[K1]|P").is_err());
+    }
+
+    #[test]
+    fn digest_compound() {
+        let trypsin = Protease::new(&[AminoAcid::Lysine], &[]);
+        let chimeric = CompoundPeptidoform::pro_forma("AKAAK+ACKR", None).unwrap();
+        assert_eq!(chimeric.peptides().count(), 2);
+        let digested = chimeric.digest(&trypsin, 0);
+        assert_eq!(
+            digested.iter().map(ToString::to_string).collect_vec(),
+            vec!["AK", "AAK", "ACK", "R"]
+        );
+        let cross_linked =
+            CompoundPeptidoform::pro_forma("AK[X:DSS#XL1]AAK//ACK[#XL1]R", None).unwrap();
+        assert_eq!(cross_linked.peptides().count(), 2);
+        let digested = cross_linked.digest(&trypsin, 0);
+        assert_eq!(
+            digested.iter().map(ToString::to_string).collect_vec(),
+            vec!["AK", "AAK", "ACK", "R"]
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn digest_proteome() {
+        let proteins = [
+            "MKWVTFISLLFLFSSAYSRGVFRRDTHKSEIAHRFKDLGEEHFK",
+            "AKAAAAAAKAAAAAAAAAAAAR",
+            "MKWVTFISLLFLFSSAYSR",
+            "DTHKSEIAHR",
+        ]
+        .map(|p| {
+            LinearPeptide::pro_forma(p, None)
+                .unwrap()
+                .into_linear()
+                .unwrap()
+        });
+        let trypsin = Protease::c_terminal_of(&[AminoAcid::Lysine, AminoAcid::Arginine]);
+        let parallel = super::digest_proteome(&proteins, &trypsin, 1, 4..=30, ..);
+        let mut serial = proteins
+            .iter()
+            .flat_map(|p| p.digest_filtered(&trypsin, 1, 4..=30, ..))
+            .map(|p| p.to_string())
+            .collect_vec();
+        serial.sort_unstable();
+        serial.dedup();
+        let mut parallel = parallel.iter().map(ToString::to_string).collect_vec();
+        assert!(parallel.iter().all_unique());
+        parallel.sort_unstable();
+        assert_eq!(parallel, serial);
+    }
+}
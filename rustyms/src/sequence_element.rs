@@ -5,18 +5,19 @@ use std::{collections::HashSet, fmt::Write, marker::PhantomData};
 use crate::{
     error::{Context, CustomError},
     modification::{
-        AmbiguousModification, CrossLinkName, LinkerSpecificity, Modification, RulePossible,
-        SimpleModification,
+        display_info_tags, AmbiguousModification, CrossLinkName, LinkerSpecificity, Modification,
+        RulePossible, SimpleModification,
     },
     peptide::{AtLeast, Linked},
     placement_rule::PlacementRule,
+    system::Mass,
     CheckedAminoAcid, Chemical, DiagnosticIon, LinearPeptide, MolecularFormula, Multi,
     MultiChemical, SequencePosition,
 };
 use serde::{Deserialize, Serialize};
 
 /// One block in a sequence meaning an aminoacid and its accompanying modifications
-#[derive(Default, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+#[derive(Default, Debug, Serialize, Deserialize)]
 pub struct SequenceElement<T> {
     /// The aminoacid
     pub aminoacid: CheckedAminoAcid<T>,
@@ -26,6 +27,10 @@ pub struct SequenceElement<T> {
     pub possible_modifications: Vec<AmbiguousModification>,
     /// If this aminoacid is part of an ambiguous sequence group `(QA)?` in ProForma
     pub ambiguous: Option<usize>,
+    /// The `INFO` tags given with the modifications on this position, these are not taken into
+    /// account when comparing sequence elements, see [`Self::info_tags`].
+    #[serde(default)]
+    pub(crate) info_tags: Vec<(SimpleModification, Vec<String>)>,
     /// The marker indicating which level of complexity this sequence element uses as higher bound
     marker: PhantomData<T>,
 }
@@ -37,6 +42,7 @@ impl<T> Clone for SequenceElement<T> {
             modifications: self.modifications.clone(),
             possible_modifications: self.possible_modifications.clone(),
             ambiguous: self.ambiguous,
+            info_tags: self.info_tags.clone(),
             marker: PhantomData,
         }
     }
@@ -62,6 +68,26 @@ impl<T> std::hash::Hash for SequenceElement<T> {
 
 impl<T> Eq for SequenceElement<T> {}
 
+impl<T: Ord> PartialOrd for SequenceElement<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Compares the same fields as [`PartialEq`], so the `INFO` tags are ignored
+impl<T: Ord> Ord for SequenceElement<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.aminoacid
+            .cmp(&other.aminoacid)
+            .then_with(|| self.modifications.cmp(&other.modifications))
+            .then_with(|| {
+                self.possible_modifications
+                    .cmp(&other.possible_modifications)
+            })
+            .then_with(|| self.ambiguous.cmp(&other.ambiguous))
+    }
+}
+
 impl<T> SequenceElement<T> {
     /// Mark this sequence element as the following complexity level, the level is not validated
     pub(super) fn mark<M>(self) -> SequenceElement<M> {
@@ -70,6 +96,7 @@ impl<T> SequenceElement<T> {
             modifications: self.modifications,
             possible_modifications: self.possible_modifications,
             ambiguous: self.ambiguous,
+            info_tags: self.info_tags,
             marker: PhantomData,
         }
     }
@@ -87,10 +114,72 @@ impl<T> SequenceElement<T> {
             modifications: Vec::new(),
             possible_modifications: Vec::new(),
             ambiguous,
+            info_tags: Vec::new(),
             marker: PhantomData,
         }
     }
 
+    /// Create a new aminoacid with the given modifications
+    pub const fn with_modifications(
+        aminoacid: CheckedAminoAcid<T>,
+        ambiguous: Option<usize>,
+        modifications: Vec<Modification>,
+    ) -> Self {
+        Self {
+            aminoacid,
+            modifications,
+            possible_modifications: Vec::new(),
+            ambiguous,
+            info_tags: Vec::new(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Get the `INFO` tags given with the given modification on this position.
+    pub fn info_tags(&self, modification: &SimpleModification) -> &[String] {
+        self.info_tags
+            .iter()
+            .find(|(m, _)| m == modification)
+            .map_or(&[], |(_, tags)| tags)
+    }
+
+    /// Add `INFO` tags to the given modification on this position, tags that are already present
+    /// are skipped. The modification itself is not added to this position.
+    pub fn add_info_tags(
+        &mut self,
+        modification: &SimpleModification,
+        tags: impl IntoIterator<Item = String>,
+    ) {
+        let index = self
+            .info_tags
+            .iter()
+            .position(|(m, _)| m == modification)
+            .unwrap_or_else(|| {
+                self.info_tags.push((modification.clone(), Vec::new()));
+                self.info_tags.len() - 1
+            });
+        for tag in tags {
+            if !self.info_tags[index].1.contains(&tag) {
+                self.info_tags[index].1.push(tag);
+            }
+        }
+        if self.info_tags[index].1.is_empty() {
+            self.info_tags.remove(index);
+        }
+    }
+
+    /// Add a modification to this sequence element
+    #[must_use]
+    pub fn with_modification(mut self, modification: Modification) -> Self {
+        self.modifications.push(modification);
+        self
+    }
+
+    /// Add a modification to this sequence element
+    pub fn add_modification(&mut self, modification: Modification) {
+        self.modifications.push(modification);
+    }
+
     /// Add a modification to this sequence element
     #[must_use]
     pub fn with_simple_modification(mut self, modification: SimpleModification) -> Self {
@@ -102,6 +191,19 @@ impl<T> SequenceElement<T> {
     pub fn add_simple_modification(&mut self, modification: SimpleModification) {
         self.modifications.push(Modification::Simple(modification));
     }
+
+    /// Get the summed formula of all modifications on this sequence element, without the amino
+    /// acid itself. For cross-links only the linker is included. Ambiguous modifications that
+    /// could be placed here are not included.
+    pub fn modification_formula(&self) -> MolecularFormula {
+        self.modifications.iter().map(Modification::formula).sum()
+    }
+
+    /// Get the summed monoisotopic mass of all modifications on this sequence element, see
+    /// [`Self::modification_formula`].
+    pub fn modification_mass(&self) -> Mass {
+        self.modification_formula().monoisotopic_mass()
+    }
 }
 
 impl<T> SequenceElement<T> {
@@ -111,6 +213,7 @@ impl<T> SequenceElement<T> {
         &self,
         f: &mut impl Write,
         placed: &[usize],
+        unknown_position: &[usize],
         last_ambiguous: Option<usize>,
         specification_compliant: bool,
     ) -> Result<Vec<usize>, std::fmt::Error> {
@@ -125,22 +228,34 @@ impl<T> SequenceElement<T> {
         for m in &self.modifications {
             write!(f, "[")?;
             m.display(f, specification_compliant)?;
+            if let Some(simple) = m.simple() {
+                display_info_tags(f, self.info_tags(simple))?;
+            }
             write!(f, "]")?;
         }
-        for m in &self.possible_modifications {
+        for m in self
+            .possible_modifications
+            .iter()
+            .filter(|m| !unknown_position.contains(&m.id))
+        {
             write!(f, "[",)?;
-            if m.preferred && !placed.contains(&m.id) {
+            let definition = m.preferred && !placed.contains(&m.id);
+            if definition {
                 extra_placed.push(m.id);
                 m.modification.display(f, specification_compliant)?;
             };
             write!(
                 f,
-                "\x23{}{}]",
+                "\x23{}{}",
                 m.group,
                 m.localisation_score
                     .map(|v| format!("({v})"))
                     .unwrap_or_default()
             )?;
+            if definition {
+                display_info_tags(f, &m.info_tags)?;
+            }
+            write!(f, "]")?;
         }
         Ok(extra_placed)
     }
@@ -328,3 +443,65 @@ where
         Self::new(value.into(), None)
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::missing_panics_doc)]
+mod tests {
+    use super::*;
+    use crate::{modification::Ontology, peptide::UnAmbiguous, LinearPeptide};
+
+    #[test]
+    fn with_modifications() {
+        let phospho: Modification = Ontology::Unimod.find_id(21, None).unwrap().into();
+        let built = SequenceElement::with_modifications(
+            CheckedAminoAcid::<UnAmbiguous>::S,
+            None,
+            vec![phospho.clone()],
+        );
+        let pushed = SequenceElement::new(CheckedAminoAcid::<UnAmbiguous>::S, None)
+            .with_modification(phospho.clone());
+        assert_eq!(built, pushed);
+
+        let peptide: LinearPeptide<UnAmbiguous> = [
+            SequenceElement::new(CheckedAminoAcid::A, None),
+            built,
+            SequenceElement::new(CheckedAminoAcid::K, None),
+        ]
+        .into();
+        assert!(peptide.enforce_modification_rules().is_ok());
+
+        let peptide: LinearPeptide<UnAmbiguous> = [
+            SequenceElement::new(CheckedAminoAcid::A, None),
+            SequenceElement::with_modifications(CheckedAminoAcid::G, None, vec![phospho]),
+            SequenceElement::new(CheckedAminoAcid::K, None),
+        ]
+        .into();
+        assert!(peptide.enforce_modification_rules().is_err());
+    }
+    #[test]
+    fn modification_formula() {
+        let serine = LinearPeptide::pro_forma("S", None).unwrap();
+        let phospho = LinearPeptide::pro_forma("S[Phospho]", None).unwrap();
+        assert_eq!(
+            serine.sequence()[0].modification_formula(),
+            crate::MolecularFormula::default()
+        );
+        assert_eq!(
+            phospho.sequence()[0].modification_formula(),
+            molecular_formula!(H 1 O 3 P 1)
+        );
+        assert!(
+            ((phospho.sequence()[0].modification_mass()
+                - serine.sequence()[0].modification_mass())
+            .value
+                - 79.966_331)
+                .abs()
+                < 1e-5
+        );
+        let double = LinearPeptide::pro_forma("S[Phospho][Formula:O1]", None).unwrap();
+        assert_eq!(
+            double.sequence()[0].modification_formula(),
+            molecular_formula!(H 1 O 4 P 1)
+        );
+    }
+}
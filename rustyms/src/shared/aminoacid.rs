@@ -81,6 +81,41 @@ impl AminoAcid {
             _ => Err(NotACodon),
         }
     }
+
+    /// Get the amino acid from its three letter code (case insensitive), eg `Ala` for alanine.
+    /// This includes the ambiguous (`Asx`, `Xle`, `Glx`, and `Xaa`) and non-standard (`Sec` and
+    /// `Pyl`) amino acids.
+    pub fn from_three_letter(code: &str) -> Option<Self> {
+        match code.to_ascii_lowercase().as_str() {
+            "ala" => Some(Self::Alanine),
+            "arg" => Some(Self::Arginine),
+            "asn" => Some(Self::Asparagine),
+            "asp" => Some(Self::AsparticAcid),
+            "cys" => Some(Self::Cysteine),
+            "gln" => Some(Self::Glutamine),
+            "glu" => Some(Self::GlutamicAcid),
+            "gly" => Some(Self::Glycine),
+            "his" => Some(Self::Histidine),
+            "ile" => Some(Self::Isoleucine),
+            "leu" => Some(Self::Leucine),
+            "lys" => Some(Self::Lysine),
+            "met" => Some(Self::Methionine),
+            "phe" => Some(Self::Phenylalanine),
+            "pro" => Some(Self::Proline),
+            "ser" => Some(Self::Serine),
+            "thr" => Some(Self::Threonine),
+            "trp" => Some(Self::Tryptophan),
+            "tyr" => Some(Self::Tyrosine),
+            "val" => Some(Self::Valine),
+            "asx" => Some(Self::AmbiguousAsparagine),
+            "xle" => Some(Self::AmbiguousLeucine),
+            "glx" => Some(Self::AmbiguousGlutamine),
+            "sec" => Some(Self::Selenocysteine),
+            "pyl" => Some(Self::Pyrrolysine),
+            "xaa" => Some(Self::Unknown),
+            _ => None,
+        }
+    }
 }
 
 impl std::str::FromStr for AminoAcid {
@@ -92,12 +127,13 @@ impl std::str::FromStr for AminoAcid {
 
 impl TryFrom<&str> for AminoAcid {
     type Error = ();
+    /// Parse a one letter or three letter code (case insensitive), see [`Self::from_three_letter`]
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         if value.is_ascii() && value.len() == 1 {
             let ch = value.chars().next().unwrap();
             ch.try_into()
         } else {
-            Err(())
+            Self::from_three_letter(value).ok_or(())
         }
     }
 }
@@ -195,7 +231,7 @@ impl MultiChemical for AminoAcid {
             Self::Methionine => molecular_formula!(H 9 C 5 O 1 N 1 S 1).into(),
             Self::Phenylalanine => molecular_formula!(H 9 C 9 O 1 N 1).into(),
             Self::Proline => molecular_formula!(H 7 C 5 O 1 N 1).into(),
-            Self::Pyrrolysine => molecular_formula!(H 19 C 11 O 2 N 3).into(),
+            Self::Pyrrolysine => molecular_formula!(H 19 C 12 O 2 N 3).into(),
             Self::Selenocysteine => molecular_formula!(H 5 C 3 O 1 N 1 Se 1).into(),
             Self::Serine => molecular_formula!(H 5 C 3 O 2 N 1).into(),
             Self::Threonine => molecular_formula!(H 7 C 4 O 2 N 1).into(),
@@ -27,6 +27,14 @@ impl MolecularFormula {
     /// Example: [13C2][12C-2]H2N
     /// Example: [13C2]C-2H2N
     /// ```
+    /// ## Element casing
+    /// Element symbols are only recognised in their canonical casing, the first letter uppercase
+    /// and any second letter lowercase. Two letter symbols are tried before one letter symbols. So
+    /// `Co` is cobalt while `CO` is carbon and oxygen, and `co` is rejected. Spaces are allowed
+    /// between all parts of the formula.
+    /// ```text
+    /// Example: CO2 (carbon dioxide) vs Co2 (two cobalt atoms)
+    /// ```
     /// ## Allow charge
     /// Allows electrons to be used to define the charge of the formula
     /// ## Allow empty
@@ -90,10 +98,10 @@ impl MolecularFormula {
                         element = Some(Element::Electron);
                     } else {
                         for possible in ELEMENT_PARSE_LIST {
-                            if value[index + isotope + ws1..index + isotope + ws1 + ele]
-                                .to_ascii_lowercase()
-                                == possible.0
-                            {
+                            if is_canonical_symbol(
+                                &value[index + isotope + ws1..index + isotope + ws1 + ele],
+                                possible.0,
+                            ) {
                                 element = Some(possible.1);
                                 break;
                             }
@@ -203,13 +211,12 @@ impl MolecularFormula {
                             ));
                         }
                     }
-                    let element_text: String = value[index..]
-                        .chars()
-                        .take(2)
-                        .collect::<String>()
-                        .to_ascii_lowercase();
+                    let element_text: String = value[index..].chars().take(2).collect::<String>();
                     for possible in ELEMENT_PARSE_LIST {
-                        if element_text.starts_with(possible.0) {
+                        if element_text
+                            .get(..possible.0.len())
+                            .is_some_and(|text| is_canonical_symbol(text, possible.0))
+                        {
                             element = Some(possible.1);
                             index += possible.0.len();
                             continue 'main_parse_loop;
@@ -249,3 +256,20 @@ impl MolecularFormula {
         Ok(result)
     }
 }
+
+/// Check if the text is the given element symbol (stored lowercase, as in [`ELEMENT_PARSE_LIST`])
+/// written in its canonical casing: first letter uppercase, any following letters lowercase.
+fn is_canonical_symbol(text: &str, symbol: &str) -> bool {
+    text.len() == symbol.len()
+        && text
+            .bytes()
+            .zip(symbol.bytes())
+            .enumerate()
+            .all(|(i, (t, s))| {
+                if i == 0 {
+                    t == s.to_ascii_uppercase()
+                } else {
+                    t == s
+                }
+            })
+}
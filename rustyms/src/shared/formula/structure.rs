@@ -204,6 +204,13 @@ impl MolecularFormula {
         self
     }
 
+    /// Remove all labels from this formula
+    #[allow(dead_code)]
+    pub(crate) fn without_labels(mut self) -> Self {
+        self.labels.clear();
+        self
+    }
+
     /// The labels of sources of ambiguity/multiplicity
     pub fn labels(&self) -> &[AmbiguousLabel] {
         &self.labels
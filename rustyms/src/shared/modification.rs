@@ -159,6 +159,8 @@ pub enum LinkerSpecificity {
 pub enum GnoComposition {
     /// Only the mass is known
     Mass(OrderedMass),
+    /// Only the composition is known
+    Composition(Vec<(MonoSaccharide, isize)>),
     /// The (full) structure is known
     Structure(GlycanStructure),
 }
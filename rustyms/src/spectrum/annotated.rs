@@ -2,16 +2,21 @@
 
 use std::cmp::Ordering;
 
+use itertools::Itertools;
 use ordered_float::OrderedFloat;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    fragment::Fragment,
+    fragment::{Fragment, FragmentKind},
+    molecular_charge::MolecularCharge,
+    peptide::{AtLeast, AtMax, Linear, SimpleLinear},
     system::{
-        f64::{Mass, MassOverCharge, Time},
+        e,
+        f64::{Mass, MassOverCharge, Ratio, Time},
         usize::Charge,
     },
-    CompoundPeptidoform,
+    Chemical, CompoundPeptidoform, LinearPeptide, MassMode, Model, MolecularFormula, Multi,
+    Tolerance, WithinTolerance,
 };
 
 use super::{PeakSpectrum, RawPeak};
@@ -35,6 +40,298 @@ pub struct AnnotatedSpectrum {
     pub(super) spectrum: Vec<AnnotatedPeak>,
 }
 
+impl AnnotatedSpectrum {
+    /// Get the error (in ppm) between the observed precursor m/z and the theoretical m/z of the
+    /// peptide, for all possible formulas of the peptide. The observed m/z is calculated as
+    /// `mass / charge` so `mass` is assumed to be the full mass of the charged precursor. The
+    /// theoretical m/z uses the charge carriers of the peptide or protons if these are not set.
+    /// Returns None if the precursor mass or charge is not known.
+    /// # Panics
+    /// If the precursor charge is higher than [`isize::MAX`].
+    pub fn precursor_mass_error(&self) -> Option<Multi<Ratio>> {
+        let charge = self.charge.filter(|c| c.value != 0)?;
+        let observed = self.mass? / charge.to_float();
+        let charge_carriers = self
+            .peptide
+            .peptides()
+            .find_map(LinearPeptide::get_charge_carriers)
+            .cloned()
+            .unwrap_or_else(|| {
+                MolecularCharge::proton(
+                    isize::try_from(charge.value)
+                        .expect("Charge of the precursor cannot be higher then isize::MAX"),
+                )
+            })
+            .formula();
+        Some(
+            self.peptide
+                .formulas()
+                .iter()
+                .map(|formula| {
+                    ((formula + &charge_carriers).monoisotopic_mass() / charge.to_float())
+                        .ppm(observed)
+                })
+                .collect(),
+        )
+    }
+
+    /// Annotate the peak at the given index with the given fragment. If multiple annotations are
+    /// allowed by the model all neighbouring peaks within the tolerance of the fragment m/z are
+    /// annotated as well.
+    pub(super) fn add_annotation(
+        &mut self,
+        index: usize,
+        mz: MassOverCharge,
+        fragment: &Fragment,
+        model: &Model,
+    ) {
+        self.spectrum[index].annotation.push(fragment.clone());
+        if model.allow_multiple_annotations {
+            let within = |peak: &AnnotatedPeak| model.tolerance.within(&peak.experimental_mz, &mz);
+            let (before, after) = self.spectrum.split_at_mut(index);
+            for peak in before
+                .iter_mut()
+                .rev()
+                .take_while(|peak| within(peak))
+                .chain(after.iter_mut().skip(1).take_while(|peak| within(peak)))
+            {
+                peak.annotation.push(fragment.clone());
+            }
+        }
+    }
+
+    /// Get the complementary b and y ions for all backbone bonds of all peptidoforms. For every bond
+    /// the most intense matched b and y ion (without neutral losses and on the monoisotopic peak)
+    /// are given. Cross-linked peptidoforms are skipped as the b and y ions there are not
+    /// complementary.
+    pub fn complementary_pairs(&self) -> Vec<ComplementaryPair> {
+        let mut pairs = Vec::new();
+        for (peptidoform_index, peptidoform) in self.peptide.peptidoforms().iter().enumerate() {
+            let ([peptide], Some(precursor)) = (
+                peptidoform.peptides(),
+                peptidoform
+                    .formulas()
+                    .first()
+                    .map(MolecularFormula::monoisotopic_mass),
+            ) else {
+                continue;
+            };
+            let find = |series: FragmentKind, series_number: usize| {
+                self.spectrum
+                    .iter()
+                    .flat_map(|peak| peak.annotation.iter().map(move |f| (peak, f)))
+                    .filter(|(_, f)| {
+                        f.peptidoform_index == peptidoform_index
+                            && f.is_series(series)
+                            && f.neutral_loss.is_none()
+                            && f.isotope == 0
+                            && f.ordinal() == Some(series_number)
+                    })
+                    .max_by(|a, b| a.0.intensity.cmp(&b.0.intensity))
+                    .map(|(peak, f)| (peak.experimental_mz, f.clone()))
+            };
+            for bond in 0..peptide.len().saturating_sub(1) {
+                let b = find(FragmentKind::b, bond + 1);
+                let y = find(FragmentKind::y, peptide.len() - bond - 1);
+                let mass_error = b.as_ref().zip(y.as_ref()).map(|(b, y)| {
+                    ComplementaryPair::experimental_neutral_mass(b)
+                        + ComplementaryPair::experimental_neutral_mass(y)
+                        - precursor
+                });
+                pairs.push(ComplementaryPair {
+                    peptidoform_index,
+                    bond,
+                    b,
+                    y,
+                    mass_error,
+                });
+            }
+        }
+        pairs
+    }
+
+    /// Get the backbone coverage of the first peptide of the first peptidoform, as used to draw
+    /// the classic split fragmentation ladder. For every backbone bond, between the residue at
+    /// that (0 based) index and the next residue, this gives if an N terminal (a, b, or c) ion and
+    /// if a C terminal (x, y, or z) ion breaking that bond is annotated.
+    pub fn backbone_coverage(&self) -> Vec<(bool, bool)> {
+        let length = self
+            .peptide
+            .peptidoforms()
+            .first()
+            .and_then(|p| p.peptides().first())
+            .map_or(0, LinearPeptide::len);
+        let mut coverage = vec![(false, false); length.saturating_sub(1)];
+        for fragment in self
+            .spectrum
+            .iter()
+            .flat_map(|peak| &peak.annotation)
+            .filter(|f| f.peptidoform_index == 0 && f.peptide_index == 0)
+        {
+            let Some(ordinal) = fragment.ordinal() else {
+                continue;
+            };
+            match fragment.ion.kind() {
+                FragmentKind::a | FragmentKind::b | FragmentKind::c => {
+                    if let Some(bond) = ordinal.checked_sub(1).and_then(|i| coverage.get_mut(i)) {
+                        bond.0 = true;
+                    }
+                }
+                FragmentKind::x | FragmentKind::y | FragmentKind::z => {
+                    if let Some(bond) = length
+                        .checked_sub(ordinal + 1)
+                        .and_then(|i| coverage.get_mut(i))
+                    {
+                        bond.1 = true;
+                    }
+                }
+                _ => (),
+            }
+        }
+        coverage
+    }
+
+    /// Localise all ambiguous modifications of the given peptide on this spectrum. For every
+    /// ambiguous modification (in order of their id) all potential locations are given with the
+    /// probability that the modification is located there, the probabilities for one ambiguous
+    /// modification sum to one.
+    ///
+    /// The scoring follows the phosphoRS approach. For each potential location the isoform with the
+    /// modification placed there is fragmented with the given model up to the precursor charge (or 1 if
+    /// unknown). The site determining ions of an isoform are its fragments whose m/z is not shared by
+    /// all other isoforms. Of these `n` site determining ions `k` are matched to a peak in this
+    /// spectrum, within the tolerance and with the mass mode of the model. The chance of matching at
+    /// least `k` ions by chance is the binomial tail `P(X >= k)` with `X ~ B(n, p)`. Here `p` is the
+    /// chance of a random m/z matching any peak, estimated as the summed width of the tolerance windows
+    /// around all peaks divided by the m/z range of the spectrum. The probability for location `i` is
+    /// then `(1 / P_i) / Σ_j (1 / P_j)`. If no location has site determining ions all locations are
+    /// equally likely.
+    pub fn localize<Complexity: AtMax<Linear> + AtLeast<SimpleLinear>>(
+        &self,
+        peptide: &LinearPeptide<Complexity>,
+        model: &Model,
+    ) -> Vec<(usize, f64)> {
+        let max_charge = self.charge.unwrap_or_else(|| Charge::new::<e>(1));
+        let tolerance = model.tolerance;
+        let probability = self.random_match_probability(tolerance);
+        let contains = |sorted: &[MassOverCharge], mz: MassOverCharge| {
+            let (low, high) = tolerance.bounds(mz);
+            let index = sorted.partition_point(|m| *m < low);
+            sorted.get(index).is_some_and(|m| *m <= high)
+        };
+
+        let mut result = Vec::new();
+        for (id, positions) in peptide.get_ambiguous_modifications().iter().enumerate() {
+            let isoforms = positions
+                .iter()
+                .filter_map(|position| {
+                    let isoform = peptide.place_ambiguous_modification(id, *position)?;
+                    let mz = isoform
+                        .generate_theoretical_fragments(max_charge, model)
+                        .iter()
+                        .map(|f| f.mz(model.mass_mode))
+                        .sorted_unstable_by(|a, b| a.value.total_cmp(&b.value))
+                        .dedup()
+                        .collect_vec();
+                    Some((*position, mz))
+                })
+                .collect_vec();
+            let scores = isoforms
+                .iter()
+                .map(|(_, mz)| {
+                    let (n, k) = mz
+                        .iter()
+                        .filter(|m| !isoforms.iter().all(|(_, other)| contains(other, **m)))
+                        .fold((0, 0), |(n, k), m| {
+                            let (low, high) = tolerance.bounds(*m);
+                            let matched = self
+                                .binary_search(low, high)
+                                .iter()
+                                .any(|p| p.experimental_mz >= low && p.experimental_mz <= high);
+                            (n + 1, k + usize::from(matched))
+                        });
+                    -ln_binomial_tail(n, k, probability)
+                })
+                .collect_vec();
+            let max = scores.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            let total: f64 = scores.iter().map(|s| (s - max).exp()).sum();
+            result.extend(
+                isoforms
+                    .iter()
+                    .zip(&scores)
+                    .map(|((position, _), score)| (*position, (score - max).exp() / total)),
+            );
+        }
+        result
+    }
+
+    /// The chance for a random m/z to match any peak in this spectrum within the given tolerance
+    fn random_match_probability(&self, tolerance: Tolerance<MassOverCharge>) -> f64 {
+        let (Some(first), Some(last)) = (self.spectrum.first(), self.spectrum.last()) else {
+            return 1.0;
+        };
+        let range = (last.experimental_mz - first.experimental_mz).value;
+        if range <= 0.0 {
+            return 1.0;
+        }
+        let windows: f64 = self
+            .spectrum
+            .iter()
+            .map(|p| {
+                let (low, high) = tolerance.bounds(p.experimental_mz);
+                (high - low).value
+            })
+            .sum();
+        (windows / range).clamp(f64::EPSILON, 1.0)
+    }
+}
+
+/// The natural logarithm of the chance of at least `k` successes in `n` tries with chance `p`
+fn ln_binomial_tail(n: usize, k: usize, p: f64) -> f64 {
+    if k == 0 || p >= 1.0 {
+        return 0.0;
+    }
+    let (ln_p, ln_q) = (p.ln(), (1.0 - p).ln());
+    let mut ln_choose = 0.0;
+    let mut terms = Vec::with_capacity(n + 1 - k);
+    for j in 0..=n {
+        if j > 0 {
+            ln_choose += ((n - j + 1) as f64).ln() - (j as f64).ln();
+        }
+        if j >= k {
+            terms.push(ln_choose + j as f64 * ln_p + (n - j) as f64 * ln_q);
+        }
+    }
+    let max = terms.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    max + terms.iter().map(|t| (t - max).exp()).sum::<f64>().ln()
+}
+
+/// The complementary b and y ions around a single backbone bond, see [`AnnotatedSpectrum::complementary_pairs`]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ComplementaryPair {
+    /// The peptidoform this bond is in, saved as the index into the list of peptidoforms in the annotated [`CompoundPeptidoform`]
+    pub peptidoform_index: usize,
+    /// The bond, this bond is between the residue at this (0 based) index and the next residue
+    pub bond: usize,
+    /// The matched b ion with the experimental mz, if matched
+    pub b: Option<(MassOverCharge, Fragment)>,
+    /// The matched y ion with the experimental mz, if matched
+    pub y: Option<(MassOverCharge, Fragment)>,
+    /// The summed experimental neutral mass of the b and y ion minus the neutral mass of the
+    /// precursor, if both are matched. For singly charged ions this is the same as comparing
+    /// `b + y` to the precursor plus a proton. A big difference indicates a potential
+    /// misannotation.
+    pub mass_error: Option<Mass>,
+}
+
+impl ComplementaryPair {
+    /// Get the neutral mass of a fragment based on the experimental mz
+    fn experimental_neutral_mass((mz, fragment): &(MassOverCharge, Fragment)) -> Mass {
+        fragment.neutral_mass(MassMode::Monoisotopic)
+            + (*mz - fragment.mz(MassMode::Monoisotopic)) * fragment.charge.to_float()
+    }
+}
+
 impl Extend<AnnotatedPeak> for AnnotatedSpectrum {
     fn extend<T: IntoIterator<Item = AnnotatedPeak>>(&mut self, iter: T) {
         self.spectrum.extend(iter);
@@ -145,6 +442,13 @@ impl AnnotatedPeak {
             isotope_annotation: Vec::new(),
         }
     }
+
+    /// Get the charge of this peak as determined by the annotation. If there are multiple
+    /// annotations the charge of the first annotation is given. For background peaks (no
+    /// annotation) the charge is unknown so this returns None.
+    pub fn charge(&self) -> Option<Charge> {
+        self.annotation.first().map(|fragment| fragment.charge)
+    }
 }
 
 impl PartialOrd for AnnotatedPeak {
@@ -175,3 +479,193 @@ impl PartialEq for AnnotatedPeak {
 }
 
 impl Eq for AnnotatedPeak {}
+
+#[cfg(test)]
+#[allow(clippy::missing_panics_doc)]
+mod tests {
+    use crate::{
+        fragment, mz, spectrum, system, AnnotatableSpectrum, CompoundPeptidoform, Fragment,
+        LinearPeptide, MassMode, Model, RawSpectrum,
+    };
+
+    #[test]
+    fn annotated_peak_charge() {
+        use crate::spectrum::{PeakSpectrum, RawPeak};
+
+        let model = Model::all();
+        let peptide = CompoundPeptidoform::pro_forma("PEPTIDEK", None).unwrap();
+        let charge = system::usize::Charge::new::<system::e>(2);
+        let fragments = peptide.generate_theoretical_fragments(charge, &model);
+        let y = fragments
+            .iter()
+            .find(|f| matches!(f.ion, fragment::FragmentType::y(_)) && f.charge == charge)
+            .unwrap();
+        let mut spectrum = RawSpectrum::default();
+        spectrum.extend([
+            RawPeak {
+                mz: y.mz(MassMode::Monoisotopic),
+                intensity: 1.0.into(),
+            },
+            RawPeak {
+                mz: system::MassOverCharge::new::<system::mz>(10.0),
+                intensity: 1.0.into(),
+            },
+        ]);
+        let annotated = spectrum.annotate(peptide, &fragments, &model);
+        let peaks = annotated.spectrum().collect::<Vec<_>>();
+        assert_eq!(peaks[0].charge(), None);
+        assert_eq!(peaks[1].charge(), Some(charge));
+    }
+
+    #[test]
+    fn precursor_mass_error() {
+        let model = Model::all();
+        let peptide = CompoundPeptidoform::pro_forma("PEPTIDEK", None).unwrap();
+        let charge = system::usize::Charge::new::<system::e>(2);
+        let fragments = peptide.generate_theoretical_fragments(charge, &model);
+        let full = (peptide.formulas()[0].clone() + molecular_formula!(H 2 Electron -2))
+            .monoisotopic_mass();
+        let mut spectrum = RawSpectrum::default();
+        spectrum.extend([spectrum::RawPeak {
+            mz: fragments[0].mz(MassMode::Monoisotopic),
+            intensity: 1.0.into(),
+        }]);
+        spectrum.charge = Some(charge);
+        spectrum.mass = Some(full * (1.0 + 10e-6));
+        let annotated = spectrum.annotate(peptide, &fragments, &model);
+        let errors = annotated.precursor_mass_error().unwrap();
+        assert_eq!(errors.len(), 1);
+        assert!((errors[0].get::<system::ratio::ppm>() - 10.0).abs() < 0.01);
+
+        let mut unknown = annotated;
+        unknown.charge = None;
+        assert!(unknown.precursor_mass_error().is_none());
+    }
+
+    #[test]
+    fn complementary_pairs() {
+        use crate::fragment::FragmentKind;
+        use crate::spectrum::RawPeak;
+
+        let model = Model::all();
+        let peptide = CompoundPeptidoform::pro_forma("PEPTIDEK", None).unwrap();
+        let charge = system::usize::Charge::new::<system::e>(1);
+        let fragments = peptide.generate_theoretical_fragments(charge, &model);
+        let find = |series: FragmentKind, ordinal: usize| {
+            fragments
+                .iter()
+                .find(|f| {
+                    f.is_series(series)
+                        && f.ordinal() == Some(ordinal)
+                        && f.neutral_loss.is_none()
+                        && f.isotope == 0
+                })
+                .unwrap()
+                .mz(MassMode::Monoisotopic)
+        };
+        let mut spectrum = RawSpectrum::default();
+        spectrum.extend(
+            [
+                find(FragmentKind::b, 2),
+                find(FragmentKind::b, 3),
+                find(FragmentKind::y, 5),
+            ]
+            .map(|mz| RawPeak {
+                mz,
+                intensity: 1.0.into(),
+            }),
+        );
+        let annotated = spectrum.annotate(peptide, &fragments, &model);
+        let pairs = annotated.complementary_pairs();
+        assert_eq!(pairs.len(), 7);
+        assert!(pairs[1].b.is_some());
+        assert!(pairs[1].y.is_none());
+        assert!(pairs[1].mass_error.is_none());
+        assert_eq!(pairs[2].bond, 2);
+        assert!(pairs[2].b.is_some());
+        assert!(pairs[2].y.is_some());
+        assert!(pairs[2].mass_error.unwrap().value.abs() < 1e-6);
+        assert!(pairs[0].b.is_none() && pairs[0].y.is_none());
+    }
+
+    #[test]
+    fn backbone_coverage() {
+        use crate::spectrum::RawPeak;
+
+        let model = Model::none().only_b_y();
+        let peptide = CompoundPeptidoform::pro_forma("PEPTK", None).unwrap();
+        let charge = system::usize::Charge::new::<system::e>(1);
+        let fragments = peptide.generate_theoretical_fragments(charge, &model);
+        let peak = |f: &Fragment| RawPeak {
+            mz: f.mz(MassMode::Monoisotopic),
+            intensity: 1.0.into(),
+        };
+
+        let mut spectrum = RawSpectrum::default();
+        spectrum.extend(fragments.iter().map(peak));
+        let full = spectrum.annotate(peptide.clone(), &fragments, &model);
+        assert_eq!(full.backbone_coverage(), vec![(true, true); 4]);
+
+        let mut spectrum = RawSpectrum::default();
+        spectrum.extend(
+            fragments
+                .iter()
+                .filter(|f| f.ion.to_string() == "b2" || f.ion.to_string() == "y1")
+                .map(peak),
+        );
+        let partial = spectrum.annotate(peptide, &fragments, &model);
+        assert_eq!(
+            partial.backbone_coverage(),
+            vec![(false, false), (true, false), (false, false), (false, true)]
+        );
+    }
+
+    #[test]
+    fn localize() {
+        let model = Model::cid_hcd();
+        let charge = system::usize::Charge::new::<system::e>(1);
+        let ambiguous = LinearPeptide::pro_forma("AS[Phospho#g1]AAAAT[#g1]AAAK", None)
+            .unwrap()
+            .into_linear()
+            .unwrap();
+        let localised = LinearPeptide::pro_forma("ASAAAAT[Phospho]AAAK", None)
+            .unwrap()
+            .into_linear()
+            .unwrap();
+        let mut spectrum = RawSpectrum::default();
+        spectrum.extend(
+            localised
+                .generate_theoretical_fragments(charge, &model)
+                .iter()
+                .filter(|f| f.neutral_loss.is_none() && f.isotope == 0)
+                .map(|f| spectrum::RawPeak {
+                    mz: f.mz(MassMode::Monoisotopic),
+                    intensity: 1.0.into(),
+                }),
+        );
+        let peptide = CompoundPeptidoform::from(ambiguous.clone());
+        let fragments = peptide.generate_theoretical_fragments(charge, &model);
+        let annotated = spectrum.annotate(peptide, &fragments, &model);
+        let localisation = annotated.localize(&ambiguous, &model);
+        assert_eq!(localisation.len(), 2);
+        assert_eq!(localisation[0].0, 1);
+        assert_eq!(localisation[1].0, 6);
+        assert!(localisation[1].1 > 0.99, "{localisation:?}");
+        assert!((localisation[0].1 + localisation[1].1 - 1.0).abs() < 1e-9);
+
+        // Without any informative peaks both locations are equally likely
+        let mut uninformative = RawSpectrum::default();
+        uninformative.extend([spectrum::RawPeak {
+            mz: mz(1000.0),
+            intensity: 1.0.into(),
+        }]);
+        let empty = uninformative.annotate(
+            CompoundPeptidoform::from(ambiguous.clone()),
+            &fragments,
+            &model,
+        );
+        let localisation = empty.localize(&ambiguous, &model);
+        assert!((localisation[0].1 - 0.5).abs() < 1e-9);
+        assert!((localisation[1].1 - 0.5).abs() < 1e-9);
+    }
+}
@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     system::{MassOverCharge, Ratio},
-    AnnotatedSpectrum, Fragment, MassMode, Model, WithinTolerance,
+    AnnotatedSpectrum, Fragment, Model, WithinTolerance,
 };
 
 impl AnnotatedSpectrum {
@@ -17,15 +17,10 @@ impl AnnotatedSpectrum {
     /// `-25..=25 + π`. The returned result give insight in the average number of matches and
     /// standard deviation. The `π` offset is needed to guarantee non integer offsets preventing
     /// spurious matches from 1 Da isotopes.
-    pub fn fdr(
-        &self,
-        fragments: &[Fragment],
-        model: &Model,
-        mass_mode: MassMode,
-    ) -> (Fdr, Vec<Vec<Fdr>>) {
+    pub fn fdr(&self, fragments: &[Fragment], model: &Model) -> (Fdr, Vec<Vec<Fdr>>) {
         let mzs = fragments
             .iter()
-            .map(|f| (f.mz(mass_mode), f.peptidoform_index, f.peptide_index))
+            .map(|f| (f.mz(model.mass_mode), f.peptidoform_index, f.peptide_index))
             .filter(|(mz, _, _)| model.mz_range.contains(mz))
             .collect_vec();
 
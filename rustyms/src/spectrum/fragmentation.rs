@@ -1,4 +1,10 @@
-use crate::{system::MassOverCharge, CompoundPeptidoform, Fragment, MassMode, Model};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::{
+    system::{usize::Charge, MassOverCharge},
+    CompoundPeptidoform, Fragment, Model,
+};
 
 use super::AnnotatedSpectrum;
 
@@ -21,30 +27,347 @@ pub trait AnnotatableSpectrum {
     fn search(&self, query: MassOverCharge, tolerance: Self::Tolerance) -> Option<usize>;
 
     /// Annotate this spectrum with the given peptidoform and given fragments see
-    /// [`crate::CompoundPeptidoform::generate_theoretical_fragments`].
+    /// [`crate::CompoundPeptidoform::generate_theoretical_fragments`]. The m/z of the fragments is
+    /// determined with the mass mode of the model.
     fn annotate(
         &self,
         peptide: CompoundPeptidoform,
         theoretical_fragments: &[Fragment],
         model: &Model,
-        mode: MassMode,
     ) -> AnnotatedSpectrum {
         let tolerance = model.tolerance.into();
         let mut annotated = Self::empty_annotated(self, peptide);
 
         for fragment in theoretical_fragments {
             // Determine fragment mz and see if it is within the model range.
-            let mz = fragment.mz(mode);
+            let mz = fragment.mz(model.mass_mode);
             if !model.mz_range.contains(&mz) {
                 continue;
             }
 
             // Get the index of the element closest to this value
             if let Some(index) = Self::search(self, mz, tolerance) {
-                annotated.spectrum[index].annotation.push(fragment.clone());
+                annotated.add_annotation(index, mz, fragment, model);
+            }
+        }
+
+        annotated
+    }
+
+    /// Annotate this spectrum with all peptidoforms in the given compound peptidoform, generating
+    /// the theoretical fragments with the given model and maximal charge. Every annotation keeps
+    /// the index of the peptidoform (and peptide) it originates from, see
+    /// [`Fragment::peptidoform_index`], so peaks in a chimeric spectrum can be attributed to each
+    /// of the peptidoforms, see [`AnnotatedSpectrum::scores`] for the scores per peptidoform.
+    fn annotate_compound(
+        &self,
+        compound: &CompoundPeptidoform,
+        model: &Model,
+        max_charge: Charge,
+    ) -> AnnotatedSpectrum {
+        let fragments = sorted_fragments(compound, model, max_charge);
+        self.annotate_sorted(compound.clone(), &fragments, model)
+    }
+
+    /// Annotate many spectra with the same peptidoform. The theoretical fragments are only generated
+    /// once (see [`crate::CompoundPeptidoform::generate_theoretical_fragments`]) and sorted on m/z,
+    /// after which each spectrum is annotated with these fragments. The annotated spectra are
+    /// returned in the same order as the given spectra.
+    fn annotate_many(
+        spectra: &[Self],
+        peptide: &CompoundPeptidoform,
+        model: &Model,
+        max_charge: Charge,
+    ) -> Vec<AnnotatedSpectrum>
+    where
+        Self: Sized,
+    {
+        let fragments = sorted_fragments(peptide, model, max_charge);
+        spectra
+            .iter()
+            .map(|spectrum| spectrum.annotate_sorted(peptide.clone(), &fragments, model))
+            .collect()
+    }
+
+    /// Annotate many spectra with the same peptidoform in parallel, see [`Self::annotate_many`].
+    ///
+    /// Only available if the feature `rayon` is turned on.
+    #[cfg(feature = "rayon")]
+    fn par_annotate_many(
+        spectra: &[Self],
+        peptide: &CompoundPeptidoform,
+        model: &Model,
+        max_charge: Charge,
+    ) -> Vec<AnnotatedSpectrum>
+    where
+        Self: Sized + Sync,
+    {
+        let fragments = sorted_fragments(peptide, model, max_charge);
+        spectra
+            .par_iter()
+            .map(|spectrum| spectrum.annotate_sorted(peptide.clone(), &fragments, model))
+            .collect()
+    }
+
+    /// Annotate this spectrum with the given fragments that are already filtered on the model m/z
+    /// range and sorted on m/z, see [`Self::annotate_many`].
+    fn annotate_sorted(
+        &self,
+        peptide: CompoundPeptidoform,
+        fragments: &[(MassOverCharge, Fragment)],
+        model: &Model,
+    ) -> AnnotatedSpectrum {
+        let tolerance = model.tolerance.into();
+        let mut annotated = Self::empty_annotated(self, peptide);
+
+        for (mz, fragment) in fragments {
+            if let Some(index) = Self::search(self, *mz, tolerance) {
+                annotated.add_annotation(index, *mz, fragment, model);
             }
         }
 
         annotated
     }
 }
+
+/// Generate the theoretical fragments for the given peptidoform, and sort them on m/z. Any fragment
+/// outside of the m/z range of the model is removed.
+fn sorted_fragments(
+    peptide: &CompoundPeptidoform,
+    model: &Model,
+    max_charge: Charge,
+) -> Vec<(MassOverCharge, Fragment)> {
+    let mut fragments = peptide
+        .generate_theoretical_fragments(max_charge, model)
+        .into_iter()
+        .map(|fragment| (fragment.mz(model.mass_mode), fragment))
+        .filter(|(mz, _)| model.mz_range.contains(mz))
+        .collect::<Vec<_>>();
+    fragments.sort_unstable_by(|a, b| a.0.value.total_cmp(&b.0.value));
+    fragments
+}
+
+#[cfg(test)]
+#[allow(clippy::missing_panics_doc)]
+mod tests {
+    use super::AnnotatableSpectrum;
+    use crate::{
+        fragment, model, rawfile, system, CompoundPeptidoform, LinearPeptide, MassMode, Model,
+        NeutralLoss, RawSpectrum, Tolerance,
+    };
+
+    #[test]
+    fn annotate_many() {
+        use crate::spectrum::PeakSpectrum;
+
+        let model = Model::all();
+        let spectra = rawfile::mgf::open("data/example.mgf").unwrap();
+        let peptide = CompoundPeptidoform::pro_forma("WFWF", None).unwrap();
+        let charge = system::usize::Charge::new::<system::e>(1);
+        let fragments = peptide.generate_theoretical_fragments(charge, &model);
+        let annotated = RawSpectrum::annotate_many(&spectra, &peptide, &model, charge);
+        assert_eq!(annotated.len(), spectra.len());
+        for (spectrum, annotated) in spectra.iter().zip(&annotated) {
+            let single = spectrum.annotate(peptide.clone(), &fragments, &model);
+            assert_eq!(annotated.spectrum().len(), single.spectrum().len());
+            for (a, b) in annotated.spectrum().zip(single.spectrum()) {
+                assert_eq!(a.annotation.len(), b.annotation.len());
+            }
+        }
+    }
+
+    #[test]
+    fn negative_mode() {
+        use crate::spectrum::{PeakSpectrum, RawPeak};
+
+        let model = Model::all();
+        let peptide = CompoundPeptidoform::pro_forma("EMEVEESPEK/-2[2I-]", None).unwrap();
+        let fragments = peptide
+            .generate_theoretical_fragments(system::usize::Charge::new::<system::e>(2), &model);
+        assert!(!fragments.is_empty());
+        assert!(fragments
+            .iter()
+            .all(|f| f.mz(MassMode::Monoisotopic).value > 0.0));
+        let neutral = LinearPeptide::pro_forma("EMEVEESPEK", None)
+            .unwrap()
+            .into_linear()
+            .unwrap()
+            .formulas()[0]
+            .clone();
+        let expected = (neutral.clone() + molecular_formula!(I 2 Electron 2)).monoisotopic_mass()
+            / system::f64::Charge::new::<system::e>(2.0);
+        let precursor = fragments
+            .iter()
+            .find(|f| f.ion == fragment::FragmentType::precursor && f.charge.value == 2)
+            .unwrap();
+        assert!(
+            (precursor.mz(MassMode::Monoisotopic).value - expected.value).abs() < 1e-6,
+            "{} != {}",
+            precursor.mz(MassMode::Monoisotopic).value,
+            expected.value
+        );
+        assert!(
+            (precursor.neutral_mass(MassMode::Monoisotopic).value
+                - neutral.monoisotopic_mass().value)
+                .abs()
+                < 1e-6
+        );
+        // Singly charged fragments carry a single iodide
+        assert!(fragments
+            .iter()
+            .any(|f| f.charge.value == 1 && f.formula.charge().value == -1));
+
+        let mut spectrum = RawSpectrum::default();
+        spectrum.extend([RawPeak {
+            mz: precursor.mz(MassMode::Monoisotopic),
+            intensity: 1.0.into(),
+        }]);
+        let annotated = spectrum.annotate(peptide, &fragments, &model);
+        assert!(annotated
+            .spectrum()
+            .next()
+            .unwrap()
+            .annotation
+            .iter()
+            .any(|f| f.ion == fragment::FragmentType::precursor));
+    }
+
+    #[test]
+    fn multiple_annotations() {
+        use crate::spectrum::{AnnotatableSpectrum, PeakSpectrum, RawPeak};
+
+        let model = Model::none()
+            .b(model::PrimaryIonSeries::default())
+            .tolerance(Tolerance::new_absolute(system::helpers::mz(0.1)));
+        let peptide = CompoundPeptidoform::pro_forma("PEPTIDE", None).unwrap();
+        let fragments = peptide
+            .generate_theoretical_fragments(system::usize::Charge::new::<system::e>(1), &model);
+        let b2 = fragments
+            .iter()
+            .find(|f| f.ion.to_string() == "b2")
+            .unwrap()
+            .mz(MassMode::Monoisotopic);
+        let mut spectrum = RawSpectrum::default();
+        spectrum.extend([-0.05, 0.0, 0.04, 0.5].map(|shift| RawPeak {
+            mz: b2 + system::helpers::mz(shift),
+            intensity: 1.0.into(),
+        }));
+        let annotated_peaks = |model: &Model| {
+            spectrum
+                .annotate(peptide.clone(), &fragments, model)
+                .spectrum()
+                .map(|peak| peak.annotation.len())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(annotated_peaks(&model), [0, 1, 0, 0]);
+        let all = model.allow_multiple_annotations(true);
+        assert_eq!(annotated_peaks(&all), [1, 1, 1, 0]);
+        let many = RawSpectrum::annotate_many(
+            &[spectrum.clone()],
+            &peptide,
+            &all,
+            system::usize::Charge::new::<system::e>(1),
+        );
+        assert_eq!(
+            many[0]
+                .spectrum()
+                .map(|peak| peak.annotation.len())
+                .collect::<Vec<_>>(),
+            [1, 1, 1, 0]
+        );
+    }
+
+    #[test]
+    fn precursor_neutral_loss() {
+        use crate::spectrum::{PeakSpectrum, RawPeak};
+
+        let model = Model::all();
+        let peptide = CompoundPeptidoform::pro_forma("EMEVEES[Phospho]PEK", None).unwrap();
+        let charge = system::usize::Charge::new::<system::e>(2);
+        let fragments = peptide.generate_theoretical_fragments(charge, &model);
+        let phosphoric_acid = NeutralLoss::Loss(molecular_formula!(H 3 P 1 O 4));
+        let precursor = fragments
+            .iter()
+            .find(|f| {
+                f.ion == fragment::FragmentType::precursor
+                    && f.charge.value == 2
+                    && f.neutral_loss.is_none()
+            })
+            .unwrap();
+        let loss = fragments
+            .iter()
+            .find(|f| {
+                f.ion == fragment::FragmentType::precursor
+                    && f.charge.value == 2
+                    && f.neutral_loss.as_ref() == Some(&phosphoric_acid)
+            })
+            .unwrap();
+        assert_eq!(precursor.label(), "[M+2H]2+");
+        assert_eq!(
+            loss.label(),
+            format!("[M+2H-{}]2+", molecular_formula!(H 3 P 1 O 4))
+        );
+        // The loss of 98 Da is seen at half the m/z difference as it is doubly charged
+        assert!(
+            (precursor.mz(MassMode::Monoisotopic) - loss.mz(MassMode::Monoisotopic))
+                .value
+                .mul_add(2.0, -97.976_896)
+                .abs()
+                < 1e-4
+        );
+
+        let mut spectrum = RawSpectrum::default();
+        spectrum.extend([loss, precursor].map(|f| RawPeak {
+            mz: f.mz(MassMode::Monoisotopic),
+            intensity: 1.0.into(),
+        }));
+        let annotated = spectrum.annotate(peptide, &fragments, &model);
+        let peaks = annotated.spectrum().collect::<Vec<_>>();
+        assert!(peaks[0]
+            .annotation
+            .iter()
+            .any(|f| f.ion == fragment::FragmentType::precursor
+                && f.neutral_loss.as_ref() == Some(&phosphoric_acid)));
+        assert!(peaks[1]
+            .annotation
+            .iter()
+            .any(|f| f.ion == fragment::FragmentType::precursor && f.neutral_loss.is_none()));
+    }
+
+    #[test]
+    fn annotate_compound() {
+        use crate::spectrum::{PeakSpectrum, RawPeak};
+
+        let model = Model::none().only_b_y();
+        let chimeric = CompoundPeptidoform::pro_forma("PEPTK+WFWFR", None).unwrap();
+        let charge = system::usize::Charge::new::<system::e>(1);
+        let fragments = chimeric.generate_theoretical_fragments(charge, &model);
+        let find = |peptidoform_index: usize, label: &str| {
+            fragments
+                .iter()
+                .find(|f| f.peptidoform_index == peptidoform_index && f.ion.to_string() == label)
+                .unwrap()
+        };
+        let mut spectrum = RawSpectrum::default();
+        spectrum.extend([find(0, "b2"), find(1, "y3")].map(|f| RawPeak {
+            mz: f.mz(MassMode::Monoisotopic),
+            intensity: 1.0.into(),
+        }));
+        let annotated = spectrum.annotate_compound(&chimeric, &model, charge);
+        assert_eq!(annotated.peptide, chimeric);
+        let origins = annotated
+            .spectrum()
+            .map(|p| {
+                p.annotation
+                    .iter()
+                    .map(|f| f.peptidoform_index)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(origins.len(), 2);
+        assert!(origins.contains(&vec![0]));
+        assert!(origins.contains(&vec![1]));
+        let (_, individual) = annotated.scores(&fragments, &model);
+        assert_eq!(individual.len(), 2);
+    }
+}
@@ -7,12 +7,13 @@ use ordered_float::OrderedFloat;
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    fragment::IntensityModel,
     spectrum::{AnnotatableSpectrum, AnnotatedPeak, PeakSpectrum},
     system::{
         f64::{Mass, MassOverCharge, Ratio, Time},
         usize::Charge,
     },
-    AnnotatedSpectrum, CompoundPeptidoform, Tolerance, WithinTolerance,
+    AnnotatedSpectrum, CompoundPeptidoform, Fragment, MassMode, Tolerance, WithinTolerance,
 };
 
 /// A raw spectrum (meaning not annotated yet)
@@ -56,9 +57,7 @@ pub struct RawSpectrum {
 
 impl RawSpectrum {
     /// Filter the spectrum to retain all with an intensity above `filter_threshold` times the maximal intensity.
-    ///
-    /// # Panics
-    /// It panics if any peaks has an intensity that is NaN.
+    /// Peaks with an intensity that is NaN are always removed.
     pub fn relative_noise_filter(&mut self, filter_threshold: f64) {
         let max = self
             .spectrum
@@ -72,11 +71,33 @@ impl RawSpectrum {
     }
 
     /// Filter the spectrum to retain all with an intensity above `filter_threshold`.
+    /// Peaks with an intensity that is NaN are always removed.
     pub fn absolute_noise_filter(&mut self, filter_threshold: f64) {
         self.spectrum.retain(|p| *p.intensity >= filter_threshold);
         self.spectrum.shrink_to_fit();
     }
 
+    /// Filter the spectrum to retain all peaks with an intensity at or above the given percentile
+    /// (0 to 100) of all intensities, using the nearest rank method. So a percentile of 50 removes
+    /// all peaks below the median intensity, and a percentile of 0 only removes peaks with an
+    /// intensity that is NaN, which are always removed.
+    pub fn percentile_noise_filter(&mut self, percentile: f64) {
+        let intensities = self
+            .spectrum
+            .iter()
+            .map(|p| *p.intensity)
+            .filter(|i| !i.is_nan())
+            .sorted_unstable_by(f64::total_cmp)
+            .collect_vec();
+        let rank =
+            (percentile.clamp(0.0, 100.0) / 100.0 * intensities.len() as f64).ceil() as usize;
+        let threshold = intensities
+            .get(rank.saturating_sub(1))
+            .copied()
+            .unwrap_or(f64::INFINITY);
+        self.absolute_noise_filter(threshold);
+    }
+
     /// Filter a spectrum by dividing it in windows and within each window only retain the `top` number of peaks.
     #[allow(clippy::missing_panics_doc)] // Cannot panic as it checks with peek first
     pub fn top_x_filter(&mut self, window_size: f64, top: usize) {
@@ -111,6 +132,312 @@ impl RawSpectrum {
 
         self.spectrum = new_spectrum;
     }
+
+    /// Bin the spectrum in uniform m/z bins of `bin_width` wide, starting at the start of `range`.
+    /// The returned vector always contains `((range.1 - range.0) / bin_width).ceil()` bins, which
+    /// makes it suitable as a fixed length vector representation of the spectrum. The range is
+    /// half-open, so peaks with an m/z below `range.0` or at or above `range.1` are dropped. The
+    /// intensities of all peaks that fall in the same bin are combined with the given aggregation.
+    ///
+    /// # Panics
+    /// It panics if the bin width is not positive or the range is reversed.
+    pub fn bin(
+        &self,
+        bin_width: MassOverCharge,
+        range: (MassOverCharge, MassOverCharge),
+        mode: BinAgg,
+    ) -> Vec<f64> {
+        assert!(bin_width.value > 0.0, "The bin width has to be positive");
+        assert!(range.0 <= range.1, "The range cannot be reversed");
+        let number_of_bins = ((range.1 - range.0).value / bin_width.value).ceil() as usize;
+        let mut bins = vec![0.0; number_of_bins];
+        for peak in &self.spectrum {
+            if peak.mz < range.0 || peak.mz >= range.1 {
+                continue;
+            }
+            let index =
+                (((peak.mz - range.0).value / bin_width.value) as usize).min(number_of_bins - 1);
+            match mode {
+                BinAgg::Sum => bins[index] += *peak.intensity,
+                BinAgg::Max => bins[index] = bins[index].max(*peak.intensity),
+            }
+        }
+        bins
+    }
+
+    /// Create a consensus spectrum from multiple spectra, for example from replicate scans of
+    /// the same precursor. All peaks are clustered across the spectra, a peak joins a cluster if it
+    /// is within the tolerance of the intensity weighted average m/z of that cluster. Only clusters
+    /// with peaks from at least `min_fraction` of the spectra are retained. The m/z of the consensus
+    /// peak is the intensity weighted average m/z of the cluster (or the plain average if the
+    /// cluster has no intensity) and the intensity is the average intensity over all given
+    /// spectra. All metadata is taken from the first spectrum, except for the precursor mass which
+    /// is averaged over all spectra that have a precursor mass.
+    pub fn consensus(
+        spectra: &[Self],
+        tolerance: Tolerance<MassOverCharge>,
+        min_fraction: f64,
+    ) -> Self {
+        let Some(first) = spectra.first() else {
+            return Self::default();
+        };
+        let peaks = spectra
+            .iter()
+            .enumerate()
+            .flat_map(|(index, spectrum)| spectrum.spectrum.iter().map(move |p| (index, p)))
+            .sorted_unstable_by(|a, b| a.1.cmp(b.1));
+
+        // Clusters of (total intensity, intensity weighted m/z, the spectra with peaks in the cluster)
+        let mut clusters: Vec<(f64, MassOverCharge, Vec<usize>)> = Vec::new();
+        for (index, peak) in peaks {
+            match clusters.last_mut() {
+                Some((intensity, mz, members)) if tolerance.within(mz, &peak.mz) => {
+                    let total = *intensity + *peak.intensity;
+                    *mz = if total > 0.0 {
+                        (*mz * *intensity + peak.mz * *peak.intensity) / total
+                    } else {
+                        // Without any intensity fall back to the unweighted mean
+                        let count = members.len() as f64;
+                        (*mz * count + peak.mz) / (count + 1.0)
+                    };
+                    *intensity += *peak.intensity;
+                    members.push(index);
+                }
+                _ => clusters.push((*peak.intensity, peak.mz, vec![index])),
+            }
+        }
+
+        let mut masses = spectra.iter().filter_map(|s| s.mass).peekable();
+        let mass = masses.peek().is_some().then(|| {
+            let (sum, count) =
+                masses.fold((Mass::default(), 0.0), |acc, m| (acc.0 + m, acc.1 + 1.0));
+            sum / count
+        });
+
+        Self {
+            mass,
+            spectrum: clusters
+                .into_iter()
+                .filter(|(_, _, members)| {
+                    members.iter().unique().count() as f64 / spectra.len() as f64 >= min_fraction
+                })
+                .map(|(intensity, mz, _)| RawPeak {
+                    mz,
+                    intensity: OrderedFloat(intensity / spectra.len() as f64),
+                })
+                .collect(),
+            ..first.clone()
+        }
+    }
+
+    /// The spectral entropy of this spectrum, the Shannon entropy (using the natural logarithm) of
+    /// the peak intensities normalised to sum to one. An empty spectrum, a spectrum with a single
+    /// peak, or a spectrum without any intensity has an entropy of zero.
+    pub fn spectral_entropy(&self) -> f64 {
+        entropy(self.spectrum.iter().map(|p| *p.intensity))
+    }
+
+    /// The entropy similarity between this spectrum and another spectrum, as defined by Li et al.
+    /// (Nature Methods 2021, doi:10.1038/s41592-021-01331-z). Spectra with an entropy below 3 are
+    /// first reweighted by raising the intensities to the power `0.25 + 0.25 * entropy`. Then peaks
+    /// are matched within the given tolerance and the similarity is calculated as
+    /// `1 - (2 * S_AB - S_A - S_B) / ln(4)` with `S_AB` the entropy of the merged spectrum. The
+    /// result ranges from 0 (nothing in common) to 1 (identical). If either spectrum is empty or
+    /// has no intensity the similarity is 0.
+    pub fn entropy_similarity(&self, other: &Self, tolerance: Tolerance<MassOverCharge>) -> f64 {
+        let a = self.entropy_weighted_intensities();
+        let b = other.entropy_weighted_intensities();
+        if a.is_empty() || b.is_empty() {
+            return 0.0;
+        }
+
+        let mut merged = a.clone();
+        let mut matched = vec![false; a.len()];
+        for (peak, intensity) in other.spectrum.iter().zip(&b) {
+            match self.search(peak.mz, tolerance) {
+                Some(index) if !matched[index] => {
+                    matched[index] = true;
+                    merged[index] += intensity;
+                }
+                _ => merged.push(*intensity),
+            }
+        }
+
+        let entropy_a = entropy(a.into_iter());
+        let entropy_b = entropy(b.into_iter());
+        let entropy_merged = entropy(merged.into_iter());
+        (1.0 - (2.0f64.mul_add(entropy_merged, -entropy_a) - entropy_b) / 4.0f64.ln())
+            .clamp(0.0, 1.0)
+    }
+
+    /// Find all matches between the peaks in this spectrum and the given fragments. Returns the
+    /// peak index, the fragment index, and the ppm error for every peak that is within the
+    /// tolerance of a fragment. In contrast to [`AnnotatableSpectrum::annotate`] a fragment can
+    /// match multiple peaks and no selection is made, so any resolution strategy can be applied to
+    /// these raw matches. The matches are sorted on fragment index and then on peak index.
+    pub fn match_fragments(
+        &self,
+        fragments: &[Fragment],
+        tolerance: Tolerance<MassOverCharge>,
+        mode: MassMode,
+    ) -> Vec<(usize, usize, Ratio)> {
+        let mut matches = Vec::new();
+        for (fragment_index, fragment) in fragments.iter().enumerate() {
+            let mz = fragment.mz(mode);
+            let (low, high) = tolerance.bounds(mz);
+            let start = self.spectrum.partition_point(|p| p.mz < low);
+            matches.extend(
+                self.spectrum[start..]
+                    .iter()
+                    .enumerate()
+                    .take_while(|(_, p)| p.mz <= high)
+                    .filter(|(_, p)| tolerance.within(&mz, &p.mz))
+                    .map(|(offset, p)| (start + offset, fragment_index, p.ppm(mz))),
+            );
+        }
+        matches
+    }
+
+    /// Compare the peaks in this spectrum explained by two sets of fragments, for example the
+    /// fragments of two candidate peptides for this spectrum. A peak is explained by a set of
+    /// fragments if any fragment is within the tolerance, see [`Self::match_fragments`].
+    pub fn compare_annotations(
+        &self,
+        a: &[Fragment],
+        b: &[Fragment],
+        tolerance: Tolerance<MassOverCharge>,
+        mode: MassMode,
+    ) -> AnnotationDiff {
+        let explained = |fragments| {
+            self.match_fragments(fragments, tolerance, mode)
+                .into_iter()
+                .map(|(peak, _, _)| peak)
+                .sorted_unstable()
+                .dedup()
+                .collect_vec()
+        };
+        let (a, b) = (explained(a), explained(b));
+        let intensity = |peaks: &[usize]| peaks.iter().map(|i| *self.spectrum[*i].intensity).sum();
+
+        let (both, only_a) = a.iter().partition(|i| b.binary_search(i).is_ok());
+        let only_b = b
+            .iter()
+            .filter(|i| a.binary_search(i).is_err())
+            .copied()
+            .collect();
+        AnnotationDiff {
+            intensity_a: intensity(&a),
+            intensity_b: intensity(&b),
+            only_a,
+            only_b,
+            both,
+        }
+    }
+
+    /// The intensities after entropy based weighting, normalised to sum to one. Returns an empty
+    /// vector if the spectrum has no intensity.
+    fn entropy_weighted_intensities(&self) -> Vec<f64> {
+        let entropy = self.spectral_entropy();
+        let weight = if entropy < 3.0 {
+            0.25f64.mul_add(entropy, 0.25)
+        } else {
+            1.0
+        };
+        let intensities = self
+            .spectrum
+            .iter()
+            .map(|p| p.intensity.powf(weight))
+            .collect_vec();
+        let total: f64 = intensities.iter().sum();
+        if total > 0.0 {
+            intensities.into_iter().map(|i| i / total).collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Create a synthetic spectrum from the given theoretical fragments, for example to test the
+    /// annotation on a spectrum with a known composition. The intensity of each fragment is given
+    /// by the `intensity_model` based on the fragment and its peptide from the compound
+    /// peptidoform the fragments were generated from, use [`crate::fragment::UniformIntensity`]
+    /// for uniform intensities. Fragments with the exact same monoisotopic m/z are combined into a
+    /// single peak with the summed intensity.
+    ///
+    /// # Panics
+    /// If a fragment does not refer to a peptide in the given compound peptidoform.
+    pub fn from_fragments(
+        fragments: &[Fragment],
+        peptide: &CompoundPeptidoform,
+        intensity_model: &impl IntensityModel,
+    ) -> Self {
+        let mut spectrum = Self::default();
+        spectrum.extend(
+            fragments
+                .iter()
+                .map(|fragment| {
+                    (
+                        OrderedFloat(fragment.mz(MassMode::Monoisotopic).value),
+                        intensity_model.intensity(
+                            fragment,
+                            &peptide.peptidoforms()[fragment.peptidoform_index].peptides()
+                                [fragment.peptide_index],
+                        ),
+                    )
+                })
+                .into_grouping_map()
+                .sum()
+                .into_iter()
+                .map(|(mz, intensity)| RawPeak {
+                    mz: MassOverCharge::new::<crate::system::mz>(mz.0),
+                    intensity: OrderedFloat(intensity),
+                }),
+        );
+        spectrum
+    }
+}
+
+/// The Shannon entropy of the given intensities after normalising them to sum to one.
+fn entropy(intensities: impl Iterator<Item = f64> + Clone) -> f64 {
+    let total: f64 = intensities.clone().sum();
+    if total <= 0.0 {
+        return 0.0;
+    }
+    -intensities
+        .filter(|i| *i > 0.0)
+        .map(|i| {
+            let p = i / total;
+            p * p.ln()
+        })
+        .sum::<f64>()
+}
+
+/// The way the intensities of peaks that fall in the same bin are combined, see [`RawSpectrum::bin`].
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum BinAgg {
+    /// Sum the intensities of all peaks in the bin
+    #[default]
+    Sum,
+    /// Take the maximal intensity of all peaks in the bin
+    Max,
+}
+
+/// The difference between two annotations of the same spectrum, see
+/// [`RawSpectrum::compare_annotations`]. All peaks are given as indices into the spectrum, sorted
+/// on m/z.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct AnnotationDiff {
+    /// The peaks only explained by the first set of fragments
+    pub only_a: Vec<usize>,
+    /// The peaks only explained by the second set of fragments
+    pub only_b: Vec<usize>,
+    /// The peaks explained by both sets of fragments
+    pub both: Vec<usize>,
+    /// The total intensity of all peaks explained by the first set of fragments
+    pub intensity_a: f64,
+    /// The total intensity of all peaks explained by the second set of fragments
+    pub intensity_b: f64,
 }
 
 impl AnnotatableSpectrum for RawSpectrum {
@@ -258,4 +585,349 @@ impl RawPeak {
     pub fn ppm(&self, mz: MassOverCharge) -> Ratio {
         self.mz.ppm(mz)
     }
+
+    /// Determine the signed ppm error for the given fragment, positive if the peak is observed at a
+    /// higher m/z than the fragment
+    pub fn signed_ppm_error(&self, mz: MassOverCharge) -> Ratio {
+        self.mz.signed_ppm(mz)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::missing_panics_doc)]
+mod tests {
+    use super::*;
+    use crate::system::f64::mz;
+
+    fn spectrum(peaks: &[(f64, f64)]) -> RawSpectrum {
+        let mut spectrum = RawSpectrum::default();
+        spectrum.extend(peaks.iter().map(|(m, i)| RawPeak {
+            mz: MassOverCharge::new::<mz>(*m),
+            intensity: OrderedFloat(*i),
+        }));
+        spectrum
+    }
+
+    #[test]
+    fn bin() {
+        let spectrum = spectrum(&[
+            (99.0, 100.0),
+            (100.0, 1.0),
+            (100.4, 2.0),
+            (101.5, 4.0),
+            (102.9, 8.0),
+            (103.0, 16.0),
+        ]);
+        let range = (
+            MassOverCharge::new::<mz>(100.0),
+            MassOverCharge::new::<mz>(103.0),
+        );
+        let width = MassOverCharge::new::<mz>(1.0);
+        assert_eq!(spectrum.bin(width, range, BinAgg::Sum), vec![3.0, 4.0, 8.0]);
+        assert_eq!(spectrum.bin(width, range, BinAgg::Max), vec![2.0, 4.0, 8.0]);
+        assert_eq!(
+            spectrum
+                .bin(MassOverCharge::new::<mz>(0.7), range, BinAgg::Sum)
+                .len(),
+            5
+        );
+        assert!(RawSpectrum::default()
+            .bin(width, range, BinAgg::Sum)
+            .iter()
+            .all(|i| *i == 0.0));
+    }
+
+    #[test]
+    fn consensus() {
+        let mut a = spectrum(&[(100.0, 1.0), (200.0, 2.0), (300.0, 5.0)]);
+        a.mass = Some(Mass::new::<crate::system::dalton>(1000.0));
+        a.title = "a".to_string();
+        let mut b = spectrum(&[(100.0001, 3.0), (250.0, 2.0), (300.0, 5.0)]);
+        b.mass = Some(Mass::new::<crate::system::dalton>(1002.0));
+        let c = spectrum(&[(100.0, 2.0), (300.0003, 5.0)]);
+        let consensus = RawSpectrum::consensus(&[a, b, c], Tolerance::new_ppm(10.0), 0.5);
+        assert_eq!(consensus.title, "a");
+        assert_eq!(
+            consensus.mass,
+            Some(Mass::new::<crate::system::dalton>(1001.0))
+        );
+        let peaks = consensus.spectrum().collect_vec();
+        assert_eq!(peaks.len(), 2);
+        assert!((peaks[0].mz.value - 100.000_05).abs() < 1e-9);
+        assert!((*peaks[0].intensity - 2.0).abs() < 1e-9);
+        assert!((peaks[1].mz.value - 300.0001).abs() < 1e-9);
+        assert!((*peaks[1].intensity - 5.0).abs() < 1e-9);
+        assert_eq!(
+            RawSpectrum::consensus(&[], Tolerance::new_ppm(10.0), 0.5),
+            RawSpectrum::default()
+        );
+        let zero = RawSpectrum::consensus(
+            &[spectrum(&[(100.0, 0.0)]), spectrum(&[(100.0002, 0.0)])],
+            Tolerance::new_ppm(10.0),
+            0.5,
+        );
+        let peaks = zero.spectrum().collect_vec();
+        assert_eq!(peaks.len(), 1);
+        assert!((peaks[0].mz.value - 100.0001).abs() < 1e-9);
+    }
+
+    #[test]
+    fn from_fragments() {
+        use crate::{
+            fragment::{fragments_to_peak_list, UniformIntensity},
+            spectrum::Score,
+            AnnotatableSpectrum, CompoundPeptidoform, Model,
+        };
+
+        let model = Model::all();
+        let peptide = CompoundPeptidoform::pro_forma("PEPTIDEK", None).unwrap();
+        let fragments = peptide.generate_theoretical_fragments(
+            crate::system::usize::Charge::new::<crate::system::e>(2),
+            &model,
+        );
+        let peaks = fragments_to_peak_list(&fragments, &peptide, &UniformIntensity);
+        assert_eq!(peaks.len(), fragments.len());
+        assert!(peaks.windows(2).all(|w| w[0].0 <= w[1].0));
+        assert!(peaks.iter().any(|(_, _, label)| label == "b3"));
+
+        let spectrum =
+            RawSpectrum::from_fragments(&fragments, &peptide, &|f: &Fragment,
+                                                                _: &crate::LinearPeptide<
+                crate::Linked,
+            >| {
+                if f.neutral_loss.is_some() {
+                    0.5
+                } else {
+                    1.0
+                }
+            });
+        assert!(spectrum.spectrum.len() <= fragments.len());
+        assert!(spectrum.spectrum.windows(2).all(|w| w[0].mz < w[1].mz));
+        assert!(
+            (spectrum.spectrum.iter().map(|p| *p.intensity).sum::<f64>()
+                - fragments
+                    .iter()
+                    .map(|f| if f.neutral_loss.is_some() { 0.5 } else { 1.0 })
+                    .sum::<f64>())
+            .abs()
+                < 1e-6
+        );
+
+        let annotated = spectrum.annotate(peptide, &fragments, &model);
+        let (combined, individual) = annotated.scores(&fragments, &model);
+        let Score::Position {
+            theoretical_positions,
+            ..
+        } = individual[0][0].score
+        else {
+            panic!("Expected a positional score for a single peptide")
+        };
+        assert_eq!(theoretical_positions.found, theoretical_positions.total);
+        let Score::UniqueFormulas {
+            peaks, intensity, ..
+        } = combined.score
+        else {
+            panic!("Expected a score over unique formulas for the combined peptides")
+        };
+        let in_range = spectrum
+            .spectrum
+            .iter()
+            .filter(|p| model.mz_range.contains(&p.mz))
+            .count();
+        assert_eq!(peaks.found as usize, in_range);
+        assert_eq!(peaks.found, peaks.total);
+        assert!((intensity.fraction() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn spectral_entropy() {
+        assert!(RawSpectrum::default().spectral_entropy().abs() < f64::EPSILON);
+        assert!(spectrum(&[(100.0, 5.0)]).spectral_entropy().abs() < f64::EPSILON);
+        assert!(spectrum(&[(100.0, 0.0)]).spectral_entropy().abs() < f64::EPSILON);
+        let even = spectrum(&[(100.0, 2.0), (200.0, 2.0), (300.0, 2.0), (400.0, 2.0)]);
+        assert!((even.spectral_entropy() - 4.0f64.ln()).abs() < 1e-12);
+        let skewed = spectrum(&[(100.0, 1.0), (200.0, 1.0), (300.0, 1.0), (400.0, 97.0)]);
+        assert!(skewed.spectral_entropy() < even.spectral_entropy());
+    }
+
+    #[test]
+    fn entropy_similarity() {
+        let tolerance = Tolerance::new_ppm(10.0);
+        let a = spectrum(&[(100.0, 1.0), (200.0, 2.0), (300.0, 5.0)]);
+        let shifted = spectrum(&[(100.0005, 1.0), (200.001, 2.0), (300.0015, 5.0)]);
+        let partial = spectrum(&[(100.0, 1.0), (250.0, 2.0), (300.0, 5.0)]);
+        let disjoint = spectrum(&[(150.0, 1.0), (250.0, 2.0)]);
+        assert!((a.entropy_similarity(&a, tolerance) - 1.0).abs() < 1e-12);
+        assert!((a.entropy_similarity(&shifted, tolerance) - 1.0).abs() < 1e-12);
+        assert!(a.entropy_similarity(&disjoint, tolerance).abs() < 1e-12);
+        let similarity = a.entropy_similarity(&partial, tolerance);
+        assert!(similarity > 0.0 && similarity < 1.0);
+        assert!((similarity - partial.entropy_similarity(&a, tolerance)).abs() < 1e-12);
+
+        let single = spectrum(&[(100.0, 3.0)]);
+        assert!((single.entropy_similarity(&single, tolerance) - 1.0).abs() < 1e-12);
+        assert!(single.entropy_similarity(&disjoint, tolerance).abs() < 1e-12);
+        assert!(
+            RawSpectrum::default()
+                .entropy_similarity(&a, tolerance)
+                .abs()
+                < f64::EPSILON
+        );
+        assert!(
+            a.entropy_similarity(&RawSpectrum::default(), tolerance)
+                .abs()
+                < f64::EPSILON
+        );
+        assert!(
+            RawSpectrum::default()
+                .entropy_similarity(&RawSpectrum::default(), tolerance)
+                .abs()
+                < f64::EPSILON
+        );
+    }
+
+    #[test]
+    fn signed_ppm_error() {
+        let theoretical = MassOverCharge::new::<mz>(500.0);
+        let peak = |offset: f64| RawPeak {
+            mz: theoretical * (1.0 + offset * 1e-6),
+            intensity: OrderedFloat(1.0),
+        };
+        let ppm = |ratio: Ratio| ratio.get::<crate::system::ratio::ppm>();
+        let above = ppm(peak(5.0).signed_ppm_error(theoretical));
+        let below = ppm(peak(-5.0).signed_ppm_error(theoretical));
+        assert!(above > 0.0 && (above - 5.0).abs() < 0.01, "{above}");
+        assert!(below < 0.0 && (below + 5.0).abs() < 0.01, "{below}");
+        assert!((ppm(peak(5.0).ppm(theoretical)) - above).abs() < 1e-9);
+        assert!((ppm(peak(-5.0).ppm(theoretical)) + below).abs() < 1e-9);
+    }
+
+    #[test]
+    fn match_fragments() {
+        use crate::{CompoundPeptidoform, Model};
+
+        let fragments = CompoundPeptidoform::pro_forma("AG", None)
+            .unwrap()
+            .generate_theoretical_fragments(
+                crate::system::usize::Charge::new::<crate::system::e>(1),
+                &Model::none(),
+            );
+        let first = fragments[0].mz(MassMode::Monoisotopic).value;
+        let spectrum = spectrum(&[
+            (first, 1.0),
+            (first * (1.0 + 5e-6), 1.0),
+            (first + 1.0, 1.0),
+        ]);
+        let matches = spectrum.match_fragments(
+            &fragments[..1],
+            Tolerance::new_ppm(10.0),
+            MassMode::Monoisotopic,
+        );
+        assert_eq!(
+            matches.iter().map(|(p, f, _)| (*p, *f)).collect_vec(),
+            [(0, 0), (1, 0)]
+        );
+        assert!(matches[0].2.get::<crate::system::ratio::ppm>() < 1e-6);
+        assert!((matches[1].2.get::<crate::system::ratio::ppm>() - 5.0).abs() < 0.01);
+        assert!(spectrum
+            .match_fragments(
+                &fragments[..1],
+                Tolerance::new_ppm(1.0),
+                MassMode::Monoisotopic
+            )
+            .iter()
+            .all(|(p, _, _)| *p == 0));
+        assert!(RawSpectrum::default()
+            .match_fragments(&fragments, Tolerance::new_ppm(10.0), MassMode::Monoisotopic)
+            .is_empty());
+    }
+
+    #[test]
+    fn compare_annotations() {
+        use crate::{
+            fragment::{FragmentKind, UniformIntensity},
+            CompoundPeptidoform, Model,
+        };
+
+        let peptide = CompoundPeptidoform::pro_forma("PEPTIDEK", None).unwrap();
+        let fragments = |sequence: &str| {
+            CompoundPeptidoform::pro_forma(sequence, None)
+                .unwrap()
+                .generate_theoretical_fragments(
+                    crate::system::usize::Charge::new::<crate::system::e>(1),
+                    &Model::none().only_b_y(),
+                )
+        };
+        // The b ions are shared, the y ions and precursor are unique to each peptide
+        let a = fragments("PEPTIDEK");
+        let b = fragments("PEPTIDER");
+        // Both peptides have the same length, and the uniform model ignores the peptide anyway
+        let mut spectrum = RawSpectrum::from_fragments(
+            &[a.clone(), b.clone()].concat(),
+            &peptide,
+            &UniformIntensity,
+        );
+        spectrum.extend([RawPeak {
+            mz: MassOverCharge::new::<mz>(2000.0),
+            intensity: OrderedFloat(10.0),
+        }]);
+        let tolerance = Tolerance::new_ppm(10.0);
+
+        let diff = spectrum.compare_annotations(&a, &b, tolerance, MassMode::Monoisotopic);
+        let shared = a.iter().filter(|f| f.is_series(FragmentKind::b)).count();
+        assert_eq!(diff.both.len(), shared);
+        assert_eq!(diff.only_a.len(), a.len() - shared);
+        assert_eq!(diff.only_b.len(), b.len() - shared);
+        assert!(diff.only_a.iter().all(|i| !diff.only_b.contains(i)));
+        assert!(diff.only_a.windows(2).all(|w| w[0] < w[1]));
+        // Shared peaks have an intensity of 2, unique peaks of 1
+        let expected = 2.0 * diff.both.len() as f64;
+        assert!((diff.intensity_a - expected - diff.only_a.len() as f64).abs() < 1e-9);
+        assert!((diff.intensity_b - expected - diff.only_b.len() as f64).abs() < 1e-9);
+
+        let same = spectrum.compare_annotations(&a, &a, tolerance, MassMode::Monoisotopic);
+        assert!(same.only_a.is_empty() && same.only_b.is_empty());
+        assert!((same.intensity_a - same.intensity_b).abs() < f64::EPSILON);
+        assert_eq!(
+            spectrum.compare_annotations(&[], &[], tolerance, MassMode::Monoisotopic),
+            AnnotationDiff::default()
+        );
+    }
+
+    #[test]
+    fn noise_filters() {
+        let peaks = [
+            (100.0, 1.0),
+            (200.0, 2.0),
+            (300.0, 3.0),
+            (400.0, f64::NAN),
+            (500.0, 4.0),
+        ];
+        let filtered = |filter: &dyn Fn(&mut RawSpectrum)| {
+            let mut spectrum = spectrum(&peaks);
+            filter(&mut spectrum);
+            spectrum.spectrum().map(|p| p.mz.value).collect_vec()
+        };
+        assert_eq!(
+            filtered(&|s| s.relative_noise_filter(0.5)),
+            [200.0, 300.0, 500.0]
+        );
+        assert_eq!(filtered(&|s| s.absolute_noise_filter(3.0)), [300.0, 500.0]);
+        assert_eq!(
+            filtered(&|s| s.absolute_noise_filter(0.0)),
+            [100.0, 200.0, 300.0, 500.0]
+        );
+        assert_eq!(
+            filtered(&|s| s.percentile_noise_filter(50.0)),
+            [200.0, 300.0, 500.0]
+        );
+        assert_eq!(
+            filtered(&|s| s.percentile_noise_filter(0.0)),
+            [100.0, 200.0, 300.0, 500.0]
+        );
+        assert_eq!(filtered(&|s| s.percentile_noise_filter(100.0)), [500.0]);
+        let mut empty = RawSpectrum::default();
+        empty.percentile_noise_filter(50.0);
+        assert_eq!(empty.spectrum().count(), 0);
+    }
 }
@@ -6,22 +6,17 @@ use serde::{Deserialize, Serialize};
 use crate::{
     fragment::{Fragment, FragmentKind},
     peptide::UnAmbiguous,
-    AnnotatedSpectrum, LinearPeptide, MassMode, Model,
+    AnnotatedSpectrum, LinearPeptide, Model,
 };
 
 impl AnnotatedSpectrum {
     /// Get the spectrum scores for this annotated spectrum.
     /// The returned tuple has the scores for all peptides combined as first item
     /// and as second item a vector with for each peptide its individual scores.
-    pub fn scores(
-        &self,
-        fragments: &[Fragment],
-        model: &Model,
-        mass_mode: MassMode,
-    ) -> (Scores, Vec<Vec<Scores>>) {
+    pub fn scores(&self, fragments: &[Fragment], model: &Model) -> (Scores, Vec<Vec<Scores>>) {
         let fragments = fragments
             .iter()
-            .filter(|f| model.mz_range.contains(&f.mz(mass_mode)))
+            .filter(|f| model.mz_range.contains(&f.mz(model.mass_mode)))
             .collect_vec();
         let total_intensity: f64 = self.spectrum.iter().map(|p| *p.intensity).sum();
         let individual_peptides = self
@@ -87,6 +82,53 @@ impl AnnotatedSpectrum {
         )
     }
 
+    /// Get a single score for this annotated spectrum, for example to rank peptide spectrum
+    /// matches. See [`ScoreModel`] for the available scoring schemes. Only the annotations with
+    /// fragments of the kinds selected in the model are considered. An empty spectrum scores `0.0`.
+    pub fn score(&self, model: &ScoreModel) -> f64 {
+        let selected = |f: &Fragment| model.ions.is_empty() || model.ions.contains(&f.ion.kind());
+        match model.scheme {
+            ScoreScheme::MatchedIntensity => {
+                let total_intensity: f64 = self.spectrum.iter().map(|p| *p.intensity).sum();
+                if total_intensity == 0.0 {
+                    return 0.0;
+                }
+                self.spectrum
+                    .iter()
+                    .filter(|p| p.annotation.iter().any(selected))
+                    .map(|p| *p.intensity)
+                    .sum::<f64>()
+                    / total_intensity
+            }
+            ScoreScheme::Hyperscore => {
+                let matched = self
+                    .spectrum
+                    .iter()
+                    .filter(|p| p.annotation.iter().any(selected))
+                    .collect_vec();
+                let intensity: f64 = matched.iter().map(|p| *p.intensity).sum();
+                if intensity <= 0.0 {
+                    return 0.0;
+                }
+                // ln(N!) for the number of matched peaks for each fragment kind
+                let log_factorials: f64 = matched
+                    .iter()
+                    .flat_map(|p| {
+                        p.annotation
+                            .iter()
+                            .filter(|f| selected(f))
+                            .map(|f| f.ion.kind())
+                            .unique()
+                    })
+                    .counts()
+                    .values()
+                    .flat_map(|n| (2..=*n).map(|i| (i as f64).ln()))
+                    .sum();
+                log_factorials + intensity.ln()
+            }
+        }
+    }
+
     /// Get the base score of this spectrum
     /// (Fragments, peaks, intensity)
     fn filtered_base_score(
@@ -306,6 +348,55 @@ impl AnnotatedSpectrum {
     }
 }
 
+/// A model to calculate a single score for an annotated spectrum, see [`AnnotatedSpectrum::score`].
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub struct ScoreModel {
+    /// The scoring scheme
+    pub scheme: ScoreScheme,
+    /// The fragment kinds that are considered, if empty all fragment kinds are considered
+    pub ions: Vec<FragmentKind>,
+}
+
+impl ScoreModel {
+    /// The fraction of the total intensity that is annotated, using all fragment kinds
+    pub const fn matched_intensity() -> Self {
+        Self {
+            scheme: ScoreScheme::MatchedIntensity,
+            ions: Vec::new(),
+        }
+    }
+
+    /// The hyperscore using the b and y ions
+    pub fn hyperscore() -> Self {
+        Self {
+            scheme: ScoreScheme::Hyperscore,
+            ions: vec![FragmentKind::b, FragmentKind::y],
+        }
+    }
+
+    /// Replace the fragment kinds that are considered
+    #[must_use]
+    pub fn ions(self, ions: Vec<FragmentKind>) -> Self {
+        Self { ions, ..self }
+    }
+}
+
+impl Default for ScoreModel {
+    fn default() -> Self {
+        Self::matched_intensity()
+    }
+}
+
+/// The scoring schemes for [`ScoreModel`]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum ScoreScheme {
+    /// The fraction of the total intensity that is annotated, between 0 and 1
+    MatchedIntensity,
+    /// A hyperscore as used by X!Tandem: `ln(N_1! * N_2! * ... * I)` with `N_k` the number of
+    /// annotated peaks for each fragment kind and `I` the summed intensity of all annotated peaks
+    Hyperscore,
+}
+
 /// The scores for an annotated spectrum
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 #[non_exhaustive]
@@ -374,3 +465,54 @@ where
         f64::from(self.found) / f64::from(self.total)
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::missing_panics_doc)]
+mod tests {
+    use crate::{
+        fragment, system, AnnotatableSpectrum, CompoundPeptidoform, Fragment, Model, RawSpectrum,
+    };
+
+    #[test]
+    fn score_coverage() {
+        use crate::spectrum::{RawPeak, ScoreModel};
+
+        let model = Model::none().only_b_y();
+        let peptide = CompoundPeptidoform::pro_forma("PEPTIDEK", None).unwrap();
+        let charge = system::usize::Charge::new::<system::e>(1);
+        let fragments = peptide.generate_theoretical_fragments(charge, &model);
+        let noise = [(1_000.5, 5.0), (1_200.7, 5.0)].map(|(mz, intensity)| RawPeak {
+            mz: system::helpers::mz(mz),
+            intensity: intensity.into(),
+        });
+        let score = |fragments: &[Fragment]| {
+            let mut spectrum =
+                RawSpectrum::from_fragments(fragments, &peptide, &fragment::UniformIntensity);
+            spectrum.extend(noise.clone());
+            let annotated = spectrum.annotate(peptide.clone(), fragments, &model);
+            (
+                annotated.score(&ScoreModel::matched_intensity()),
+                annotated.score(&ScoreModel::hyperscore()),
+            )
+        };
+        let b2 = fragments
+            .iter()
+            .find(|f| f.label() == "b2")
+            .unwrap()
+            .clone();
+        let y3 = fragments
+            .iter()
+            .find(|f| f.label() == "y3")
+            .unwrap()
+            .clone();
+        let high = score(&fragments);
+        let low = score(&[b2, y3]);
+        assert!(high.0 > low.0, "{high:?} {low:?}");
+        assert!(high.1 > low.1, "{high:?} {low:?}");
+        assert!((low.0 - 2.0 / 12.0).abs() < 1e-9);
+        // One b and one y ion with a summed intensity of 2
+        assert!((low.1 - 2.0_f64.ln()).abs() < 1e-9);
+        // Only noise peaks
+        assert_eq!(score(&[]), (0.0, 0.0));
+    }
+}
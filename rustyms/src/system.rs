@@ -1,5 +1,10 @@
 //! The measurement system used in this crate.
 //! A redefinition of the important SI units for them to be stored in a more sensible base unit for MS purposes.
+//!
+//! Charge states (eg the maximal charge of fragments or the charge of a fragment) are always
+//! stored as [`usize::Charge`], as a fractional number of charges is not possible. The default
+//! [`Charge`] uses f64 and is only meant for arithmetic, for example to calculate m/z values. Use
+//! [`usize::Charge::to_float`] to convert a charge state for use in these calculations.
 
 #![allow(clippy::non_canonical_clone_impl)]
 #![allow(clippy::ignored_unit_patterns)]
@@ -149,6 +154,27 @@ pub mod f64 {
     }
 }
 
+/// Helper functions to annotate a plain number with its unit, these are also available at the
+/// crate root
+pub mod helpers {
+    use super::{Charge, MassOverCharge};
+
+    #[allow(unused_imports)]
+    pub use super::f64::da;
+
+    /// Annotate the given number as being in m/z
+    #[allow(dead_code)]
+    pub fn mz(v: f64) -> MassOverCharge {
+        MassOverCharge::new::<super::mass_over_charge::mz>(v)
+    }
+
+    /// Annotate the given number as being in atomic units of charge
+    #[allow(dead_code)]
+    pub fn charge(v: f64) -> Charge {
+        Charge::new::<super::charge::e>(v)
+    }
+}
+
 /// All quantities with usize as underlying type
 #[allow(unused_imports)]
 pub mod usize {
@@ -181,17 +207,37 @@ pub mod isize {
     pub use super::time::s;
 }
 
+impl usize::Charge {
+    /// Convert this charge state into a float charge to use it in calculations, for example to
+    /// divide a mass by this charge to get the m/z.
+    pub fn to_float(self) -> Charge {
+        Charge::new::<charge::e>(self.value as f64)
+    }
+}
+
 impl MassOverCharge {
     /// Absolute ppm error between this number and the given other
     pub fn ppm(self, b: Self) -> Ratio {
         Ratio::new::<crate::system::ratio::ppm>(((self - b).abs() / self.abs()).value * 1e6)
     }
+
+    /// Signed ppm error between this number and the given other, positive if this number is
+    /// higher than the other. The absolute value is identical to [`Self::ppm`].
+    pub fn signed_ppm(self, b: Self) -> Ratio {
+        Ratio::new::<crate::system::ratio::ppm>(((self - b) / self.abs()).value * 1e6)
+    }
 }
 impl Mass {
     /// Absolute ppm error between this number and the given other
     pub fn ppm(self, b: Self) -> Ratio {
         Ratio::new::<crate::system::ratio::ppm>(((self - b).abs() / self.abs()).value * 1e6)
     }
+
+    /// Signed ppm error between this number and the given other, positive if this number is
+    /// higher than the other. The absolute value is identical to [`Self::ppm`].
+    pub fn signed_ppm(self, b: Self) -> Ratio {
+        Ratio::new::<crate::system::ratio::ppm>(((self - b) / self.abs()).value * 1e6)
+    }
 }
 
 /// A wrapper around [`Ratio`] which implements Eq/Ord/Hash to help in auto deriving these on other structs.
@@ -328,3 +374,19 @@ impl std::hash::Hash for OrderedMass {
         helper_functions::f64_bits(self.0.value).hash(state);
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::missing_panics_doc)]
+mod tests {
+    use super::helpers::{charge, da, mz};
+    use super::*;
+
+    #[test]
+    fn unit_helpers() {
+        assert_eq!(mz(20.0), MassOverCharge::new::<mass_over_charge::mz>(20.0));
+        assert_eq!(da(18.0), Mass::new::<mass::dalton>(18.0));
+        assert_eq!(charge(2.0), Charge::new::<charge::e>(2.0));
+        assert!((da(18.0) / charge(2.0) - mz(9.0)).value.abs() < f64::EPSILON);
+        assert_eq!(usize::Charge::new::<charge::e>(2).to_float(), charge(2.0));
+    }
+}
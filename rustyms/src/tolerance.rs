@@ -42,7 +42,9 @@ where
         + std::ops::Add<T, Output = T>
         + Copy,
 {
-    /// Find the bounds around a given value for this tolerance
+    /// Find the bounds around a given value for this tolerance. The bounds are inclusive and match
+    /// [`WithinTolerance::within`] with the given value as first argument, so these can be used
+    /// for range queries on sorted data.
     pub fn bounds(&self, value: impl Into<T>) -> (T, T) {
         let value = value.into();
         match self {
@@ -57,6 +59,19 @@ where
     }
 }
 
+/// A bare ratio (for example a ppm value) is interpreted as a relative tolerance
+impl<T> From<Ratio> for Tolerance<T> {
+    fn from(value: Ratio) -> Self {
+        Self::new_relative(value)
+    }
+}
+
+impl<T> From<OrderedRatio> for Tolerance<T> {
+    fn from(value: OrderedRatio) -> Self {
+        Self::Relative(value)
+    }
+}
+
 impl<T: Display> Display for Tolerance<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -170,3 +185,45 @@ impl WithinTolerance<Mass, Multi<Mass>> for Tolerance<Mass> {
         b.iter().any(|b| self.within(a, b))
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::missing_panics_doc)]
+mod tests {
+    use super::*;
+    use crate::Model;
+
+    #[test]
+    fn ppm_into_tolerance() {
+        let model = Model::all().tolerance(Ratio::new::<crate::system::ratio::ppm>(20.0));
+        assert_eq!(model.tolerance, Tolerance::new_ppm(20.0));
+        let mz = MassOverCharge::new::<crate::system::mass_over_charge::mz>(1000.0);
+        let close = MassOverCharge::new::<crate::system::mass_over_charge::mz>(1000.015);
+        let far = MassOverCharge::new::<crate::system::mass_over_charge::mz>(1000.025);
+        assert!(model.tolerance.within(&mz, &close));
+        assert!(!model.tolerance.within(&mz, &far));
+        let absolute = Model::all().tolerance(Tolerance::new_absolute(MassOverCharge::new::<
+            crate::system::mass_over_charge::mz,
+        >(0.02)));
+        assert!(absolute.tolerance.within(&mz, &close));
+        assert!(!absolute.tolerance.within(&mz, &far));
+    }
+
+    #[test]
+    fn bounds_edges() {
+        let center = da(1000.0);
+        for (tolerance, low, high) in [
+            (Tolerance::<Mass>::new_ppm(10.0), da(999.99), da(1000.01)),
+            (Tolerance::new_absolute(da(0.5)), da(999.5), da(1000.5)),
+        ] {
+            let bounds: [Mass; 2] = tolerance.bounds(center).into();
+            assert!((bounds[0] - low).value.abs() < 1e-9, "{tolerance}");
+            assert!((bounds[1] - high).value.abs() < 1e-9, "{tolerance}");
+            assert!(tolerance.within(&center, &center));
+            for edge in bounds {
+                let offset = (edge - center) * 1e-6;
+                assert!(tolerance.within(&center, &(edge - offset)), "{tolerance}");
+                assert!(!tolerance.within(&center, &(edge + offset)), "{tolerance}");
+            }
+        }
+    }
+}